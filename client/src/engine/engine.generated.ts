@@ -0,0 +1,536 @@
+// Generated from the Rust model by `cargo run --bin gen_ts_types` in engine/. Do not edit;
+// change the Rust types and regenerate.
+
+export type RoomId = number;
+
+export type UnitId = number;
+
+export type TrapId = number;
+
+/**
+ * Sequential identifier of an event within a single simulation's log.
+ *
+ * Ids start at 0 and match the event's index in [`crate::SimulationResult::events`].
+ */
+export type EventId = number;
+
+/**
+ * A signed decimal number stored as thousandths in an `i64`. Arithmetic saturates.
+ */
+export type Fixed = number;
+
+/**
+ * Top-level dungeon description used for simulations.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "rooms": [
+ *     {
+ *       "id": 1,
+ *       "traps": [],
+ *       "monsters": [],
+ *       "tags": ["core"]
+ *     }
+ *   ],
+ *   "edges": [[1, 2], [2, 3]],
+ *   "core_room_id": 1,
+ *   "core_hp": 250,
+ *   "modifiers": ["relic_furnace_core"]
+ * }
+ * ```
+ */
+export type DungeonState = { rooms: Array<RoomState>, edges: Array<[RoomId, RoomId]>, core_room_id: RoomId, core_hp: number, 
+/**
+ * Relic ids drafted as global modifiers, in pick order.
+ */
+modifiers?: Array<string>, };
+
+/**
+ * State for an individual room, including its occupants and tags.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "id": 1,
+ *   "traps": [],
+ *   "monsters": [],
+ *   "tags": ["spawn", "safe"]
+ * }
+ * ```
+ */
+export type RoomState = { id: RoomId, traps: Array<TrapInstance>, monsters: Array<UnitInstance>, tags: Array<string>, 
+/**
+ * Content pack id this room was instantiated from, if any.
+ */
+template_id?: string | null, };
+
+/**
+ * A placed trap instance with runtime state.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "id": 3,
+ *   "trigger_type": "on_enter",
+ *   "cooldown_ticks": 12,
+ *   "cooldown_remaining": 0,
+ *   "max_charges": 4,
+ *   "charges_used": 1,
+ *   "damage": 10,
+ *   "status_on_hit": null,
+ *   "tags": ["aoe"],
+ *   "template_id": "trap_fire_glyph"
+ * }
+ * ```
+ */
+export type TrapInstance = { id: TrapId, trigger_type: TrapTriggerType, cooldown_ticks: number, cooldown_remaining: number, max_charges: number | null, charges_used: number, damage: number, status_on_hit: StatusInstance | null, tags: Array<string>, 
+/**
+ * Content pack id this trap was instantiated from, if any.
+ */
+template_id?: string | null, };
+
+/**
+ * The condition under which a trap triggers.
+ */
+export type TrapTriggerType = "on_enter" | "on_exit" | "timed";
+
+/**
+ * Fully instantiated unit with runtime state.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "id": 10,
+ *   "faction": "monster",
+ *   "stats": {
+ *     "max_hp": 120,
+ *     "armor": 5,
+ *     "move_speed": 1.1,
+ *     "attack_damage": 12,
+ *     "attack_interval_ticks": 30,
+ *     "attack_range": 1
+ *   },
+ *   "hp": 120,
+ *   "room_id": 2,
+ *   "status_effects": [],
+ *   "ai_behavior": "aggressive",
+ *   "attack_cooldown": 0,
+ *   "template_id": "monster_ember_guard"
+ * }
+ * ```
+ */
+export type UnitInstance = { id: UnitId, faction: Faction, stats: UnitStats, hp: number, room_id: RoomId, status_effects: Array<StatusInstance>, ai_behavior: AiBehavior, attack_cooldown: number, 
+/**
+ * Content pack id this unit was instantiated from, if any.
+ */
+template_id?: string | null, };
+
+/**
+ * Immutable stat block for a unit instance.
+ */
+export type UnitStats = { max_hp: number, armor: number, 
+/**
+ * Rooms per tick; see [`crate::fixed`].
+ */
+move_speed: Fixed, attack_damage: number, attack_interval_ticks: number, attack_range: number, };
+
+/**
+ * Team allegiance for a unit.
+ *
+ * Serialized values are lower snake case to keep JSON stable (`"hero"`, `"monster"`).
+ */
+export type Faction = "hero" | "monster";
+
+/**
+ * Basic AI behavior for a unit.
+ */
+export type AiBehavior = "passive" | "aggressive" | "defensive";
+
+/**
+ * A concrete status effect applied to a unit.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "kind": "poison",
+ *   "remaining_ticks": 12,
+ *   "magnitude": 3.5
+ * }
+ * ```
+ */
+export type StatusInstance = { kind: StatusKind, remaining_ticks: number, magnitude: Fixed, };
+
+/**
+ * Types of status effects that can be applied to a unit.
+ */
+export type StatusKind = "poison" | "burn" | "slow" | "stun" | "buff_damage" | "buff_armor";
+
+/**
+ * Configuration for a wave of invading heroes.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "id": "wave-1",
+ *   "entries": [
+ *     {
+ *       "hero_template_id": "ember_guard",
+ *       "count": 3,
+ *       "spawn_room_id": 2,
+ *       "delay_ticks": 0
+ *     }
+ *   ],
+ *   "modifiers": ["enraged"]
+ * }
+ * ```
+ */
+export type WaveConfig = { id: string, entries: Array<HeroSpawn>, modifiers: Array<string>, };
+
+/**
+ * Defines a hero spawn entry in a wave.
+ */
+export type HeroSpawn = { hero_template_id: string, count: number, spawn_room_id: RoomId, delay_ticks: number, };
+
+/**
+ * Tunable simulation parameters, echoed back in [`crate::SimulationResult`].
+ *
+ * Every field has a default, so a partial JSON object only overrides what it names.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "limits": { "max_ticks": 20000, "max_heroes_per_wave": 100 },
+ *   "min_damage": 1,
+ *   "tick_duration_ms": 100,
+ *   "rules": { "cross_room_attacks": false },
+ *   "checksum_interval": 10
+ * }
+ * ```
+ */
+export type SimulationConfig = { limits: SimulationLimits, 
+/**
+ * Lowest damage a hit can deal after armor.
+ */
+min_damage: number, 
+/**
+ * Real-time length of one tick, for clients converting ticks to wall-clock time.
+ */
+tick_duration_ms: number, rules: RuleToggles, 
+/**
+ * Record a state checksum every this many ticks, plus one for the final tick, in
+ * [`crate::SimulationResult::checksums`]. `0` records none.
+ */
+checksum_interval: number, };
+
+/**
+ * Hard caps enforced before and during a simulation. Exceeding any of them fails fast
+ * with a [`crate::error::SimError`] instead of running unbounded work.
+ */
+export type SimulationLimits = { 
+/**
+ * Maximum number of live units (monsters plus heroes) at any time.
+ */
+max_units: number, 
+/**
+ * Maximum `max_ticks` a caller may request.
+ */
+max_ticks: number, 
+/**
+ * Maximum number of events in a single result.
+ */
+max_events: number, 
+/**
+ * Maximum total hero count across all spawn entries of a wave.
+ */
+max_heroes_per_wave: number, 
+/**
+ * Maximum monsters plus traps placed in a single room.
+ */
+max_entities_per_room: number, max_rooms: number, max_edges: number, 
+/**
+ * Maximum traps across the whole dungeon.
+ */
+max_traps: number, };
+
+/**
+ * Switches for movement and targeting rules. Defaults reproduce the original rules.
+ */
+export type RuleToggles = { 
+/**
+ * Units may hit targets in other rooms up to their `attack_range`; when off, units
+ * only attack inside their own room.
+ */
+cross_room_attacks: boolean, 
+/**
+ * Heroes attack monsters in range before the core; when off, a hero with the core in
+ * range always attacks the core.
+ */
+heroes_target_monsters_first: boolean, 
+/**
+ * Any positive move speed moves a hero at least one room per tick; when off, speeds
+ * below 1.0 leave the hero in place.
+ */
+min_one_step_per_tick: boolean, };
+
+export type SimulationResult = { outcome: SimulationOutcome, final_dungeon: DungeonState, final_heroes: Array<UnitInstance>, stats: SimulationStats, events: Array<SimulationEvent>, config: SimulationConfig, score: WaveScore, 
+/**
+ * State checksums taken every `config.checksum_interval` ticks; empty when disabled.
+ */
+checksums?: Array<TickChecksum>, engine_version: string, };
+
+export type SimulationOutcome = "DungeonWin" | "HeroesWin" | "Timeout" | { "Stalemate": StalemateReason };
+
+/**
+ * Why a wave ended in [`SimulationOutcome::Stalemate`]. In every case every spawn has
+ * happened, no unit carries a status effect and nothing can deal damage any more.
+ */
+export type StalemateReason = "CoreUnreachable" | "HeroesImmobile" | "NoDamagePossible";
+
+export type SimulationStats = { ticks_run: number, heroes_spawned: number, heroes_killed: number, monsters_killed: number, total_damage_to_core: number, };
+
+/**
+ * A single entry in the simulation's event log.
+ *
+ * Every event carries a sequential `id` and, when it was set off by an earlier
+ * event, the `caused_by` id of that event. Following `caused_by` links walks a
+ * trigger chain such as trap -> damage -> status -> death without matching ticks.
+ */
+export type SimulationEvent = { "UnitSpawned": { tick: number, id: EventId, caused_by: EventId | null, unit_id: UnitId, room_id: RoomId, hp: number, template_id: string | null, } } | { "UnitMoved": { tick: number, id: EventId, caused_by: EventId | null, unit_id: UnitId, from: RoomId, to: RoomId, } } | { "TrapTriggered": { tick: number, id: EventId, caused_by: EventId | null, trap_id: TrapId, room_id: RoomId, } } | { "DamageApplied": { tick: number, id: EventId, caused_by: EventId | null, source: DamageSource, target: UnitId, amount: number, hp_before: number, hp_after: number, } } | { "StatusApplied": { tick: number, id: EventId, caused_by: EventId | null, target: UnitId, kind: StatusKind, magnitude: Fixed, duration_ticks: number, } } | { "UnitDied": { tick: number, id: EventId, caused_by: EventId | null, unit_id: UnitId, 
+/**
+ * Unit that landed the killing blow, if it was a unit.
+ */
+killer: UnitId | null, cause: DamageSource, } } | { "CoreDamaged": { tick: number, id: EventId, caused_by: EventId | null, amount: number, core_hp_after: number, } };
+
+/**
+ * What dealt a point of damage.
+ *
+ * Serialized with the same external tagging as [`SimulationEvent`], e.g.
+ * `{"Unit": 3}`, `{"Trap": 0}`, `{"Status": "poison"}` or `"Environment"`.
+ */
+export type DamageSource = { "Unit": UnitId } | { "Trap": TrapId } | { "Status": StatusKind } | "Environment";
+
+export type TickChecksum = { 
+/**
+ * Ticks completed when the checksum was taken.
+ */
+tick: number, checksum: StateChecksum, };
+
+/**
+ * FNV-1a hash of the simulation state after a tick. Serialized as 16 hex digits so
+ * JavaScript clients do not lose precision.
+ */
+export type StateChecksum = string;
+
+/**
+ * Score of one wave, component by component.
+ */
+export type WaveScore = { version: number, tier: number, hero_kills: number, core_hp: number, efficiency: number, multiplier_permille: number, 
+/**
+ * Points added (or removed) by the relic multiplier.
+ */
+relic_bonus: number, total: number, };
+
+/**
+ * Snapshot of a wave in progress, as returned by [`WaveSimulation::view`].
+ */
+export type WaveView = { 
+/**
+ * Ticks completed so far.
+ */
+tick: number, max_ticks: number, dungeon: DungeonState, heroes: Array<UnitInstance>, stats: SimulationStats, 
+/**
+ * Events logged so far; the cursor for the next [`WaveSimulation::events_since`].
+ */
+event_count: number, 
+/**
+ * Set once the wave has finished.
+ */
+outcome?: SimulationOutcome | null, };
+
+/**
+ * Rules for a whole run: per-wave simulation settings plus the between-wave rules.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "max_ticks_per_wave": 2000,
+ *   "waves_per_tier": 5,
+ *   "between_waves": { "respawn_monsters": true, "core_repair": 10 }
+ * }
+ * ```
+ */
+export type RunRules = { config: SimulationConfig, max_ticks_per_wave: number, 
+/**
+ * Waves per tier; waves `0..waves_per_tier` are tier 1, the next block tier 2, and so on.
+ */
+waves_per_tier: number, between_waves: BetweenWaveRules, };
+
+/**
+ * What happens to the dungeon between two waves of a run. Monster attack cooldowns are
+ * always reset so every wave starts with the defenders ready.
+ */
+export type BetweenWaveRules = { 
+/**
+ * Reset every trap's charges and cooldown.
+ */
+recharge_traps: boolean, 
+/**
+ * Bring monsters killed during the wave back at full HP in the room they started it in.
+ */
+respawn_monsters: boolean, 
+/**
+ * Heal surviving monsters by this percentage of their max HP.
+ */
+monster_heal_percent: number, 
+/**
+ * Core HP restored, capped at the core HP the run started with.
+ */
+core_repair: number, 
+/**
+ * Heroes alive at the end of a wave stay in the dungeon for the next one.
+ */
+carry_over_heroes: boolean, };
+
+export type RunResult = { 
+/**
+ * One result per simulated wave, in order. Waves after the core falls are not run.
+ */
+waves: Array<SimulationResult>, summary: RunSummary, 
+/**
+ * The dungeon as the last simulated wave left it.
+ */
+final_dungeon: DungeonState, engine_version: string, };
+
+export type RunSummary = { 
+/**
+ * `DungeonWin` if the core survived every wave, `HeroesWin` if a wave destroyed it.
+ */
+outcome: SimulationOutcome, 
+/**
+ * Waves that ended with the core still standing.
+ */
+waves_cleared: number, core_hp_remaining: number, ticks_run: number, heroes_spawned: number, heroes_killed: number, monsters_killed: number, total_damage_to_core: number, score: RunScore, };
+
+/**
+ * Score of a run: each component summed over its waves.
+ */
+export type RunScore = { version: number, hero_kills: number, core_hp: number, efficiency: number, relic_bonus: number, total: number, };
+
+/**
+ * Draft rarity of a content entry; see [`crate::draft::RarityWeights`].
+ */
+export type Rarity = "common" | "uncommon" | "rare" | "epic";
+
+/**
+ * Knobs for offer generation.
+ */
+export type DraftRules = { 
+/**
+ * Options per offer; fewer are offered when the eligible pool is smaller.
+ */
+options_per_offer: number, rarity_weights: RarityWeights, };
+
+/**
+ * Relative draft weight per rarity. A weight of 0 removes that rarity from offers.
+ */
+export type RarityWeights = { common: number, uncommon: number, rare: number, epic: number, };
+
+export type DraftOffer = { draft_index: number, tier: number, options: Array<DraftOption>, };
+
+/**
+ * One entry of an offer, naming content by its content pack id.
+ */
+export type DraftOption = { "add_room": { template_id: string, rarity: Rarity, } } | { "add_trap": { template_id: string, rarity: Rarity, } } | { "add_monster": { template_id: string, rarity: Rarity, } } | { "global_modifier": { relic_id: string, rarity: Rarity, } };
+
+/**
+ * The player's choice from a [`DraftOffer`].
+ *
+ * # JSON example
+ * ```json
+ * { "option": 1, "target_room": 4 }
+ * ```
+ */
+export type DraftPick = { 
+/**
+ * Index into [`DraftOffer::options`].
+ */
+option: number, 
+/**
+ * Room receiving a trap or monster, or the existing room a new room connects to.
+ * Ignored for global modifiers.
+ */
+target_room?: RoomId | null, };
+
+/**
+ * A completed run as submitted by a client (`POST /v1/run/submit`).
+ *
+ * `seed` is a full `u64`, so it travels as a decimal string that JavaScript cannot round;
+ * an integer is still accepted if it is at most 2^53 - 1.
+ *
+ * # JSON example
+ * ```json
+ * {
+ *   "content_version": "1.0.0",
+ *   "engine_version": "0.1.0",
+ *   "seed": "123456789",
+ *   "challenge_id": null,
+ *   "score": 850,
+ *   "outcome": "DungeonWin",
+ *   "run_summary": {
+ *     "waves_cleared": 2,
+ *     "core_hp_remaining": 42,
+ *     "draft_choices": [
+ *       { "tier": 1, "option": 0, "target_room": 1, "picked_id": "trap_fire_glyph" },
+ *       { "tier": 1, "option": 2, "picked_id": "relic_furnace_core" }
+ *     ]
+ *   }
+ * }
+ * ```
+ */
+export type RunSubmission = { content_version: string, engine_version: string, seed: string, challenge_id?: string | null, 
+/**
+ * Run score as computed by the client, compared exactly with the replay.
+ */
+score: number, outcome: SimulationOutcome, run_summary: SubmittedRunSummary, };
+
+export type SubmittedRunSummary = { waves_cleared: number, core_hp_remaining: number, 
+/**
+ * One choice per draft, in order; draft `i` precedes wave `i`.
+ */
+draft_choices: Array<DraftChoice>, };
+
+export type DraftChoice = { 
+/**
+ * Tier the client believed the draft was at.
+ */
+tier: number, 
+/**
+ * Content id of the picked option, checked against the regenerated offer.
+ */
+picked_id: string, 
+/**
+ * Index into [`DraftOffer::options`].
+ */
+option: number, 
+/**
+ * Room receiving a trap or monster, or the existing room a new room connects to.
+ * Ignored for global modifiers.
+ */
+target_room?: RoomId | null, };
+
+export type VerificationReport = { 
+/**
+ * True when there are no mismatches.
+ */
+valid: boolean, mismatches: Array<Mismatch>, 
+/**
+ * Summary of the replayed run, absent when the replay could not finish.
+ */
+recomputed: RunSummary | null, };
+
+/**
+ * One way a submission disagrees with the replay. The `reason` tag is part of the
+ * backend contract.
+ */
+export type Mismatch = { "reason": "no_run_template" } | { "reason": "content_version", expected: string, claimed: string, } | { "reason": "engine_version", expected: string, claimed: string, } | { "reason": "missing_draft", draft_index: number, } | { "reason": "extra_drafts", expected: number, claimed: number, } | { "reason": "tier", draft_index: number, expected: number, claimed: number, } | { "reason": "picked_id", draft_index: number, expected: string | null, claimed: string, } | { "reason": "illegal_pick", draft_index: number, message: string, } | { "reason": "simulation_failed", wave_index: number, message: string, } | { "reason": "outcome", expected: SimulationOutcome, claimed: SimulationOutcome, } | { "reason": "score", expected: number, claimed: number, score_version: number, } | { "reason": "waves_cleared", expected: number, claimed: number, } | { "reason": "core_hp_remaining", expected: number, claimed: number, };
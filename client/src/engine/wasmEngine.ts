@@ -1,5 +1,24 @@
-import type { SimulationResult, DungeonState, WaveConfig } from './types';
-import initWasm, { simulate_wave_wasm, engine_version_wasm } from '../../../engine/wasm/pkg/engine_wasm';
+import type {
+  SimulationResult,
+  DungeonState,
+  WaveConfig,
+  SimulationConfig,
+  RunResult,
+  RunRules,
+  DraftOffer,
+  DraftPick,
+  DraftRules,
+  SimulationEvent,
+  WaveView,
+} from './types';
+import initWasm, {
+  simulate_wave_wasm,
+  simulate_run_wasm,
+  draft_offer_wasm,
+  apply_draft_pick_wasm,
+  engine_version_wasm,
+  Simulation,
+} from '../../../engine/wasm/pkg/engine_wasm';
 
 let initialized = false;
 let initPromise: Promise<void> | null = null;
@@ -35,12 +54,84 @@ export async function simulateWave(
   wave: WaveConfig,
   seed: bigint,
   maxTicks: number,
+  config?: Partial<SimulationConfig>,
 ): Promise<SimulationResult> {
   await ensureInitialized();
-  const result = simulate_wave_wasm(dungeon, wave, seed, maxTicks);
+  const result = simulate_wave_wasm(dungeon, wave, seed, maxTicks, config);
   const plain = JSON.parse(JSON.stringify(result)) as SimulationResult;
   if (!plain.engine_version) {
     throw new Error('engine_version missing from SimulationResult');
   }
   return plain;
 }
+
+/**
+ * A wave the caller advances itself, e.g. a few ticks per `requestAnimationFrame`, to
+ * render it live. Call `finish()` (or `free()`) when done to release the wasm memory.
+ */
+export interface SteppedWave {
+  /** Run up to `ticks` ticks; returns how many ran (0 once finished). */
+  step(ticks: number): number;
+  /** Events logged since the previous call. */
+  takeEvents(): SimulationEvent[];
+  view(): WaveView;
+  isFinished(): boolean;
+  finish(): SimulationResult;
+  free(): void;
+}
+
+export async function startWave(
+  dungeon: DungeonState,
+  wave: WaveConfig,
+  seed: bigint,
+  maxTicks: number,
+  config?: Partial<SimulationConfig>,
+): Promise<SteppedWave> {
+  await ensureInitialized();
+  const simulation = new Simulation(dungeon, wave, seed, maxTicks, config);
+  let cursor = 0;
+  return {
+    step: (ticks) => simulation.step(ticks),
+    takeEvents: () => {
+      const events = simulation.events_since(cursor) as SimulationEvent[];
+      cursor += events.length;
+      return events;
+    },
+    view: () => simulation.state_view() as WaveView,
+    isFinished: () => simulation.is_finished(),
+    finish: () => JSON.parse(JSON.stringify(simulation.finish())) as SimulationResult,
+    free: () => simulation.free(),
+  };
+}
+
+export async function simulateRun(
+  dungeon: DungeonState,
+  waves: WaveConfig[],
+  runSeed: bigint,
+  rules?: Partial<RunRules>,
+): Promise<RunResult> {
+  await ensureInitialized();
+  const result = simulate_run_wasm(dungeon, waves, runSeed, rules);
+  return JSON.parse(JSON.stringify(result)) as RunResult;
+}
+
+export async function draftOffer(
+  content: unknown,
+  runSeed: bigint,
+  draftIndex: number,
+  tier: number,
+  rules?: Partial<DraftRules>,
+): Promise<DraftOffer> {
+  await ensureInitialized();
+  return draft_offer_wasm(content, runSeed, draftIndex, tier, rules) as DraftOffer;
+}
+
+export async function applyDraftPick(
+  dungeon: DungeonState,
+  content: unknown,
+  offer: DraftOffer,
+  pick: DraftPick,
+): Promise<DungeonState> {
+  await ensureInitialized();
+  return apply_draft_pick_wasm(dungeon, content, offer, pick) as DungeonState;
+}
@@ -39,6 +39,36 @@ const baseWave: StoredWaveReplay = {
       total_damage_to_core: 0,
     },
     events: [],
+    config: {
+      limits: {
+        max_units: 512,
+        max_ticks: 60_000,
+        max_events: 10_000,
+        max_heroes_per_wave: 512,
+        max_entities_per_room: 512,
+        max_rooms: 1_024,
+        max_edges: 4_096,
+        max_traps: 2_048,
+      },
+      min_damage: 1,
+      tick_duration_ms: 100,
+      rules: {
+        cross_room_attacks: true,
+        heroes_target_monsters_first: true,
+        min_one_step_per_tick: true,
+      },
+      checksum_interval: 0,
+    },
+    score: {
+      version: 1,
+      tier: 1,
+      hero_kills: 0,
+      core_hp: 0,
+      efficiency: 0,
+      multiplier_permille: 1000,
+      relic_bonus: 0,
+      total: 0,
+    },
   },
 };
 
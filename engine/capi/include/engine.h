@@ -0,0 +1,112 @@
+/* C API for the dungeon simulation engine. See src/lib.rs for the contract. */
+
+#ifndef ENGINE_CAPI_H
+#define ENGINE_CAPI_H
+
+/* Generated by build.rs from src/lib.rs with cbindgen. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Result of every call. Values are stable; new codes are only ever appended.
+typedef enum EngineStatus {
+  ENGINE_STATUS_OK = 0,
+  // A required pointer argument was null.
+  ENGINE_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not valid UTF-8.
+  ENGINE_STATUS_INVALID_UTF8 = 2,
+  // A JSON argument did not parse into the expected shape.
+  ENGINE_STATUS_INVALID_JSON = 3,
+  // `engine_verify` on a handle without a content pack.
+  ENGINE_STATUS_NO_CONTENT_PACK = 4,
+  // The engine panicked. The handle stays usable; please report the inputs.
+  ENGINE_STATUS_INTERNAL = 5,
+  // Engine errors: one code per `SimError` variant, in declaration order.
+  ENGINE_STATUS_ENTITY_LIMIT = 100,
+  ENGINE_STATUS_EVENT_LIMIT = 101,
+  ENGINE_STATUS_TICK_LIMIT = 102,
+  ENGINE_STATUS_HERO_LIMIT = 103,
+  ENGINE_STATUS_ROOM_LIMIT = 104,
+  ENGINE_STATUS_EDGE_LIMIT = 105,
+  ENGINE_STATUS_TRAP_LIMIT = 106,
+  ENGINE_STATUS_INVALID_DRAFT_PICK = 107,
+  // The dungeon or wave failed validation; `out_json` holds the `ValidationReport`.
+  ENGINE_STATUS_INVALID_INPUT = 108,
+} EngineStatus;
+
+// Opaque simulation handle: an optional content pack for verification plus the last
+// error message.
+typedef struct EngineSimulator EngineSimulator;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// The engine version the library was built from, e.g. `"0.1.0"`. Static; do not free.
+const char *engine_version(void);
+
+// Create a handle without a content pack. Release it with `engine_simulator_destroy`.
+struct EngineSimulator *engine_simulator_create(void);
+
+// Destroy a handle. Null is ignored.
+//
+// # Safety
+//
+// `sim` must be null or a handle from `engine_simulator_create` not yet destroyed.
+void engine_simulator_destroy(struct EngineSimulator *sim);
+
+// Parse `content_json` as a `ContentPack` and keep it for `engine_verify`, replacing any
+// previous pack.
+//
+// # Safety
+//
+// `sim` must be a live handle and `content_json` a NUL-terminated string.
+enum EngineStatus engine_simulator_set_content(struct EngineSimulator *sim,
+                                               const char *content_json);
+
+// Simulate one wave. `request_json` is `{"dungeon", "wave", "seed", "max_ticks"?,
+// "config"?, "summary_only"?}`; `*out_json` receives the `SimulationResult` (or
+// `WaveSummary` with `summary_only`).
+//
+// # Safety
+//
+// `sim` must be a live handle, `request_json` a NUL-terminated string and `out_json` a
+// writable pointer.
+enum EngineStatus engine_simulate(struct EngineSimulator *sim,
+                                  const char *request_json,
+                                  char **out_json);
+
+// Replay a run submission against the handle's content pack. `request_json` is
+// `{"submission"}`; `*out_json` receives the `VerificationReport`. A rejected run is
+// still `ENGINE_STATUS_OK`, with `"valid": false` in the report.
+//
+// # Safety
+//
+// As for `engine_simulate`.
+enum EngineStatus engine_verify(struct EngineSimulator *sim,
+                                const char *request_json,
+                                char **out_json);
+
+// The message for the handle's last failed call, or `""`. Owned by the handle and valid
+// until its next call; do not free.
+//
+// # Safety
+//
+// `sim` must be a live handle.
+const char *engine_last_error(const struct EngineSimulator *sim);
+
+// Free a string returned through `out_json`. Null is ignored.
+//
+// # Safety
+//
+// `json` must be null or a string from this library not yet freed.
+void engine_string_free(char *json);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ENGINE_CAPI_H */
@@ -0,0 +1,323 @@
+//! C ABI for the simulation engine (`libengine_capi`), for consumers that cannot link Rust
+//! or run wasm, such as the Go verification backend.
+//!
+//! Requests and replies are NUL-terminated UTF-8 JSON with the same shapes as the
+//! `sim_server` routes. Every call returns an [`EngineStatus`]; on failure
+//! `engine_last_error` describes it. Strings written to `out_json` belong to the caller
+//! and are released with `engine_string_free`. A handle may be used from any thread but
+//! not from two at once. `include/engine.h` is generated from this file by `build.rs`.
+
+use std::any::Any;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+use std::sync::OnceLock;
+
+use engine::error::SimError;
+use engine::model::ContentPack;
+use engine::service::{SimulateParams, VerifyParams};
+use engine::{DungeonState, ENGINE_VERSION};
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Result of every call. Values are stable; new codes are only ever appended.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EngineStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// A JSON argument did not parse into the expected shape.
+    InvalidJson = 3,
+    /// `engine_verify` on a handle without a content pack.
+    NoContentPack = 4,
+    /// The engine panicked. The handle stays usable; please report the inputs.
+    Internal = 5,
+    /// Engine errors: one code per `SimError` variant, in declaration order.
+    EntityLimit = 100,
+    EventLimit = 101,
+    TickLimit = 102,
+    HeroLimit = 103,
+    RoomLimit = 104,
+    EdgeLimit = 105,
+    TrapLimit = 106,
+    InvalidDraftPick = 107,
+    /// The dungeon or wave failed validation; `out_json` holds the `ValidationReport`.
+    InvalidInput = 108,
+}
+
+impl From<&SimError> for EngineStatus {
+    fn from(err: &SimError) -> Self {
+        match err {
+            SimError::EntityLimit => EngineStatus::EntityLimit,
+            SimError::EventLimit => EngineStatus::EventLimit,
+            SimError::TickLimit => EngineStatus::TickLimit,
+            SimError::HeroLimit => EngineStatus::HeroLimit,
+            SimError::RoomLimit => EngineStatus::RoomLimit,
+            SimError::EdgeLimit => EngineStatus::EdgeLimit,
+            SimError::TrapLimit => EngineStatus::TrapLimit,
+            SimError::InvalidDraftPick(_) => EngineStatus::InvalidDraftPick,
+            SimError::InvalidInput(_) => EngineStatus::InvalidInput,
+        }
+    }
+}
+
+/// Opaque simulation handle: an optional content pack for verification plus the last
+/// error message.
+pub struct EngineSimulator {
+    content: Option<ContentPack>,
+    last_error: CString,
+}
+
+/// A failed call: its status, message, and any JSON detail for `out_json`.
+struct Failure {
+    status: EngineStatus,
+    message: String,
+    detail: Option<String>,
+}
+
+impl Failure {
+    fn new(status: EngineStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            detail: None,
+        }
+    }
+}
+
+impl From<SimError> for Failure {
+    fn from(err: SimError) -> Self {
+        let mut failure = Failure::new(EngineStatus::from(&err), err.to_string());
+        if let SimError::InvalidInput(report) = err {
+            failure.message = "invalid input".into();
+            failure.detail = Some(to_json(&report));
+        }
+        failure
+    }
+}
+
+/// The simulate request: the `sim_server` `POST /simulate` body.
+#[derive(Deserialize)]
+struct SimulateRequest {
+    dungeon: DungeonState,
+    #[serde(flatten)]
+    params: SimulateParams,
+}
+
+/// The engine version the library was built from, e.g. `"0.1.0"`. Static; do not free.
+#[unsafe(no_mangle)]
+pub extern "C" fn engine_version() -> *const c_char {
+    static VERSION: OnceLock<CString> = OnceLock::new();
+    VERSION
+        .get_or_init(|| CString::new(ENGINE_VERSION).expect("version has no NUL"))
+        .as_ptr()
+}
+
+/// Create a handle without a content pack. Release it with `engine_simulator_destroy`.
+#[unsafe(no_mangle)]
+pub extern "C" fn engine_simulator_create() -> *mut EngineSimulator {
+    Box::into_raw(Box::new(EngineSimulator {
+        content: None,
+        last_error: CString::default(),
+    }))
+}
+
+/// Destroy a handle. Null is ignored.
+///
+/// # Safety
+///
+/// `sim` must be null or a handle from `engine_simulator_create` not yet destroyed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_simulator_destroy(sim: *mut EngineSimulator) {
+    if !sim.is_null() {
+        // SAFETY: the caller passes a live handle from `engine_simulator_create`.
+        drop(unsafe { Box::from_raw(sim) });
+    }
+}
+
+/// Parse `content_json` as a `ContentPack` and keep it for `engine_verify`, replacing any
+/// previous pack.
+///
+/// # Safety
+///
+/// `sim` must be a live handle and `content_json` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_simulator_set_content(
+    sim: *mut EngineSimulator,
+    content_json: *const c_char,
+) -> EngineStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        call(sim, ptr::null_mut(), |sim| {
+            sim.content = Some(parse(content_json, "content pack")?);
+            Ok(None)
+        })
+    }
+}
+
+/// Simulate one wave. `request_json` is `{"dungeon", "wave", "seed", "max_ticks"?,
+/// "config"?, "summary_only"?}`; `*out_json` receives the `SimulationResult` (or
+/// `WaveSummary` with `summary_only`).
+///
+/// # Safety
+///
+/// `sim` must be a live handle, `request_json` a NUL-terminated string and `out_json` a
+/// writable pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_simulate(
+    sim: *mut EngineSimulator,
+    request_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> EngineStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        call(sim, out_json, |_| {
+            let request: SimulateRequest = parse(request_json, "simulate request")?;
+            let reply = request.params.run(request.dungeon)?;
+            Ok(Some(to_json(&reply)))
+        })
+    }
+}
+
+/// Replay a run submission against the handle's content pack. `request_json` is
+/// `{"submission"}`; `*out_json` receives the `VerificationReport`. A rejected run is
+/// still `ENGINE_STATUS_OK`, with `"valid": false` in the report.
+///
+/// # Safety
+///
+/// As for `engine_simulate`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_verify(
+    sim: *mut EngineSimulator,
+    request_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> EngineStatus {
+    // SAFETY: forwarded from the caller.
+    unsafe {
+        call(sim, out_json, |sim| {
+            let request: VerifyParams = parse(request_json, "verify request")?;
+            let content = sim
+                .content
+                .as_ref()
+                .ok_or_else(|| Failure::new(EngineStatus::NoContentPack, "no content pack set"))?;
+            Ok(Some(to_json(&request.run(content))))
+        })
+    }
+}
+
+/// The message for the handle's last failed call, or `""`. Owned by the handle and valid
+/// until its next call; do not free.
+///
+/// # Safety
+///
+/// `sim` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_last_error(sim: *const EngineSimulator) -> *const c_char {
+    // SAFETY: the caller passes a live handle.
+    match unsafe { sim.as_ref() } {
+        Some(sim) => sim.last_error.as_ptr(),
+        None => c"null handle".as_ptr(),
+    }
+}
+
+/// Free a string returned through `out_json`. Null is ignored.
+///
+/// # Safety
+///
+/// `json` must be null or a string from this library not yet freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn engine_string_free(json: *mut c_char) {
+    if !json.is_null() {
+        // SAFETY: the string came from `CString::into_raw` in `call`.
+        drop(unsafe { CString::from_raw(json) });
+    }
+}
+
+/// Run `body` on the handle, catching panics, recording the error message and writing
+/// any JSON reply (or failure detail) to `out_json`, which is nulled first.
+unsafe fn call(
+    sim: *mut EngineSimulator,
+    out_json: *mut *mut c_char,
+    body: impl FnOnce(&mut EngineSimulator) -> Result<Option<String>, Failure>,
+) -> EngineStatus {
+    // SAFETY: the caller passes a live handle not in use elsewhere.
+    let Some(sim) = (unsafe { sim.as_mut() }) else {
+        return EngineStatus::NullArgument;
+    };
+    if !out_json.is_null() {
+        // SAFETY: the caller passes a writable pointer.
+        unsafe { *out_json = ptr::null_mut() };
+    }
+    sim.last_error = CString::default();
+
+    let (status, json) = match catch_unwind(AssertUnwindSafe(|| body(sim))) {
+        Ok(Ok(json)) => (EngineStatus::Ok, json),
+        Ok(Err(failure)) => {
+            sim.last_error = c_string(failure.message);
+            (failure.status, failure.detail)
+        }
+        Err(payload) => {
+            sim.last_error = c_string(panic_message(payload.as_ref()));
+            (EngineStatus::Internal, None)
+        }
+    };
+    if let Some(json) = json {
+        if out_json.is_null() {
+            sim.last_error = c_string("out_json is null".into());
+            return EngineStatus::NullArgument;
+        }
+        // SAFETY: checked non-null above; the caller passes a writable pointer.
+        unsafe { *out_json = c_string(json).into_raw() };
+    }
+    status
+}
+
+/// Parse a NUL-terminated JSON argument.
+fn parse<T: for<'de> Deserialize<'de>>(json: *const c_char, what: &str) -> Result<T, Failure> {
+    if json.is_null() {
+        return Err(Failure::new(
+            EngineStatus::NullArgument,
+            format!("{what} is null"),
+        ));
+    }
+    // SAFETY: the caller passes a NUL-terminated string.
+    let json = unsafe { CStr::from_ptr(json) }.to_str().map_err(|err| {
+        Failure::new(
+            EngineStatus::InvalidUtf8,
+            format!("{what} is not UTF-8: {err}"),
+        )
+    })?;
+    serde_json::from_str(json).map_err(|err| {
+        Failure::new(
+            EngineStatus::InvalidJson,
+            format!("failed to parse {what}: {err}"),
+        )
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("replies serialize to JSON")
+}
+
+/// "engine panicked", with the panic message when the payload carries one.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let message = payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str));
+    match message {
+        Some(message) => format!("engine panicked: {message}"),
+        None => "engine panicked".into(),
+    }
+}
+
+/// JSON and messages contain no NUL unless a string field did; replace it rather than fail.
+fn c_string(text: String) -> CString {
+    CString::new(text).unwrap_or_else(|err| {
+        let text = String::from_utf8_lossy(&err.into_vec()).replace('\0', "\u{FFFD}");
+        CString::new(text).expect("NULs were replaced")
+    })
+}
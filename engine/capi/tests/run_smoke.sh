@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+# Build the library, compile tests/smoke.c against the generated header and run it.
+# Usage: tests/run_smoke.sh (CC defaults to cc)
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+cargo build --release
+mkdir -p target/smoke
+"${CC:-cc}" -std=c99 -Wall -Wextra -Werror -Iinclude tests/smoke.c \
+  -Ltarget/release -lengine_capi -o target/smoke/smoke
+LD_LIBRARY_PATH="target/release${LD_LIBRARY_PATH:+:$LD_LIBRARY_PATH}" \
+  DYLD_LIBRARY_PATH="target/release" target/smoke/smoke
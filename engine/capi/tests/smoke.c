@@ -0,0 +1,95 @@
+/* Smoke test for the C API: run with tests/run_smoke.sh. */
+#include <stdio.h>
+#include <string.h>
+
+#include "engine.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                                    \
+  do {                                                                 \
+    if (!(cond)) {                                                     \
+      fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+              #cond);                                                  \
+      failures++;                                                      \
+    }                                                                  \
+  } while (0)
+
+#define DUNGEON                                                              \
+  "\"dungeon\":{\"rooms\":[{\"id\":0,\"traps\":[],\"monsters\":[],\"tags\":[]}]," \
+  "\"edges\":[],\"core_room_id\":0,\"core_hp\":228}"
+#define WAVE(spawn_room)                                                   \
+  "\"wave\":{\"id\":\"smoke\",\"entries\":[{\"hero_template_id\":\"hero-0\"," \
+  "\"count\":3,\"spawn_room_id\":" #spawn_room ",\"delay_ticks\":8}],"        \
+  "\"modifiers\":[]}"
+
+static char *simulate(EngineSimulator *sim, const char *request,
+                      EngineStatus expected) {
+  char *out = NULL;
+  EngineStatus status = engine_simulate(sim, request, &out);
+  if (status != expected) {
+    fprintf(stderr, "engine_simulate: status %d (%s), expected %d\n", status,
+            engine_last_error(sim), expected);
+    failures++;
+  }
+  return out;
+}
+
+int main(void) {
+  CHECK(strlen(engine_version()) > 0);
+
+  EngineSimulator *sim = engine_simulator_create();
+  CHECK(sim != NULL);
+
+  /* A full result, then the same seed again: runs are deterministic. */
+  const char *request = "{" DUNGEON "," WAVE(0) ",\"seed\":7,\"max_ticks\":250}";
+  char *first = simulate(sim, request, ENGINE_STATUS_OK);
+  char *second = simulate(sim, request, ENGINE_STATUS_OK);
+  CHECK(first != NULL && second != NULL && strcmp(first, second) == 0);
+  CHECK(first != NULL && strstr(first, "\"outcome\"") != NULL);
+  CHECK(strcmp(engine_last_error(sim), "") == 0);
+  engine_string_free(first);
+  engine_string_free(second);
+
+  /* Invalid input: the validation report comes back through out_json. */
+  const char *bad_room = "{" DUNGEON "," WAVE(9) ",\"seed\":7}";
+  char *report = simulate(sim, bad_room, ENGINE_STATUS_INVALID_INPUT);
+  CHECK(report != NULL && strstr(report, "\"issues\"") != NULL);
+  CHECK(strcmp(engine_last_error(sim), "invalid input") == 0);
+  engine_string_free(report);
+
+  /* Engine limits map to their own codes. */
+  const char *limit = "{" DUNGEON "," WAVE(0) ",\"seed\":1,\"max_ticks\":4000000000}";
+  CHECK(simulate(sim, limit, ENGINE_STATUS_TICK_LIMIT) == NULL);
+
+  CHECK(simulate(sim, "{not json", ENGINE_STATUS_INVALID_JSON) == NULL);
+  CHECK(strlen(engine_last_error(sim)) > 0);
+  CHECK(engine_simulate(sim, NULL, NULL) == ENGINE_STATUS_NULL_ARGUMENT);
+  CHECK(engine_simulate(NULL, request, NULL) == ENGINE_STATUS_NULL_ARGUMENT);
+
+  /* Verification needs a content pack; one without a run template rejects. */
+  const char *submission =
+      "{\"submission\":{\"content_version\":\"1.0.0\",\"engine_version\":\"0\","
+      "\"seed\":1,\"score\":0,\"outcome\":\"DungeonWin\",\"run_summary\":"
+      "{\"waves_cleared\":0,\"core_hp_remaining\":0,\"draft_choices\":[]}}}";
+  char *verdict = NULL;
+  CHECK(engine_verify(sim, submission, &verdict) ==
+        ENGINE_STATUS_NO_CONTENT_PACK);
+  CHECK(verdict == NULL);
+  CHECK(engine_simulator_set_content(sim, "{\"version\":\"1.0.0\"}") ==
+        ENGINE_STATUS_OK);
+  CHECK(engine_verify(sim, submission, &verdict) == ENGINE_STATUS_OK);
+  CHECK(verdict != NULL && strstr(verdict, "\"valid\":false") != NULL);
+  engine_string_free(verdict);
+
+  engine_simulator_destroy(sim);
+  engine_simulator_destroy(NULL);
+  engine_string_free(NULL);
+
+  if (failures > 0) {
+    fprintf(stderr, "%d check(s) failed\n", failures);
+    return 1;
+  }
+  printf("C API smoke test passed (engine %s)\n", engine_version());
+  return 0;
+}
@@ -0,0 +1,184 @@
+//! Python bindings for the simulation engine (`engine_py`).
+//!
+//! Inputs are plain dicts (or dataclasses) shaped like the engine's JSON types and results
+//! come back as dicts. Values cross the boundary through Python's `json` module, so the
+//! shapes are exactly those of the JSON API. Simulation runs with the GIL released.
+
+use engine::model::ContentPack as EnginePack;
+use engine::sim::{BatchJob, WaveSummary, simulate_batch as engine_batch};
+use engine::verify::{RunSubmission, verify_run as engine_verify};
+use engine::{DungeonState, ENGINE_VERSION, SimulationConfig, WaveConfig};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+create_exception!(
+    engine_py,
+    SimulationError,
+    PyException,
+    "The engine rejected the inputs (a limit was exceeded or the dungeon/wave is invalid)."
+);
+
+/// A parsed content pack, reusable across calls without re-parsing.
+#[pyclass(frozen, module = "engine_py")]
+struct ContentPack {
+    pack: EnginePack,
+}
+
+#[pymethods]
+impl ContentPack {
+    /// Parse a content pack from a dict.
+    #[staticmethod]
+    fn from_dict(pack: &Bound<'_, PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            pack: from_python(pack, "content pack")?,
+        })
+    }
+
+    #[getter]
+    fn version(&self) -> String {
+        self.pack.version.clone()
+    }
+
+    fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        to_python(py, &self.pack)
+    }
+}
+
+/// Read and parse a content pack JSON file.
+#[pyfunction]
+fn load_content_pack(path: &str) -> PyResult<ContentPack> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|err| PyValueError::new_err(format!("failed to read {path}: {err}")))?;
+    let pack = serde_json::from_str(&data)
+        .map_err(|err| PyValueError::new_err(format!("failed to parse {path}: {err}")))?;
+    Ok(ContentPack { pack })
+}
+
+/// Simulate one wave and return the full result. `max_ticks` defaults to
+/// `config["limits"]["max_ticks"]`.
+#[pyfunction]
+#[pyo3(signature = (dungeon, wave, seed, max_ticks=None, config=None))]
+fn simulate_wave(
+    py: Python<'_>,
+    dungeon: &Bound<'_, PyAny>,
+    wave: &Bound<'_, PyAny>,
+    seed: u64,
+    max_ticks: Option<u32>,
+    config: Option<&Bound<'_, PyAny>>,
+) -> PyResult<Py<PyAny>> {
+    let dungeon: DungeonState = from_python(dungeon, "dungeon")?;
+    let wave: WaveConfig = from_python(wave, "wave")?;
+    let config = config_from_python(config)?;
+    let max_ticks = max_ticks.unwrap_or(config.limits.max_ticks);
+
+    let result = py
+        .detach(|| engine::simulate_wave(dungeon, wave, seed, max_ticks, &config))
+        .map_err(sim_error)?;
+    to_python(py, &result)
+}
+
+/// Simulate `runs` seeds from `start_seed` on `threads` threads (`0`: all cores) and
+/// return one summary dict per seed, in seed order.
+#[pyfunction]
+#[pyo3(signature = (dungeon, wave, runs, start_seed=1, max_ticks=None, config=None, threads=0))]
+fn simulate_batch(
+    dungeon: &Bound<'_, PyAny>,
+    wave: &Bound<'_, PyAny>,
+    runs: u64,
+    start_seed: u64,
+    max_ticks: Option<u32>,
+    config: Option<&Bound<'_, PyAny>>,
+    threads: usize,
+) -> PyResult<Py<PyAny>> {
+    let py = dungeon.py();
+    let dungeon: DungeonState = from_python(dungeon, "dungeon")?;
+    let wave: WaveConfig = from_python(wave, "wave")?;
+    let config = config_from_python(config)?;
+    let max_ticks = max_ticks.unwrap_or(config.limits.max_ticks);
+
+    let summaries = py.detach(|| {
+        let jobs = BatchJob::seed_sweep(&dungeon, &wave, start_seed, runs, max_ticks, &config);
+        engine_batch(&jobs, threads)
+            .into_iter()
+            .collect::<Result<Vec<WaveSummary>, _>>()
+    });
+    to_python(py, &summaries.map_err(sim_error)?)
+}
+
+/// Check a dungeon/wave pair without simulating. Returns the validation report.
+#[pyfunction]
+fn validate(
+    py: Python<'_>,
+    dungeon: &Bound<'_, PyAny>,
+    wave: &Bound<'_, PyAny>,
+) -> PyResult<Py<PyAny>> {
+    let dungeon: DungeonState = from_python(dungeon, "dungeon")?;
+    let wave: WaveConfig = from_python(wave, "wave")?;
+    to_python(py, &engine::validate(&dungeon, &wave))
+}
+
+/// Replay a run submission against a content pack. Returns the verification report.
+#[pyfunction]
+fn verify_run(
+    py: Python<'_>,
+    submission: &Bound<'_, PyAny>,
+    content: &ContentPack,
+) -> PyResult<Py<PyAny>> {
+    let submission: RunSubmission = from_python(submission, "submission")?;
+    let report = py.detach(|| engine_verify(&submission, &content.pack));
+    to_python(py, &report)
+}
+
+#[pymodule]
+fn engine_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("__version__", ENGINE_VERSION)?;
+    m.add("SimulationError", m.py().get_type::<SimulationError>())?;
+    m.add_class::<ContentPack>()?;
+    m.add_function(wrap_pyfunction!(load_content_pack, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_wave, m)?)?;
+    m.add_function(wrap_pyfunction!(simulate_batch, m)?)?;
+    m.add_function(wrap_pyfunction!(validate, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_run, m)?)?;
+    Ok(())
+}
+
+fn config_from_python(config: Option<&Bound<'_, PyAny>>) -> PyResult<SimulationConfig> {
+    match config {
+        Some(config) if !config.is_none() => from_python(config, "config"),
+        _ => Ok(SimulationConfig::default()),
+    }
+}
+
+/// Dataclasses are converted with `dataclasses.asdict` first.
+fn from_python<T: DeserializeOwned>(value: &Bound<'_, PyAny>, what: &str) -> PyResult<T> {
+    let py = value.py();
+    let dataclasses = py.import("dataclasses")?;
+    let is_instance = dataclasses
+        .call_method1("is_dataclass", (value,))?
+        .is_truthy()?
+        && !value.is_instance_of::<pyo3::types::PyType>();
+    let value = if is_instance {
+        dataclasses.call_method1("asdict", (value,))?
+    } else {
+        value.clone()
+    };
+    let json: String = py
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&json)
+        .map_err(|err| PyValueError::new_err(format!("failed to parse {what}: {err}")))
+}
+
+fn to_python<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<Py<PyAny>> {
+    let json = serde_json::to_string(value)
+        .map_err(|err| PyValueError::new_err(format!("failed to serialize result: {err}")))?;
+    Ok(py.import("json")?.call_method1("loads", (json,))?.unbind())
+}
+
+fn sim_error(err: engine::error::SimError) -> PyErr {
+    SimulationError::new_err(err.to_string())
+}
@@ -0,0 +1,84 @@
+"""Tests for the engine_py extension. Build it first with `maturin develop`."""
+
+import dataclasses
+import json
+import pathlib
+import unittest
+
+import engine_py
+
+FIXTURES = pathlib.Path(__file__).resolve().parents[2] / "src" / "sim" / "test_fixtures"
+CORPUS = json.loads((FIXTURES / "conformance_corpus.json").read_text())
+# Must match CONFORMANCE_MAX_TICKS in src/sim/tests.rs.
+CORPUS_MAX_TICKS = 250
+# Result fields the corpus digest covers, in SimulationResult order.
+RULES_OUTPUT = ("outcome", "final_dungeon", "final_heroes", "stats", "events", "score")
+
+
+def result_digest(result):
+    """FNV-1a of the compact JSON of the rules output, as in src/sim/tests.rs."""
+    result = {key: result[key] for key in RULES_OUTPUT}
+    data = json.dumps(result, separators=(",", ":"), ensure_ascii=False).encode()
+    digest = 0xCBF29CE484222325
+    for byte in data:
+        digest = ((digest ^ byte) * 0x100000001B3) & 0xFFFFFFFFFFFFFFFF
+    return f"{digest:016x}"
+
+
+@dataclasses.dataclass
+class Spawn:
+    hero_template_id: str
+    count: int
+    spawn_room_id: int
+    delay_ticks: int = 0
+
+
+@dataclasses.dataclass
+class Wave:
+    id: str
+    entries: list
+    modifiers: list = dataclasses.field(default_factory=list)
+
+
+class EnginePyTest(unittest.TestCase):
+    def test_results_match_the_native_conformance_corpus(self):
+        for index, case in enumerate(CORPUS[:16]):
+            result = engine_py.simulate_wave(
+                case["dungeon"], case["wave"], case["seed"], CORPUS_MAX_TICKS
+            )
+            self.assertEqual(case["result_digest"], result_digest(result), f"case {index}")
+
+    def test_batch_summaries_follow_seed_order(self):
+        case = CORPUS[0]
+        summaries = engine_py.simulate_batch(
+            case["dungeon"], case["wave"], runs=8, start_seed=40, max_ticks=100, threads=2
+        )
+        self.assertEqual(list(range(40, 48)), [s["seed"] for s in summaries])
+        single = engine_py.simulate_wave(case["dungeon"], case["wave"], 43, 100)
+        self.assertEqual(single["stats"], summaries[3]["stats"])
+
+    def test_dataclasses_are_accepted(self):
+        case = CORPUS[0]
+        spawn_room = case["wave"]["entries"][0]["spawn_room_id"]
+        wave = Wave(id="dc", entries=[Spawn("h", 1, spawn_room)])
+        result = engine_py.simulate_wave(case["dungeon"], wave, 1, 50)
+        self.assertEqual(1, result["stats"]["heroes_spawned"])
+
+    def test_validation_and_errors(self):
+        case = CORPUS[0]
+        wave = dict(case["wave"], entries=[dict(case["wave"]["entries"][0], spawn_room_id=999)])
+        report = engine_py.validate(case["dungeon"], wave)
+        self.assertTrue(report["issues"])
+        with self.assertRaises(engine_py.SimulationError):
+            engine_py.simulate_wave(case["dungeon"], wave, 1, 50)
+        with self.assertRaises(ValueError):
+            engine_py.simulate_wave({"rooms": "nope"}, case["wave"], 1, 50)
+
+    def test_content_packs_load_from_dicts(self):
+        pack = engine_py.ContentPack.from_dict({"version": "2.1.0"})
+        self.assertEqual("2.1.0", pack.version)
+        self.assertEqual("2.1.0", pack.to_dict()["version"])
+
+
+if __name__ == "__main__":
+    unittest.main()
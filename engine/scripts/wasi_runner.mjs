@@ -0,0 +1,9 @@
+// Cargo runner for wasm32-wasip1 test binaries: `node scripts/wasi_runner.mjs <file.wasm> [args...]`.
+import { readFile } from 'node:fs/promises';
+import { WASI } from 'node:wasi';
+
+const [wasmPath, ...args] = process.argv.slice(2);
+const wasi = new WASI({ version: 'preview1', args: [wasmPath, ...args], env: process.env });
+const module = await WebAssembly.compile(await readFile(wasmPath));
+const instance = await WebAssembly.instantiate(module, wasi.getImportObject());
+process.exitCode = wasi.start(instance);
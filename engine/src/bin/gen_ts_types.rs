@@ -0,0 +1,25 @@
+//! Regenerate the client's TypeScript declarations from the Rust model.
+//! Usage: gen_ts_types [output.ts] (default: `engine::typescript::BINDINGS_PATH`)
+
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use engine::typescript::{BINDINGS_PATH, typescript_bindings};
+
+fn main() -> ExitCode {
+    let path = env::args().nth(1).map_or_else(
+        || PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(BINDINGS_PATH),
+        PathBuf::from,
+    );
+    match std::fs::write(&path, typescript_bindings()) {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: failed to write {}: {err}", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
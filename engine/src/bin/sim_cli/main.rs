@@ -0,0 +1,809 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use engine::error::SimError;
+use engine::model::ContentPack;
+use engine::model::{SimulationOutcome, SimulationResult};
+use engine::schema;
+use engine::service::{ErrorInfo, ErrorKind, ErrorReply};
+use engine::sim::narrative::{NarrativeFormat, Narrator};
+use engine::sim::stats::{ConfidenceInterval, StressReport, StressStats, z_for_confidence};
+use engine::sim::{ChecksumDivergence, WaveSummary, first_divergence};
+use engine::verify::{RunSubmission, verify_run};
+use engine::{
+    BatchJob, DungeonState, ENGINE_VERSION, SimulationConfig, WaveConfig, simulate_batch,
+    simulate_wave, validate,
+};
+use serde::Serialize;
+
+mod serve;
+
+/// Seeds simulated between early-stopping checkpoints of `stress`.
+const STRESS_CHUNK: u64 = 1024;
+
+struct RunArgs {
+    dungeon: PathBuf,
+    wave: PathBuf,
+    seed: u64,
+    max_ticks: Option<u32>,
+    config: Option<PathBuf>,
+    summary_only: bool,
+    event_limit: usize,
+    content: Option<PathBuf>,
+    markdown: bool,
+}
+
+struct StressArgs {
+    dungeon: PathBuf,
+    wave: PathBuf,
+    start_seed: u64,
+    runs: u64,
+    max_ticks: Option<u32>,
+    config: Option<PathBuf>,
+    verbose: bool,
+    threads: usize,
+    confidence: f64,
+    precision: Option<f64>,
+    min_runs: u64,
+    histograms: bool,
+}
+
+struct ValidateArgs {
+    dungeon: PathBuf,
+    wave: PathBuf,
+}
+
+struct VerifyArgs {
+    submission: PathBuf,
+    content: PathBuf,
+}
+
+struct CompareArgs {
+    left: PathBuf,
+    right: PathBuf,
+}
+
+struct SchemaArgs {
+    out: Option<PathBuf>,
+}
+
+/// How results and errors are written to stdout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One pretty-printed JSON document.
+    Json,
+    /// One compact JSON document per line.
+    Ndjson,
+}
+
+impl Format {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            other => Err(format!(
+                "unknown format {other}; expected text, json or ndjson"
+            )),
+        }
+    }
+
+    fn is_json(self) -> bool {
+        self != Format::Text
+    }
+
+    /// Print `value` as JSON: pretty for `json`, a single line otherwise.
+    fn emit<T: Serialize>(self, value: &T) {
+        let json = match self {
+            Format::Json => serde_json::to_string_pretty(value),
+            Format::Text | Format::Ndjson => serde_json::to_string(value),
+        };
+        println!("{}", json.expect("CLI output types serialize to JSON"));
+    }
+}
+
+/// Exit code for each kind of failure. The `sim_server` kinds never occur here.
+fn exit_code(kind: ErrorKind) -> u8 {
+    match kind {
+        ErrorKind::Usage => 2,
+        ErrorKind::Input => 3,
+        ErrorKind::Simulation => 4,
+        ErrorKind::Failed
+        | ErrorKind::Request
+        | ErrorKind::TooLarge
+        | ErrorKind::NotFound
+        | ErrorKind::MethodNotAllowed => 1,
+    }
+}
+
+/// Why a command did not succeed, with the exit code of its kind filled in.
+#[derive(Debug)]
+struct CliError(ErrorInfo);
+
+impl CliError {
+    fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        ErrorInfo::new(kind, message).into()
+    }
+
+    fn exit_code(&self) -> u8 {
+        exit_code(self.0.kind)
+    }
+
+    /// Report the error on stdout (JSON formats) or stderr (text). A `Failed` command has
+    /// already printed its verdict, so JSON formats add nothing.
+    fn report(self, format: Format) {
+        if !format.is_json() {
+            eprintln!("error: {}", self.0.message);
+        } else if self.0.kind != ErrorKind::Failed {
+            format.emit(&ErrorReply { error: self.0 });
+        }
+    }
+}
+
+impl From<ErrorInfo> for CliError {
+    fn from(mut info: ErrorInfo) -> Self {
+        info.exit_code = Some(exit_code(info.kind));
+        CliError(info)
+    }
+}
+
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::new(ErrorKind::Usage, message)
+    }
+}
+
+impl From<SimError> for CliError {
+    fn from(err: SimError) -> Self {
+        ErrorInfo::from(err).into()
+    }
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let format = match take_format(&mut args) {
+        Ok(format) => format,
+        Err(err) => {
+            // The format itself is unusable, so report in the one every tool can read.
+            let code = err.exit_code();
+            err.report(Format::Json);
+            return ExitCode::from(code);
+        }
+    };
+
+    match dispatch(args, format) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            let code = err.exit_code();
+            err.report(format);
+            ExitCode::from(code)
+        }
+    }
+}
+
+/// Remove `--format <value>` from anywhere in `args`; every subcommand accepts it.
+fn take_format(args: &mut Vec<String>) -> Result<Format, CliError> {
+    let Some(position) = args.iter().position(|arg| arg == "--format") else {
+        return Ok(Format::Text);
+    };
+    args.remove(position);
+    if position >= args.len() {
+        return Err("expected value after --format".to_string().into());
+    }
+    Ok(Format::parse(&args.remove(position))?)
+}
+
+fn dispatch(args: Vec<String>, format: Format) -> Result<(), CliError> {
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        print_usage();
+        return Err("missing command".to_string().into());
+    };
+
+    match command.as_str() {
+        "run" => run_once(parse_run_args(args.collect())?, format),
+        "stress" => stress(parse_stress_args(args.collect())?, format),
+        "validate" => validate_inputs(parse_validate_args(args.collect())?, format),
+        "verify" => verify_submission(parse_verify_args(args.collect())?, format),
+        "compare" => compare_results(parse_compare_args(args.collect())?, format),
+        "schema" => write_schema(parse_schema_args(args.collect())?, format),
+        "serve-stdio" => {
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument: {arg}").into());
+            }
+            serve::serve_stdio()
+        }
+        _ => {
+            if !format.is_json() {
+                print_usage();
+            }
+            Err(format!("unknown command: {command}").into())
+        }
+    }
+}
+
+fn parse_run_args(raw: Vec<String>) -> Result<RunArgs, String> {
+    let mut dungeon = None;
+    let mut wave = None;
+    let mut seed = None;
+    let mut max_ticks = None;
+    let mut config = None;
+    let mut summary_only = false;
+    let mut event_limit = 0usize;
+    let mut content = None;
+    let mut markdown = false;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dungeon" => dungeon = Some(next_path(&arg, iter.next())?),
+            "--wave" => wave = Some(next_path(&arg, iter.next())?),
+            "--seed" => seed = Some(next_number(&arg, iter.next())?),
+            "--max-ticks" => max_ticks = Some(next_number(&arg, iter.next())?),
+            "--config" => config = Some(next_path(&arg, iter.next())?),
+            "--summary-only" => summary_only = true,
+            "--event-limit" => event_limit = next_number(&arg, iter.next())?,
+            "--content" => content = Some(next_path(&arg, iter.next())?),
+            "--markdown" => markdown = true,
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(RunArgs {
+        dungeon: required("--dungeon", dungeon)?,
+        wave: required("--wave", wave)?,
+        seed: required("--seed", seed)?,
+        max_ticks,
+        config,
+        summary_only,
+        event_limit,
+        content,
+        markdown,
+    })
+}
+
+fn parse_stress_args(raw: Vec<String>) -> Result<StressArgs, String> {
+    let mut dungeon = None;
+    let mut wave = None;
+    let mut start_seed = 1u64;
+    let mut runs = 100u64;
+    let mut max_ticks = None;
+    let mut config = None;
+    let mut verbose = false;
+    let mut threads = 0usize;
+    let mut confidence = 0.95;
+    let mut precision = None;
+    let mut min_runs = 100u64;
+    let mut histograms = false;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dungeon" => dungeon = Some(next_path(&arg, iter.next())?),
+            "--wave" => wave = Some(next_path(&arg, iter.next())?),
+            "--start-seed" => start_seed = next_number(&arg, iter.next())?,
+            "--runs" => runs = next_number(&arg, iter.next())?,
+            "--max-ticks" => max_ticks = Some(next_number(&arg, iter.next())?),
+            "--config" => config = Some(next_path(&arg, iter.next())?),
+            "--verbose" => verbose = true,
+            "--threads" => threads = next_number(&arg, iter.next())?,
+            "--confidence" => confidence = next_number(&arg, iter.next())?,
+            "--precision" => precision = Some(next_number(&arg, iter.next())?),
+            "--min-runs" => min_runs = next_number(&arg, iter.next())?,
+            "--histograms" => histograms = true,
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(StressArgs {
+        dungeon: required("--dungeon", dungeon)?,
+        wave: required("--wave", wave)?,
+        start_seed,
+        runs,
+        max_ticks,
+        config,
+        verbose,
+        threads,
+        confidence,
+        precision,
+        min_runs,
+        histograms,
+    })
+}
+
+fn parse_validate_args(raw: Vec<String>) -> Result<ValidateArgs, String> {
+    let mut dungeon = None;
+    let mut wave = None;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dungeon" => dungeon = Some(next_path(&arg, iter.next())?),
+            "--wave" => wave = Some(next_path(&arg, iter.next())?),
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(ValidateArgs {
+        dungeon: required("--dungeon", dungeon)?,
+        wave: required("--wave", wave)?,
+    })
+}
+
+fn parse_verify_args(raw: Vec<String>) -> Result<VerifyArgs, String> {
+    let mut submission = None;
+    let mut content = None;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--submission" => submission = Some(next_path(&arg, iter.next())?),
+            "--content" => content = Some(next_path(&arg, iter.next())?),
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(VerifyArgs {
+        submission: required("--submission", submission)?,
+        content: required("--content", content)?,
+    })
+}
+
+fn parse_compare_args(raw: Vec<String>) -> Result<CompareArgs, String> {
+    let mut left = None;
+    let mut right = None;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--left" => left = Some(next_path(&arg, iter.next())?),
+            "--right" => right = Some(next_path(&arg, iter.next())?),
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(CompareArgs {
+        left: required("--left", left)?,
+        right: required("--right", right)?,
+    })
+}
+
+fn parse_schema_args(raw: Vec<String>) -> Result<SchemaArgs, String> {
+    let mut out = None;
+
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--out" => out = Some(next_path(&arg, iter.next())?),
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(SchemaArgs { out })
+}
+
+fn required<T>(name: &str, value: Option<T>) -> Result<T, String> {
+    value.ok_or_else(|| format!("missing required argument {name}"))
+}
+
+fn next_path(flag: &str, value: Option<String>) -> Result<PathBuf, String> {
+    value
+        .map(PathBuf::from)
+        .ok_or_else(|| format!("expected value after {flag}"))
+}
+
+fn next_number<T>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+    value
+        .ok_or_else(|| format!("expected value after {flag}"))?
+        .parse()
+        .map_err(|err| format!("failed to parse {flag}: {err}"))
+}
+
+fn run_once(args: RunArgs, format: Format) -> Result<(), CliError> {
+    let dungeon: DungeonState = load_json(&args.dungeon)?;
+    let wave: WaveConfig = load_json(&args.wave)?;
+
+    let content: Option<ContentPack> = args.content.as_ref().map(load_json).transpose()?;
+    let config = load_config(args.config.as_ref())?;
+    let max_ticks = args.max_ticks.unwrap_or(config.limits.max_ticks);
+    let result = simulate_wave(dungeon.clone(), wave, args.seed, max_ticks, &config)?;
+
+    if format.is_json() {
+        if args.summary_only {
+            format.emit(&WaveSummary {
+                seed: args.seed,
+                outcome: result.outcome,
+                stats: result.stats,
+                core_hp_remaining: result.final_dungeon.core_hp,
+                score: result.score.total,
+            });
+        } else {
+            format.emit(&result);
+        }
+        return Ok(());
+    }
+
+    print_summary(
+        args.seed,
+        &result.outcome,
+        &result.final_dungeon.core_hp,
+        &result.stats,
+        result.events.len(),
+        result.config.limits.max_events,
+    );
+    println!("Score: {} (v{})", result.score.total, result.score.version);
+
+    if !args.summary_only {
+        let format = if args.markdown {
+            NarrativeFormat::Markdown
+        } else {
+            NarrativeFormat::PlainText
+        };
+        let lines = Narrator::new(&dungeon, content.as_ref(), format).lines(&result.events);
+        let total_lines = lines.len();
+        let limit = if args.event_limit == 0 {
+            total_lines
+        } else {
+            args.event_limit.min(total_lines)
+        };
+
+        println!("\nEvent log (showing {limit} of {total_lines}):");
+        for line in lines.iter().take(limit) {
+            println!("{line}");
+        }
+        if limit < total_lines {
+            println!("... {} more entries omitted ...", total_lines - limit);
+        }
+    }
+
+    Ok(())
+}
+
+/// `stress --format json` output.
+#[derive(Serialize)]
+struct StressOutput<'a> {
+    start_seed: u64,
+    stopped_early: bool,
+    #[serde(flatten)]
+    report: &'a StressReport,
+}
+
+/// One line of `stress --format ndjson`: a line per seed, then the report.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StressLine<'a> {
+    Seed(&'a WaveSummary),
+    Report(StressOutput<'a>),
+}
+
+fn stress(args: StressArgs, format: Format) -> Result<(), CliError> {
+    let dungeon: DungeonState = load_json(&args.dungeon)?;
+    let wave: WaveConfig = load_json(&args.wave)?;
+    let config = load_config(args.config.as_ref())?;
+    let max_ticks = args.max_ticks.unwrap_or(config.limits.max_ticks);
+    let z = z_for_confidence(args.confidence)
+        .ok_or_else(|| "--confidence must be between 0 and 1 (exclusive)".to_string())?;
+
+    // Seeds run in chunks so a precision target can stop the sweep early. The stopping
+    // point is checked after every seed in order, so it does not depend on the chunk size
+    // or thread count.
+    let mut stats = StressStats::default();
+    let mut stopped_early = false;
+    while stats.runs < args.runs && !stopped_early {
+        let chunk = (args.runs - stats.runs).min(STRESS_CHUNK);
+        let jobs = BatchJob::seed_sweep(
+            &dungeon,
+            &wave,
+            args.start_seed.wrapping_add(stats.runs),
+            chunk,
+            max_ticks,
+            &config,
+        );
+        for summary in simulate_batch(&jobs, args.threads) {
+            let summary = summary?;
+            stats.record(&summary);
+            if format == Format::Ndjson {
+                format.emit(&StressLine::Seed(&summary));
+            } else if args.verbose && format == Format::Text {
+                println!(
+                    "seed {}: {:?} (ticks: {}, hero kills: {}, monster kills: {})",
+                    summary.seed,
+                    summary.outcome,
+                    summary.stats.ticks_run,
+                    summary.stats.heroes_killed,
+                    summary.stats.monsters_killed
+                );
+            }
+            if let Some(precision) = args.precision
+                && stats.runs >= args.min_runs
+                && stats.is_precise(z, precision)
+            {
+                stopped_early = true;
+                break;
+            }
+        }
+    }
+
+    let report = stats
+        .report(args.confidence)
+        .ok_or_else(|| "--confidence must be between 0 and 1 (exclusive)".to_string())?;
+    let output = StressOutput {
+        start_seed: args.start_seed,
+        stopped_early,
+        report: &report,
+    };
+    match format {
+        Format::Text => {
+            print_stress_report(&report, args.start_seed, stopped_early, args.histograms)
+        }
+        Format::Json => format.emit(&output),
+        Format::Ndjson => format.emit(&StressLine::Report(output)),
+    }
+    Ok(())
+}
+
+fn print_stress_report(
+    report: &StressReport,
+    start_seed: u64,
+    stopped_early: bool,
+    histograms: bool,
+) {
+    println!(
+        "Ran {} simulations starting at seed {start_seed}{}",
+        report.runs,
+        if stopped_early {
+            " (stopped early: target precision reached)"
+        } else {
+            ""
+        }
+    );
+    let level = report.confidence * 100.0;
+    let rate = |count: u64, ci: &ConfidenceInterval| {
+        format!(
+            "{count} ({:.1}%, {level}% CI {:.1}%-{:.1}%)",
+            ci.estimate * 100.0,
+            ci.low * 100.0,
+            ci.high * 100.0
+        )
+    };
+    println!(
+        "Dungeon wins: {}",
+        rate(report.dungeon_wins, &report.dungeon_win_rate)
+    );
+    println!(
+        "Hero wins: {}",
+        rate(report.hero_wins, &report.hero_win_rate)
+    );
+    println!("Timeouts: {}", report.timeouts);
+    println!("Stalemates: {}", report.stalemates);
+
+    let metrics = [
+        ("Core damage", &report.core_damage),
+        ("Ticks", &report.ticks),
+        ("Heroes killed", &report.heroes_killed),
+        ("Monsters lost", &report.monsters_lost),
+    ];
+    println!(
+        "\n{:<14}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>10}",
+        "", "min", "p10", "p50", "p90", "p99", "max", "mean"
+    );
+    for (name, summary) in metrics {
+        if let Some(d) = summary {
+            println!(
+                "{name:<14}{:>8}{:>8}{:>8}{:>8}{:>8}{:>8}{:>10.2}",
+                d.min, d.p10, d.p50, d.p90, d.p99, d.max, d.mean
+            );
+        }
+    }
+
+    if histograms {
+        for (name, summary) in metrics {
+            let Some(d) = summary else { continue };
+            println!("\n{name}:");
+            let widest = d
+                .histogram
+                .iter()
+                .map(|bin| bin.count)
+                .max()
+                .unwrap_or(0)
+                .max(1);
+            for bin in &d.histogram {
+                let bar = "#".repeat((bin.count * 40).div_ceil(widest) as usize);
+                let range = if bin.low == bin.high {
+                    bin.low.to_string()
+                } else {
+                    format!("{}..={}", bin.low, bin.high)
+                };
+                let line = format!("  {range:>14} {:>7} {bar}", bin.count);
+                println!("{}", line.trim_end());
+            }
+        }
+    }
+}
+
+fn validate_inputs(args: ValidateArgs, format: Format) -> Result<(), CliError> {
+    let dungeon: DungeonState = load_json(&args.dungeon)?;
+    let wave: WaveConfig = load_json(&args.wave)?;
+    let report = validate(&dungeon, &wave);
+
+    if format.is_json() {
+        format.emit(&report);
+    } else {
+        for issue in &report.issues {
+            println!("{issue}");
+        }
+        let errors = report.errors().count();
+        let warnings = report.warnings().count();
+        println!("{errors} error(s), {warnings} warning(s)");
+    }
+
+    if report.has_errors() {
+        return Err(CliError::new(ErrorKind::Failed, "validation failed"));
+    }
+    Ok(())
+}
+
+/// Prints the [`engine::verify::VerificationReport`] as JSON on stdout (also in text
+/// format) and fails when the run is invalid, so callers can use either the exit code or
+/// the report.
+fn verify_submission(args: VerifyArgs, format: Format) -> Result<(), CliError> {
+    let submission: RunSubmission = load_json(&args.submission)?;
+    let content: ContentPack = load_json(&args.content)?;
+    let report = verify_run(&submission, &content);
+
+    match format {
+        Format::Text => Format::Json.emit(&report),
+        Format::Json | Format::Ndjson => format.emit(&report),
+    }
+    if !report.valid {
+        return Err(CliError::new(ErrorKind::Failed, "run verification failed"));
+    }
+    Ok(())
+}
+
+/// `compare --format json` output.
+#[derive(Serialize)]
+struct CompareOutput {
+    /// Checksums in the shorter of the two results.
+    compared: usize,
+    divergence: Option<ChecksumDivergence>,
+}
+
+fn compare_results(args: CompareArgs, format: Format) -> Result<(), CliError> {
+    let left: SimulationResult = load_json(&args.left)?;
+    let right: SimulationResult = load_json(&args.right)?;
+    if left.checksums.is_empty() || right.checksums.is_empty() {
+        return Err(CliError::new(
+            ErrorKind::Input,
+            "both results need checksums; set checksum_interval in the config",
+        ));
+    }
+
+    let compared = left.checksums.len().min(right.checksums.len());
+    let divergence = first_divergence(&left.checksums, &right.checksums);
+    if format.is_json() {
+        format.emit(&CompareOutput {
+            compared,
+            divergence,
+        });
+    }
+    let Some(divergence) = divergence else {
+        if !format.is_json() {
+            println!("No divergence across {compared} checksummed ticks.");
+        }
+        return Ok(());
+    };
+    if !format.is_json() {
+        let show = |checksum: Option<_>| match checksum {
+            Some(checksum) => format!("{checksum}"),
+            None => "(ended)".to_string(),
+        };
+        println!("First divergent tick: {}", divergence.tick);
+        println!("  left:  {}", show(divergence.left));
+        println!("  right: {}", show(divergence.right));
+    }
+    Err(CliError::new(ErrorKind::Failed, "results diverged"))
+}
+
+/// `schema --out <dir> --format json` output.
+#[derive(Serialize)]
+struct SchemaOutput {
+    path: PathBuf,
+    schema_version: u32,
+}
+
+/// Print the JSON Schema bundle, or write it to `<out>/engine.schema.json`.
+fn write_schema(args: SchemaArgs, format: Format) -> Result<(), CliError> {
+    let bundle = schema::schema_bundle();
+    let Some(dir) = args.out else {
+        print!("{bundle}");
+        return Ok(());
+    };
+
+    let path = dir.join(schema::BUNDLE_FILE);
+    fs::create_dir_all(&dir)
+        .and_then(|()| fs::write(&path, bundle))
+        .map_err(|err| {
+            CliError::new(
+                ErrorKind::Input,
+                format!("failed to write {}: {err}", path.display()),
+            )
+        })?;
+    if format.is_json() {
+        format.emit(&SchemaOutput {
+            path,
+            schema_version: schema::SCHEMA_VERSION,
+        });
+    } else {
+        println!(
+            "wrote {} (schema version {})",
+            path.display(),
+            schema::SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
+fn load_json<T: serde::de::DeserializeOwned>(path: &PathBuf) -> Result<T, CliError> {
+    let data = fs::read_to_string(path).map_err(|err| {
+        CliError::new(
+            ErrorKind::Input,
+            format!("failed to read {}: {err}", path.display()),
+        )
+    })?;
+    serde_json::from_str(&data).map_err(|err| {
+        CliError::new(
+            ErrorKind::Input,
+            format!("failed to parse {}: {err}", path.display()),
+        )
+    })
+}
+
+fn load_config(path: Option<&PathBuf>) -> Result<SimulationConfig, CliError> {
+    match path {
+        Some(path) => load_json(path),
+        None => Ok(SimulationConfig::default()),
+    }
+}
+
+fn print_summary(
+    seed: u64,
+    outcome: &SimulationOutcome,
+    core_hp: &i32,
+    stats: &engine::model::SimulationStats,
+    event_count: usize,
+    max_events: usize,
+) {
+    println!("Engine version: {ENGINE_VERSION}");
+    println!("Seed: {seed}");
+    println!("Outcome: {:?}", outcome);
+    println!("Core HP after wave: {core_hp}");
+    println!("Ticks run: {}", stats.ticks_run);
+    println!("Heroes spawned: {}", stats.heroes_spawned);
+    println!("Heroes killed: {}", stats.heroes_killed);
+    println!("Monsters killed: {}", stats.monsters_killed);
+    println!("Total damage to core: {}", stats.total_damage_to_core);
+    println!("Events emitted: {event_count} / {max_events}");
+}
+
+fn print_usage() {
+    eprintln!("Usage (every subcommand also takes --format text|json|ndjson):");
+    eprintln!(
+        "  sim_cli run --dungeon <file> --wave <file> --seed <n> [--max-ticks <n>] [--config <file>] [--summary-only] [--event-limit <n>] [--content <file>] [--markdown]"
+    );
+    eprintln!(
+        "  sim_cli stress --dungeon <file> --wave <file> [--start-seed <n>] [--runs <n>] [--max-ticks <n>] [--config <file>] [--verbose] [--threads <n>] [--confidence <level>] [--precision <half-width>] [--min-runs <n>] [--histograms]"
+    );
+    eprintln!("  sim_cli validate --dungeon <file> --wave <file>");
+    eprintln!("  sim_cli verify --submission <file> --content <file>");
+    eprintln!("  sim_cli compare --left <result file> --right <result file>");
+    eprintln!("  sim_cli schema [--out <dir>]  (JSON Schema bundle; stdout without --out)");
+    eprintln!("  sim_cli serve-stdio  (JSON-lines requests on stdin, responses on stdout)");
+}
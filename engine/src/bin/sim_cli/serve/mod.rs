@@ -0,0 +1,215 @@
+//! `sim_cli serve-stdio`: a long-lived worker speaking JSON lines.
+//!
+//! Each stdin line is one request object with an `op` and an optional `id`, which is
+//! echoed back. Each request gets exactly one stdout line,
+//! `{"id": ..., "ok": true, "result": ...}` or `{"id": ..., "ok": false, "error": ...}`,
+//! flushed immediately, in request order. Errors are [`ErrorInfo`]s without an exit code.
+//! A dungeon or content pack sent inline together with a `dungeon_id`/`content_id` is
+//! cached under that id for the life of the process, so later requests can send only the
+//! id.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use engine::DungeonState;
+use engine::model::ContentPack;
+use engine::service::{
+    BatchParams, ErrorInfo, ErrorKind, SimulateParams, ValidateParams, VerifyParams, version_info,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::CliError;
+
+/// Fields for each op are those of the matching [`engine::service`] params plus a dungeon
+/// or content reference.
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum Request {
+    Simulate {
+        #[serde(flatten)]
+        dungeon: DungeonRef,
+        #[serde(flatten)]
+        params: SimulateParams,
+    },
+    Validate {
+        #[serde(flatten)]
+        dungeon: DungeonRef,
+        #[serde(flatten)]
+        params: ValidateParams,
+    },
+    Batch {
+        #[serde(flatten)]
+        dungeon: DungeonRef,
+        #[serde(flatten)]
+        params: BatchParams,
+    },
+    Verify {
+        #[serde(flatten)]
+        content: ContentRef,
+        #[serde(flatten)]
+        params: VerifyParams,
+    },
+    Version,
+}
+
+/// An inline dungeon, a cached one, or both (cache the inline dungeon under the id).
+#[derive(Deserialize)]
+struct DungeonRef {
+    dungeon: Option<DungeonState>,
+    dungeon_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ContentRef {
+    content: Option<Box<ContentPack>>,
+    content_id: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    id: Value,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorInfo>,
+}
+
+#[derive(Default)]
+struct Worker {
+    dungeons: HashMap<String, DungeonState>,
+    content_packs: HashMap<String, ContentPack>,
+}
+
+/// Answer requests from stdin until it closes.
+pub(crate) fn serve_stdio() -> Result<(), CliError> {
+    serve(io::stdin().lock(), io::stdout().lock())
+}
+
+/// Answer each non-blank line of `input` with one line on `output`.
+fn serve(input: impl BufRead, mut output: impl Write) -> Result<(), CliError> {
+    let mut worker = Worker::default();
+    for line in input.lines() {
+        let line = line.map_err(|err| {
+            CliError::new(ErrorKind::Input, format!("failed to read stdin: {err}"))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = worker.handle_line(&line);
+        let json = serde_json::to_string(&response).expect("responses serialize to JSON");
+        writeln!(output, "{json}")
+            .and_then(|()| output.flush())
+            .map_err(|err| {
+                CliError::new(ErrorKind::Input, format!("failed to write stdout: {err}"))
+            })?;
+    }
+    Ok(())
+}
+
+impl Worker {
+    fn handle_line(&mut self, line: &str) -> Response {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(err) => {
+                return Response::error(Value::Null, usage(format!("invalid JSON: {err}")));
+            }
+        };
+        let id = value.get("id").cloned().unwrap_or(Value::Null);
+        let outcome = Request::deserialize(value)
+            .map_err(|err| usage(format!("invalid request: {err}")))
+            .and_then(|request| self.handle(request));
+        match outcome {
+            Ok(result) => Response {
+                id,
+                ok: true,
+                result: Some(result),
+                error: None,
+            },
+            Err(err) => Response::error(id, err),
+        }
+    }
+
+    fn handle(&mut self, request: Request) -> Result<Value, CliError> {
+        match request {
+            Request::Simulate { dungeon, params } => {
+                to_value(&params.run(self.dungeon(dungeon)?.into_owned())?)
+            }
+            Request::Validate { dungeon, params } => {
+                to_value(&params.run(self.dungeon(dungeon)?.as_ref()))
+            }
+            Request::Batch { dungeon, params } => {
+                to_value(&params.run(self.dungeon(dungeon)?.as_ref())?)
+            }
+            Request::Verify { content, params } => {
+                to_value(&params.run(self.content(content)?.as_ref()))
+            }
+            Request::Version => to_value(&version_info()),
+        }
+    }
+
+    fn dungeon(&mut self, reference: DungeonRef) -> Result<Cow<'_, DungeonState>, CliError> {
+        resolve(
+            &mut self.dungeons,
+            reference.dungeon,
+            reference.dungeon_id,
+            "dungeon",
+        )
+    }
+
+    fn content(&mut self, reference: ContentRef) -> Result<Cow<'_, ContentPack>, CliError> {
+        resolve(
+            &mut self.content_packs,
+            reference.content.map(|content| *content),
+            reference.content_id,
+            "content",
+        )
+    }
+}
+
+/// The inline value (cached under `id` if given), else the cached value for `id`.
+fn resolve<'a, T: Clone>(
+    cache: &'a mut HashMap<String, T>,
+    inline: Option<T>,
+    id: Option<String>,
+    field: &str,
+) -> Result<Cow<'a, T>, CliError> {
+    match (inline, id) {
+        (Some(value), Some(id)) => Ok(Cow::Borrowed(
+            cache.entry(id).insert_entry(value).into_mut(),
+        )),
+        (Some(value), None) => Ok(Cow::Owned(value)),
+        (None, Some(id)) => cache
+            .get(&id)
+            .map(Cow::Borrowed)
+            .ok_or_else(|| usage(format!("unknown {field}_id {id}"))),
+        (None, None) => Err(usage(format!("missing {field} or {field}_id"))),
+    }
+}
+
+impl Response {
+    fn error(id: Value, error: CliError) -> Self {
+        Response {
+            id,
+            ok: false,
+            result: None,
+            error: Some(ErrorInfo {
+                exit_code: None,
+                ..error.0
+            }),
+        }
+    }
+}
+
+fn usage(message: String) -> CliError {
+    CliError::new(ErrorKind::Usage, message)
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, CliError> {
+    Ok(serde_json::to_value(value).expect("responses serialize to JSON"))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,110 @@
+use engine::sim::test_fixtures;
+use serde_json::{Value, json};
+
+use super::{Worker, serve};
+
+fn handle(worker: &mut Worker, request: Value) -> Value {
+    serde_json::to_value(worker.handle_line(&request.to_string())).unwrap()
+}
+
+fn duel() -> (Value, Value) {
+    let fixture = test_fixtures::core_room_duel();
+    (
+        serde_json::to_value(&fixture.dungeon).unwrap(),
+        serde_json::to_value(&fixture.wave).unwrap(),
+    )
+}
+
+#[test]
+fn inline_dungeons_with_an_id_are_cached_for_later_requests() {
+    let (dungeon, wave) = duel();
+    let mut worker = Worker::default();
+
+    let first = handle(
+        &mut worker,
+        json!({ "id": 1, "op": "simulate", "dungeon_id": "keep", "dungeon": dungeon,
+                "wave": wave, "seed": 7, "max_ticks": 50, "summary_only": true }),
+    );
+    assert_eq!(json!(1), first["id"]);
+    assert_eq!(json!(true), first["ok"], "{first}");
+    assert_eq!(json!(7), first["result"]["seed"]);
+
+    let cached = handle(
+        &mut worker,
+        json!({ "id": "by-id", "op": "batch", "dungeon_id": "keep", "wave": wave,
+                "start_seed": 7, "runs": 2, "max_ticks": 50 }),
+    );
+    assert_eq!(json!("by-id"), cached["id"]);
+    assert_eq!(json!(true), cached["ok"], "{cached}");
+    let summaries = cached["result"]["summaries"].as_array().unwrap();
+    assert_eq!(2, summaries.len());
+    assert_eq!(
+        first["result"], summaries[0],
+        "the cached dungeon is the inline one"
+    );
+}
+
+#[test]
+fn unknown_ids_fail_without_an_exit_code() {
+    let (_, wave) = duel();
+    let mut worker = Worker::default();
+
+    let response = handle(
+        &mut worker,
+        json!({ "id": 3, "op": "validate", "dungeon_id": "moat", "wave": wave }),
+    );
+    assert_eq!(
+        json!({
+            "id": 3,
+            "ok": false,
+            "error": { "kind": "usage", "message": "unknown dungeon_id moat" }
+        }),
+        response
+    );
+}
+
+#[test]
+fn unreadable_lines_are_answered_with_a_null_id() {
+    let mut worker = Worker::default();
+
+    let response = serde_json::to_value(worker.handle_line("{\"id\": 4, \"op\"")).unwrap();
+    assert_eq!(Value::Null, response["id"]);
+    assert_eq!(json!(false), response["ok"]);
+    assert_eq!(json!("usage"), response["error"]["kind"]);
+
+    let response = handle(&mut worker, json!({ "id": 5, "op": "teleport" }));
+    assert_eq!(json!(5), response["id"], "a parsed id is echoed");
+    assert_eq!(json!("usage"), response["error"]["kind"]);
+}
+
+#[test]
+fn every_request_gets_one_response_in_order() {
+    let input = [
+        json!({ "id": 1, "op": "version" }).to_string(),
+        String::new(),
+        "not json".to_string(),
+        json!({ "id": 2, "op": "validate" }).to_string(),
+        json!({ "op": "version" }).to_string(),
+        json!({ "id": 3, "op": "version" }).to_string(),
+    ]
+    .join("\n");
+    let mut output = Vec::new();
+    serve(input.as_bytes(), &mut output).unwrap();
+
+    let responses: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let ids: Vec<&Value> = responses.iter().map(|response| &response["id"]).collect();
+    assert_eq!(
+        vec![&json!(1), &Value::Null, &json!(2), &Value::Null, &json!(3)],
+        ids,
+        "blank lines are skipped"
+    );
+    let ok: Vec<bool> = responses
+        .iter()
+        .map(|response| response["ok"].as_bool().unwrap())
+        .collect();
+    assert_eq!(vec![true, false, false, true, true], ok);
+}
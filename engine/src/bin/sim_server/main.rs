@@ -0,0 +1,395 @@
+//! Local HTTP server exposing the engine to the backend verifier, Python tools and
+//! dashboards. Built with `--features server`.
+//!
+//! Routes take and return the engine's JSON types; request fields match
+//! [`engine::service`] plus the `dungeon` or `content` they run against:
+//! - `POST /simulate`, `/validate`, `/batch` with a `dungeon`,
+//! - `POST /verify-run` with a `content` pack,
+//! - `GET /version`.
+//!
+//! `--max-concurrent` worker threads handle requests, so it also caps CPU use: a batch
+//! runs on its request's worker thread rather than fanning out. Further connections wait
+//! in the accept queue.
+//!
+//! Request configs cannot lift the server's own ceilings: `config.limits` above
+//! `--max-ticks`, `--max-events` or the default limits, and batches or run verifications
+//! that would simulate more than `--max-request-ticks` ticks, are refused with 413.
+
+use std::env;
+use std::io::Read;
+use std::num::NonZeroUsize;
+use std::process::ExitCode;
+use std::thread;
+
+use engine::DungeonState;
+use engine::error::SimError;
+use engine::model::ContentPack;
+use engine::service::{
+    BatchParams, ErrorInfo, ErrorKind, ErrorReply, SimulateParams, ValidateParams, VerifyParams,
+    version_info,
+};
+use engine::sim::SimulationLimits;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server, StatusCode};
+
+struct ServerArgs {
+    addr: String,
+    max_body_bytes: usize,
+    max_concurrent: usize,
+    max_batch_runs: u64,
+    /// Ceiling on every `config.limits` a request sends, including a run template's.
+    limits: SimulationLimits,
+    /// Ticks one request may simulate in total: `runs * max_ticks` for a batch, waves times
+    /// `max_ticks_per_wave` for a run verification.
+    max_request_ticks: u64,
+}
+
+impl Default for ServerArgs {
+    fn default() -> Self {
+        Self {
+            addr: "127.0.0.1:8787".into(),
+            max_body_bytes: 4 * 1024 * 1024,
+            max_concurrent: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            max_batch_runs: 100_000,
+            limits: SimulationLimits::default(),
+            max_request_ticks: 100_000_000,
+        }
+    }
+}
+
+/// A request body: the route's params plus the dungeon they run against.
+#[derive(Deserialize)]
+struct WithDungeon<P> {
+    dungeon: DungeonState,
+    #[serde(flatten)]
+    params: P,
+}
+
+#[derive(Deserialize)]
+struct WithContent<P> {
+    content: ContentPack,
+    #[serde(flatten)]
+    params: P,
+}
+
+/// A failed request: its status and the [`ErrorInfo`] sent as the body.
+#[derive(Debug)]
+struct ApiError {
+    status: u16,
+    info: ErrorInfo,
+}
+
+impl ApiError {
+    fn new(status: u16, kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            info: ErrorInfo::new(kind, message),
+        }
+    }
+}
+
+impl From<SimError> for ApiError {
+    fn from(err: SimError) -> Self {
+        Self {
+            status: 422,
+            info: err.into(),
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1).collect()) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("error: {message}");
+            eprintln!(
+                "Usage: sim_server [--addr <host:port>] [--max-body-bytes <n>] [--max-concurrent <n>] [--max-batch-runs <n>] [--max-ticks <n>] [--max-events <n>] [--max-request-ticks <n>]"
+            );
+            return ExitCode::from(2);
+        }
+    };
+    let server = match Server::http(&args.addr) {
+        Ok(server) => server,
+        Err(err) => {
+            eprintln!("error: failed to bind {}: {err}", args.addr);
+            return ExitCode::FAILURE;
+        }
+    };
+    eprintln!(
+        "sim_server listening on http://{} ({} workers)",
+        args.addr, args.max_concurrent
+    );
+
+    thread::scope(|scope| {
+        for _ in 0..args.max_concurrent {
+            scope.spawn(|| {
+                while let Ok(request) = server.recv() {
+                    handle(request, &args);
+                }
+            });
+        }
+    });
+    ExitCode::SUCCESS
+}
+
+fn parse_args(raw: Vec<String>) -> Result<ServerArgs, String> {
+    let mut args = ServerArgs::default();
+    let mut iter = raw.into_iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--addr" => args.addr = next_value(&arg, iter.next())?,
+            "--max-body-bytes" => args.max_body_bytes = next_number(&arg, iter.next())?,
+            "--max-concurrent" => args.max_concurrent = next_number(&arg, iter.next())?,
+            "--max-batch-runs" => args.max_batch_runs = next_number(&arg, iter.next())?,
+            "--max-ticks" => args.limits.max_ticks = next_number(&arg, iter.next())?,
+            "--max-events" => args.limits.max_events = next_number(&arg, iter.next())?,
+            "--max-request-ticks" => args.max_request_ticks = next_number(&arg, iter.next())?,
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+    if args.max_concurrent == 0 {
+        return Err("--max-concurrent must be at least 1".into());
+    }
+    Ok(args)
+}
+
+fn next_value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("expected value after {flag}"))
+}
+
+fn next_number<T>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    <T as std::str::FromStr>::Err: std::fmt::Display,
+{
+    next_value(flag, value)?
+        .parse()
+        .map_err(|err| format!("failed to parse {flag}: {err}"))
+}
+
+/// The parts of an HTTP request the routes read, so routing can be tested without a
+/// socket.
+trait ApiRequest {
+    fn method(&self) -> &Method;
+    fn url(&self) -> &str;
+    /// The declared `Content-Length`, if any.
+    fn body_length(&self) -> Option<usize>;
+    fn body(&mut self) -> &mut dyn Read;
+}
+
+impl ApiRequest for Request {
+    fn method(&self) -> &Method {
+        Request::method(self)
+    }
+
+    fn url(&self) -> &str {
+        Request::url(self)
+    }
+
+    fn body_length(&self) -> Option<usize> {
+        Request::body_length(self)
+    }
+
+    fn body(&mut self) -> &mut dyn Read {
+        self.as_reader()
+    }
+}
+
+fn handle(mut request: Request, args: &ServerArgs) {
+    let (status, body) = reply(&mut request, args);
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let response = Response::from_string(body)
+        .with_status_code(StatusCode(status))
+        .with_header(content_type);
+    // The client may already have gone away; nothing useful to do about it.
+    let _ = request.respond(response);
+}
+
+/// The status and JSON body answering `request`.
+fn reply(request: &mut impl ApiRequest, args: &ServerArgs) -> (u16, String) {
+    match route(request, args) {
+        Ok(body) => (200, body),
+        Err(error) => (error.status, to_json(&ErrorReply { error: error.info })),
+    }
+}
+
+fn route(request: &mut impl ApiRequest, args: &ServerArgs) -> Result<String, ApiError> {
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    let method = request.method().clone();
+    match (path.as_str(), method) {
+        ("/version", Method::Get) => Ok(to_json(&version_info())),
+        ("/simulate", Method::Post) => {
+            let body: WithDungeon<SimulateParams> = read_json(request, args)?;
+            check_limits(&body.params.config.limits, args)?;
+            Ok(to_json(&body.params.run(body.dungeon)?))
+        }
+        ("/validate", Method::Post) => {
+            let body: WithDungeon<ValidateParams> = read_json(request, args)?;
+            Ok(to_json(&body.params.run(&body.dungeon)))
+        }
+        ("/batch", Method::Post) => {
+            let mut body: WithDungeon<BatchParams> = read_json(request, args)?;
+            if body.params.runs > args.max_batch_runs {
+                return Err(ApiError::new(
+                    413,
+                    ErrorKind::TooLarge,
+                    format!("runs exceeds the limit of {}", args.max_batch_runs),
+                ));
+            }
+            let config = &body.params.config;
+            check_limits(&config.limits, args)?;
+            let max_ticks = body.params.max_ticks.unwrap_or(config.limits.max_ticks);
+            check_request_ticks(body.params.runs, max_ticks, args)?;
+            body.params.threads = 1;
+            Ok(to_json(&body.params.run(&body.dungeon)?))
+        }
+        ("/verify-run", Method::Post) => {
+            let body: WithContent<VerifyParams> = read_json(request, args)?;
+            if let Some(template) = &body.content.run {
+                let rules = &template.rules;
+                check_limits(&rules.config.limits, args)?;
+                check_request_ticks(template.waves.len() as u64, rules.max_ticks_per_wave, args)?;
+            }
+            Ok(to_json(&body.params.run(&body.content)))
+        }
+        ("/version" | "/simulate" | "/validate" | "/batch" | "/verify-run", _) => {
+            Err(ApiError::new(
+                405,
+                ErrorKind::MethodNotAllowed,
+                format!("wrong method for {path}"),
+            ))
+        }
+        _ => Err(ApiError::new(
+            404,
+            ErrorKind::NotFound,
+            format!("no route {path}"),
+        )),
+    }
+}
+
+/// Refuse request limits above the server's, so a client cannot lift them.
+fn check_limits(limits: &SimulationLimits, args: &ServerArgs) -> Result<(), ApiError> {
+    let ceiling = &args.limits;
+    let fields = [
+        (
+            "max_units",
+            limits.max_units as u64,
+            ceiling.max_units as u64,
+        ),
+        (
+            "max_ticks",
+            u64::from(limits.max_ticks),
+            u64::from(ceiling.max_ticks),
+        ),
+        (
+            "max_events",
+            limits.max_events as u64,
+            ceiling.max_events as u64,
+        ),
+        (
+            "max_heroes_per_wave",
+            u64::from(limits.max_heroes_per_wave),
+            u64::from(ceiling.max_heroes_per_wave),
+        ),
+        (
+            "max_entities_per_room",
+            limits.max_entities_per_room as u64,
+            ceiling.max_entities_per_room as u64,
+        ),
+        (
+            "max_rooms",
+            limits.max_rooms as u64,
+            ceiling.max_rooms as u64,
+        ),
+        (
+            "max_edges",
+            limits.max_edges as u64,
+            ceiling.max_edges as u64,
+        ),
+        (
+            "max_traps",
+            limits.max_traps as u64,
+            ceiling.max_traps as u64,
+        ),
+    ];
+    match fields.into_iter().find(|(_, value, max)| value > max) {
+        Some((name, _, max)) => Err(ApiError::new(
+            413,
+            ErrorKind::TooLarge,
+            format!("limits.{name} exceeds the server limit of {max}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Refuse requests that would simulate more than `max_request_ticks` ticks in total.
+fn check_request_ticks(waves: u64, max_ticks: u32, args: &ServerArgs) -> Result<(), ApiError> {
+    if waves.saturating_mul(u64::from(max_ticks)) > args.max_request_ticks {
+        return Err(ApiError::new(
+            413,
+            ErrorKind::TooLarge,
+            format!(
+                "{waves} waves of up to {max_ticks} ticks exceed the server limit of {} ticks",
+                args.max_request_ticks
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Parse the body as JSON, refusing bodies over `max_body_bytes` whether or not they
+/// declare a length.
+fn read_json<T: DeserializeOwned>(
+    request: &mut impl ApiRequest,
+    args: &ServerArgs,
+) -> Result<T, ApiError> {
+    let too_large = || {
+        ApiError::new(
+            413,
+            ErrorKind::TooLarge,
+            format!("request body exceeds {} bytes", args.max_body_bytes),
+        )
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > args.max_body_bytes)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    request
+        .body()
+        .take(args.max_body_bytes as u64 + 1)
+        .read_to_end(&mut body)
+        .map_err(|err| {
+            ApiError::new(
+                400,
+                ErrorKind::Request,
+                format!("failed to read body: {err}"),
+            )
+        })?;
+    if body.len() > args.max_body_bytes {
+        return Err(too_large());
+    }
+    serde_json::from_slice(&body).map_err(|err| {
+        ApiError::new(
+            400,
+            ErrorKind::Request,
+            format!("invalid request body: {err}"),
+        )
+    })
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("responses serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,231 @@
+use std::io::{Cursor, Read};
+
+use engine::model::{ContentPack, RunTemplate};
+use engine::sim::{RunRules, SimulationConfig, test_fixtures};
+use serde_json::{Value, json};
+use tiny_http::Method;
+
+use super::{ApiRequest, ServerArgs, reply};
+
+struct TestRequest {
+    method: Method,
+    url: String,
+    declared_length: Option<usize>,
+    body: Cursor<Vec<u8>>,
+}
+
+impl ApiRequest for TestRequest {
+    fn method(&self) -> &Method {
+        &self.method
+    }
+
+    fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn body_length(&self) -> Option<usize> {
+        self.declared_length
+    }
+
+    fn body(&mut self) -> &mut dyn Read {
+        &mut self.body
+    }
+}
+
+fn request(method: Method, url: &str, body: Vec<u8>) -> TestRequest {
+    TestRequest {
+        method,
+        url: url.into(),
+        declared_length: Some(body.len()),
+        body: Cursor::new(body),
+    }
+}
+
+/// Route `request` and return the status and parsed reply.
+fn send(mut request: TestRequest, args: &ServerArgs) -> (u16, Value) {
+    let (status, body) = reply(&mut request, args);
+    (
+        status,
+        serde_json::from_str(&body).expect("replies are JSON"),
+    )
+}
+
+fn post(url: &str, body: &Value, args: &ServerArgs) -> (u16, Value) {
+    send(
+        request(Method::Post, url, body.to_string().into_bytes()),
+        args,
+    )
+}
+
+fn duel() -> (Value, Value) {
+    let fixture = test_fixtures::core_room_duel();
+    (
+        serde_json::to_value(&fixture.dungeon).unwrap(),
+        serde_json::to_value(&fixture.wave).unwrap(),
+    )
+}
+
+fn assert_error(reply: (u16, Value), status: u16, kind: &str) {
+    assert_eq!(status, reply.0, "{}", reply.1);
+    let error = reply.1["error"].as_object().expect("an error object");
+    assert_eq!(kind, error["kind"]);
+    assert!(error["message"].is_string());
+    assert!(
+        error
+            .keys()
+            .all(|key| ["kind", "message", "validation"].contains(&key.as_str()))
+    );
+}
+
+#[test]
+fn oversized_bodies_are_refused_with_or_without_a_length() {
+    let args = ServerArgs {
+        max_body_bytes: 64,
+        ..ServerArgs::default()
+    };
+    let big = vec![b' '; 65];
+
+    let mut declared = request(Method::Post, "/validate", b"{}".to_vec());
+    declared.declared_length = Some(65);
+    assert_error(send(declared, &args), 413, "too_large");
+
+    let mut chunked = request(Method::Post, "/validate", big);
+    chunked.declared_length = None;
+    assert_error(send(chunked, &args), 413, "too_large");
+}
+
+#[test]
+fn unknown_routes_and_methods_are_404_and_405() {
+    let args = ServerArgs::default();
+    assert_eq!(
+        (
+            404,
+            json!({ "error": { "kind": "not_found", "message": "no route /nowhere" } })
+        ),
+        send(request(Method::Get, "/nowhere", Vec::new()), &args)
+    );
+    assert_error(
+        send(request(Method::Get, "/simulate", Vec::new()), &args),
+        405,
+        "method_not_allowed",
+    );
+
+    let (status, body) = send(request(Method::Get, "/version?x=1", Vec::new()), &args);
+    assert_eq!(200, status);
+    assert!(body["engine_version"].is_string());
+}
+
+#[test]
+fn malformed_and_rejected_inputs_use_the_error_object() {
+    let args = ServerArgs::default();
+    let garbage = request(Method::Post, "/simulate", b"{".to_vec());
+    assert_error(send(garbage, &args), 400, "request");
+
+    let (dungeon, mut wave) = duel();
+    wave["entries"][0]["spawn_room_id"] = json!(99);
+    let (status, body) = post(
+        "/simulate",
+        &json!({ "dungeon": dungeon, "wave": wave, "seed": 1 }),
+        &args,
+    );
+    assert!(
+        !body["error"]["validation"]["issues"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+    assert_error((status, body), 422, "simulation");
+}
+
+#[test]
+fn batches_are_capped_by_runs_and_total_ticks() {
+    let args = ServerArgs {
+        max_batch_runs: 10,
+        max_request_ticks: 1_000,
+        ..ServerArgs::default()
+    };
+    let (dungeon, wave) = duel();
+    let batch = |runs: u64, max_ticks: u32| {
+        json!({
+            "dungeon": dungeon,
+            "wave": wave,
+            "runs": runs,
+            "max_ticks": max_ticks
+        })
+    };
+
+    assert_error(post("/batch", &batch(11, 10), &args), 413, "too_large");
+    assert_error(post("/batch", &batch(10, 101), &args), 413, "too_large");
+    let (status, body) = post("/batch", &batch(10, 100), &args);
+    assert_eq!(200, status, "{body}");
+    assert_eq!(10, body["summaries"].as_array().unwrap().len());
+}
+
+#[test]
+fn request_limits_cannot_exceed_the_server_ceilings() {
+    let mut args = ServerArgs::default();
+    args.limits.max_ticks = 100;
+    args.limits.max_events = 500;
+    let (dungeon, wave) = duel();
+    let simulate = |limits: Value| {
+        let mut config = serde_json::to_value(SimulationConfig::default()).unwrap();
+        config["limits"] = limits;
+        json!({ "dungeon": dungeon, "wave": wave, "seed": 1, "config": config })
+    };
+    let mut limits = serde_json::to_value(&args.limits).unwrap();
+
+    let (status, body) = post("/simulate", &simulate(limits.clone()), &args);
+    assert_eq!(200, status, "{body}");
+    limits["max_events"] = json!(501);
+    assert_error(
+        post("/simulate", &simulate(limits.clone()), &args),
+        413,
+        "too_large",
+    );
+    limits["max_events"] = json!(500);
+    limits["max_ticks"] = json!(60_000);
+    assert_error(
+        post("/simulate", &simulate(limits), &args),
+        413,
+        "too_large",
+    );
+}
+
+#[test]
+fn run_templates_are_held_to_the_server_ceilings() {
+    let fixture = test_fixtures::core_room_duel();
+    let mut rules = RunRules::default();
+    rules.config.limits.max_ticks = 500;
+    rules.max_ticks_per_wave = 500;
+    let content = ContentPack {
+        run: Some(RunTemplate {
+            starting_dungeon: fixture.dungeon.clone(),
+            waves: vec![fixture.wave.clone(); 3],
+            rules,
+            draft_rules: Default::default(),
+        }),
+        ..ContentPack::default()
+    };
+    let submission = json!({
+        "content_version": "1.0.0",
+        "engine_version": engine::ENGINE_VERSION,
+        "seed": 1,
+        "score": 0,
+        "outcome": "DungeonWin",
+        "run_summary": { "waves_cleared": 3, "core_hp_remaining": 0, "draft_choices": [] }
+    });
+    let body = json!({ "content": content, "submission": submission });
+
+    let mut args = ServerArgs {
+        max_request_ticks: 1_499,
+        ..ServerArgs::default()
+    };
+    assert_error(post("/verify-run", &body, &args), 413, "too_large");
+    args.max_request_ticks = 1_500;
+    args.limits.max_ticks = 499;
+    assert_error(post("/verify-run", &body, &args), 413, "too_large");
+    args.limits.max_ticks = 500;
+    let (status, report) = post("/verify-run", &body, &args);
+    assert_eq!(200, status, "{report}");
+    assert!(report["valid"].is_boolean());
+}
@@ -0,0 +1,364 @@
+//! Deterministic draft offers and picks.
+//!
+//! Between waves the player is offered a few content entries (rooms, traps, monsters and
+//! relics) drawn from the [`ContentPack`] with rarity weighting, picks one, and the pick is
+//! applied to the [`DungeonState`]. Offers depend only on the content pack, the run seed,
+//! the draft index and the tier, so the client (through wasm) and the backend replaying a
+//! run (contract/phase4.md §6.1) produce identical offers and dungeons.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::SimError;
+use crate::model::{
+    AiBehavior, ContentPack, DungeonState, Faction, Rarity, RoomId, RoomState, TrapId,
+    TrapInstance, UnitId, UnitInstance,
+};
+use crate::rng::{Rng, derive_seed};
+
+/// Stream id separating draft seeds from the per-wave seeds of a run.
+const DRAFT_STREAM: u64 = 0x4452_4146_5400_0000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Relative draft weight per rarity. A weight of 0 removes that rarity from offers.
+pub struct RarityWeights {
+    pub common: u32,
+    pub uncommon: u32,
+    pub rare: u32,
+    pub epic: u32,
+}
+
+impl RarityWeights {
+    pub fn weight(&self, rarity: Rarity) -> u32 {
+        match rarity {
+            Rarity::Common => self.common,
+            Rarity::Uncommon => self.uncommon,
+            Rarity::Rare => self.rare,
+            Rarity::Epic => self.epic,
+        }
+    }
+}
+
+impl Default for RarityWeights {
+    fn default() -> Self {
+        Self {
+            common: 60,
+            uncommon: 25,
+            rare: 10,
+            epic: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Knobs for offer generation.
+pub struct DraftRules {
+    /// Options per offer; fewer are offered when the eligible pool is smaller.
+    pub options_per_offer: usize,
+    pub rarity_weights: RarityWeights,
+}
+
+impl Default for DraftRules {
+    fn default() -> Self {
+        Self {
+            options_per_offer: 3,
+            rarity_weights: RarityWeights::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+/// One entry of an offer, naming content by its content pack id.
+pub enum DraftOption {
+    AddRoom { template_id: String, rarity: Rarity },
+    AddTrap { template_id: String, rarity: Rarity },
+    AddMonster { template_id: String, rarity: Rarity },
+    GlobalModifier { relic_id: String, rarity: Rarity },
+}
+
+impl DraftOption {
+    /// Content pack id of the offered entry.
+    pub fn content_id(&self) -> &str {
+        match self {
+            DraftOption::AddRoom { template_id, .. }
+            | DraftOption::AddTrap { template_id, .. }
+            | DraftOption::AddMonster { template_id, .. } => template_id,
+            DraftOption::GlobalModifier { relic_id, .. } => relic_id,
+        }
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        match self {
+            DraftOption::AddRoom { rarity, .. }
+            | DraftOption::AddTrap { rarity, .. }
+            | DraftOption::AddMonster { rarity, .. }
+            | DraftOption::GlobalModifier { rarity, .. } => *rarity,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub struct DraftOffer {
+    pub draft_index: u32,
+    pub tier: u32,
+    pub options: Vec<DraftOption>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+/// The player's choice from a [`DraftOffer`].
+///
+/// # JSON example
+/// ```json
+/// { "option": 1, "target_room": 4 }
+/// ```
+pub struct DraftPick {
+    /// Index into [`DraftOffer::options`].
+    pub option: usize,
+    /// Room receiving a trap or monster, or the existing room a new room connects to.
+    /// Ignored for global modifiers.
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub target_room: Option<RoomId>,
+}
+
+/// Seed of the RNG used for draft number `draft_index` of a run.
+pub fn draft_seed(run_seed: u64, draft_index: u32) -> u64 {
+    derive_seed(derive_seed(run_seed, DRAFT_STREAM), u64::from(draft_index))
+}
+
+/// Generate the offer for draft number `draft_index` at `tier`.
+///
+/// Eligible entries are those with `unlock_tier <= tier` and a non-zero rarity weight,
+/// ordered by kind (rooms, traps, monsters, relics) and then id so the pack's array order
+/// does not matter. Options are drawn by weight without replacement.
+pub fn generate_offer(
+    content: &ContentPack,
+    run_seed: u64,
+    draft_index: u32,
+    tier: u32,
+    rules: &DraftRules,
+) -> DraftOffer {
+    let mut pool = eligible_options(content, tier, &rules.rarity_weights);
+    let mut rng = Rng::new(draft_seed(run_seed, draft_index));
+    let mut options = Vec::new();
+    while options.len() < rules.options_per_offer && !pool.is_empty() {
+        let total: u64 = pool.iter().map(|(_, weight)| u64::from(*weight)).sum();
+        let mut roll = rng.gen_below(total);
+        let idx = pool
+            .iter()
+            .position(|(_, weight)| {
+                let weight = u64::from(*weight);
+                if roll < weight {
+                    true
+                } else {
+                    roll -= weight;
+                    false
+                }
+            })
+            .expect("roll is below the total weight");
+        options.push(pool.remove(idx).0);
+    }
+
+    DraftOffer {
+        draft_index,
+        tier,
+        options,
+    }
+}
+
+fn eligible_options(
+    content: &ContentPack,
+    tier: u32,
+    weights: &RarityWeights,
+) -> Vec<(DraftOption, u32)> {
+    let mut rooms: Vec<_> = content
+        .rooms
+        .iter()
+        .filter(|r| r.unlock_tier <= tier)
+        .map(|r| (&r.id, r.rarity))
+        .collect();
+    let mut traps: Vec<_> = content
+        .traps
+        .iter()
+        .filter(|t| t.unlock_tier <= tier)
+        .map(|t| (&t.id, t.rarity))
+        .collect();
+    let mut monsters: Vec<_> = content
+        .monsters
+        .iter()
+        .filter(|m| m.unlock_tier <= tier)
+        .map(|m| (&m.id, m.rarity))
+        .collect();
+    let mut relics: Vec<_> = content
+        .relics
+        .iter()
+        .filter(|r| r.unlock_tier <= tier)
+        .map(|r| (&r.id, r.rarity))
+        .collect();
+    for list in [&mut rooms, &mut traps, &mut monsters, &mut relics] {
+        list.sort();
+    }
+
+    let rooms = rooms.into_iter().map(|(id, rarity)| DraftOption::AddRoom {
+        template_id: id.clone(),
+        rarity,
+    });
+    let traps = traps.into_iter().map(|(id, rarity)| DraftOption::AddTrap {
+        template_id: id.clone(),
+        rarity,
+    });
+    let monsters = monsters
+        .into_iter()
+        .map(|(id, rarity)| DraftOption::AddMonster {
+            template_id: id.clone(),
+            rarity,
+        });
+    let relics = relics
+        .into_iter()
+        .map(|(id, rarity)| DraftOption::GlobalModifier {
+            relic_id: id.clone(),
+            rarity,
+        });
+
+    rooms
+        .chain(traps)
+        .chain(monsters)
+        .chain(relics)
+        .filter_map(|option| {
+            let weight = weights.weight(option.rarity());
+            (weight > 0).then_some((option, weight))
+        })
+        .collect()
+}
+
+/// Apply `pick` from `offer` to `dungeon`.
+///
+/// New rooms, traps and monsters get the next id above the highest one already in the
+/// dungeon. On error the dungeon is left unchanged.
+pub fn apply_pick(
+    dungeon: &mut DungeonState,
+    content: &ContentPack,
+    offer: &DraftOffer,
+    pick: &DraftPick,
+) -> Result<(), SimError> {
+    let option = offer.options.get(pick.option).ok_or_else(|| {
+        invalid(format!(
+            "option {} out of range for an offer of {}",
+            pick.option,
+            offer.options.len()
+        ))
+    })?;
+
+    match option {
+        DraftOption::AddRoom { template_id, .. } => {
+            let config = content
+                .room(template_id)
+                .ok_or_else(|| invalid(format!("unknown room template {template_id}")))?;
+            let connect_to = target_room(dungeon, pick)?;
+            let id = next_id(dungeon.rooms.iter().map(|r| r.id.0))?;
+            dungeon.rooms.push(RoomState {
+                id: RoomId(id),
+                traps: Vec::new(),
+                monsters: Vec::new(),
+                tags: config.tags.clone(),
+                template_id: Some(config.id.clone()),
+            });
+            dungeon.edges.push((connect_to, RoomId(id)));
+        }
+        DraftOption::AddTrap { template_id, .. } => {
+            let config = content
+                .trap(template_id)
+                .ok_or_else(|| invalid(format!("unknown trap template {template_id}")))?;
+            let room_id = target_room(dungeon, pick)?;
+            let id = next_id(
+                dungeon
+                    .rooms
+                    .iter()
+                    .flat_map(|r| r.traps.iter().map(|t| t.id.0)),
+            )?;
+            let room = room_mut(dungeon, room_id);
+            room.traps.push(TrapInstance {
+                id: TrapId(id),
+                trigger_type: config.trigger_type.clone(),
+                cooldown_ticks: config.cooldown_ticks,
+                cooldown_remaining: 0,
+                max_charges: config.max_charges,
+                charges_used: 0,
+                damage: config.base_damage,
+                status_on_hit: config.status_on_hit.clone(),
+                tags: config.tags.clone(),
+                template_id: Some(config.id.clone()),
+            });
+        }
+        DraftOption::AddMonster { template_id, .. } => {
+            let config = content
+                .monster(template_id)
+                .ok_or_else(|| invalid(format!("unknown monster template {template_id}")))?;
+            let room_id = target_room(dungeon, pick)?;
+            let id = next_id(
+                dungeon
+                    .rooms
+                    .iter()
+                    .flat_map(|r| r.monsters.iter().map(|m| m.id.0)),
+            )?;
+            let room = room_mut(dungeon, room_id);
+            room.monsters.push(UnitInstance {
+                id: UnitId(id),
+                faction: Faction::Monster,
+                stats: config.stats.clone(),
+                hp: config.stats.max_hp,
+                room_id,
+                status_effects: Vec::new(),
+                ai_behavior: AiBehavior::Aggressive,
+                attack_cooldown: 0,
+                template_id: Some(config.id.clone()),
+            });
+        }
+        DraftOption::GlobalModifier { relic_id, .. } => {
+            if content.relic(relic_id).is_none() {
+                return Err(invalid(format!("unknown relic {relic_id}")));
+            }
+            dungeon.modifiers.push(relic_id.clone());
+        }
+    }
+    Ok(())
+}
+
+fn invalid(message: String) -> SimError {
+    SimError::InvalidDraftPick(message)
+}
+
+fn target_room(dungeon: &DungeonState, pick: &DraftPick) -> Result<RoomId, SimError> {
+    let room_id = pick
+        .target_room
+        .ok_or_else(|| invalid("pick needs a target_room".into()))?;
+    if dungeon.rooms.iter().any(|r| r.id == room_id) {
+        Ok(room_id)
+    } else {
+        Err(invalid(format!("target room {} does not exist", room_id.0)))
+    }
+}
+
+fn room_mut(dungeon: &mut DungeonState, room_id: RoomId) -> &mut RoomState {
+    dungeon
+        .rooms
+        .iter_mut()
+        .find(|r| r.id == room_id)
+        .expect("target_room checked the room exists")
+}
+
+fn next_id(ids: impl Iterator<Item = u32>) -> Result<u32, SimError> {
+    match ids.max() {
+        None => Ok(0),
+        Some(max) => max.checked_add(1).ok_or(SimError::EntityLimit),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+
+use crate::draft::{
+    DraftOffer, DraftOption, DraftPick, DraftRules, RarityWeights, apply_pick, generate_offer,
+};
+use crate::error::SimError;
+use crate::model::{
+    ContentPack, DungeonState, HeroSpawn, Rarity, RoomId, RoomState, TrapId, UnitId, WaveConfig,
+};
+use crate::sim::validate;
+
+fn content() -> ContentPack {
+    serde_json::from_str(
+        r#"{
+            "version": "1.0.0",
+            "rooms": [
+                { "id": "room_corridor", "name": "Corridor", "tags": ["corridor"] },
+                { "id": "room_vault", "name": "Vault", "rarity": "rare", "unlock_tier": 2 }
+            ],
+            "traps": [
+                { "id": "trap_spikes", "name": "Spikes", "base_damage": 4, "cooldown_ticks": 2,
+                  "trigger_type": "on_enter" },
+                { "id": "trap_fire_glyph", "name": "Fire Glyph", "base_damage": 15,
+                  "cooldown_ticks": 10, "trigger_type": "on_enter", "rarity": "uncommon",
+                  "tags": ["fire"] },
+                { "id": "trap_inferno", "name": "Inferno", "base_damage": 40,
+                  "cooldown_ticks": 20, "trigger_type": "timed", "rarity": "epic",
+                  "unlock_tier": 3 }
+            ],
+            "monsters": [
+                { "id": "monster_rat", "name": "Rat", "max_hp": 8, "armor": 0,
+                  "move_speed": 0.0, "attack_damage": 2, "attack_interval_ticks": 1,
+                  "attack_range": 0 },
+                { "id": "monster_ember_guard", "name": "Ember Guard", "rarity": "rare",
+                  "max_hp": 100, "armor": 5, "move_speed": 0.0, "attack_damage": 10,
+                  "attack_interval_ticks": 6, "attack_range": 1 }
+            ],
+            "relics": [
+                { "id": "relic_furnace_core", "name": "Furnace Core", "rarity": "epic",
+                  "effect_type": "global_multiplier", "effect_target_tag": "fire",
+                  "effect_multiplier": 1.2 }
+            ]
+        }"#,
+    )
+    .expect("content pack should parse")
+}
+
+fn dungeon() -> DungeonState {
+    let room = |id| RoomState {
+        id: RoomId(id),
+        traps: Vec::new(),
+        monsters: Vec::new(),
+        tags: Vec::new(),
+        template_id: None,
+    };
+    DungeonState {
+        rooms: vec![room(0), room(1)],
+        edges: vec![(RoomId(0), RoomId(1))],
+        core_room_id: RoomId(1),
+        core_hp: 50,
+        modifiers: Vec::new(),
+    }
+}
+
+#[test]
+fn offers_are_deterministic_distinct_and_tier_gated() {
+    let content = content();
+    let rules = DraftRules::default();
+    for draft_index in 0..50 {
+        let offer = generate_offer(&content, 77, draft_index, 1, &rules);
+        assert_eq!(offer, generate_offer(&content, 77, draft_index, 1, &rules));
+        assert_eq!(3, offer.options.len());
+
+        let ids: HashSet<&str> = offer.options.iter().map(DraftOption::content_id).collect();
+        assert_eq!(3, ids.len(), "options are drawn without replacement");
+        assert!(!ids.contains("room_vault"), "room_vault unlocks at tier 2");
+        assert!(
+            !ids.contains("trap_inferno"),
+            "trap_inferno unlocks at tier 3"
+        );
+    }
+
+    let mut shuffled = content.clone();
+    shuffled.traps.reverse();
+    shuffled.rooms.reverse();
+    assert_eq!(
+        generate_offer(&content, 5, 3, 3, &rules),
+        generate_offer(&shuffled, 5, 3, 3, &rules),
+        "pack array order must not change offers"
+    );
+}
+
+#[test]
+fn offers_follow_rarity_weights() {
+    let content = content();
+    let rules = DraftRules {
+        options_per_offer: 2,
+        rarity_weights: RarityWeights {
+            common: 0,
+            uncommon: 0,
+            rare: 1,
+            epic: 0,
+        },
+    };
+    let offer = generate_offer(&content, 1, 0, 3, &rules);
+    let ids: Vec<&str> = offer.options.iter().map(DraftOption::content_id).collect();
+    assert_eq!(2, ids.len());
+    assert!(offer.options.iter().all(|o| o.rarity() == Rarity::Rare));
+
+    let rules = DraftRules {
+        options_per_offer: 1,
+        ..DraftRules::default()
+    };
+    let mut common = 0;
+    let mut epic = 0;
+    for draft_index in 0..400 {
+        match generate_offer(&content, 9, draft_index, 3, &rules).options[0].rarity() {
+            Rarity::Common => common += 1,
+            Rarity::Epic => epic += 1,
+            _ => {}
+        }
+    }
+    assert!(common > epic * 3, "common {common} vs epic {epic}");
+}
+
+/// Offers are part of the run verification contract: a change here means the backend can
+/// no longer replay runs recorded by older clients.
+#[test]
+fn offers_are_pinned_for_a_known_seed() {
+    let offer = generate_offer(&content(), 42, 0, 3, &DraftRules::default());
+    let ids: Vec<&str> = offer.options.iter().map(DraftOption::content_id).collect();
+    assert_eq!(vec!["trap_inferno", "monster_rat", "room_corridor"], ids);
+}
+
+#[test]
+fn picks_extend_the_dungeon_deterministically() {
+    let content = content();
+    let offer = DraftOffer {
+        draft_index: 0,
+        tier: 3,
+        options: vec![
+            DraftOption::AddRoom {
+                template_id: "room_corridor".into(),
+                rarity: Rarity::Common,
+            },
+            DraftOption::AddTrap {
+                template_id: "trap_fire_glyph".into(),
+                rarity: Rarity::Uncommon,
+            },
+            DraftOption::AddMonster {
+                template_id: "monster_rat".into(),
+                rarity: Rarity::Common,
+            },
+            DraftOption::GlobalModifier {
+                relic_id: "relic_furnace_core".into(),
+                rarity: Rarity::Epic,
+            },
+        ],
+    };
+    let pick = |option, room: Option<u32>| DraftPick {
+        option,
+        target_room: room.map(RoomId),
+    };
+
+    let mut dungeon = dungeon();
+    apply_pick(&mut dungeon, &content, &offer, &pick(0, Some(0))).unwrap();
+    apply_pick(&mut dungeon, &content, &offer, &pick(1, Some(2))).unwrap();
+    apply_pick(&mut dungeon, &content, &offer, &pick(1, Some(2))).unwrap();
+    apply_pick(&mut dungeon, &content, &offer, &pick(2, Some(1))).unwrap();
+    apply_pick(&mut dungeon, &content, &offer, &pick(3, None)).unwrap();
+
+    let new_room = &dungeon.rooms[2];
+    assert_eq!(RoomId(2), new_room.id);
+    assert_eq!(Some("room_corridor"), new_room.template_id.as_deref());
+    assert_eq!(vec!["corridor".to_string()], new_room.tags);
+    assert!(dungeon.edges.contains(&(RoomId(0), RoomId(2))));
+    let trap_ids: Vec<TrapId> = new_room.traps.iter().map(|t| t.id).collect();
+    assert_eq!(vec![TrapId(0), TrapId(1)], trap_ids);
+    assert_eq!(15, new_room.traps[0].damage);
+    let rat = &dungeon.rooms[1].monsters[0];
+    assert_eq!((UnitId(0), RoomId(1), 8), (rat.id, rat.room_id, rat.hp));
+    assert_eq!(vec!["relic_furnace_core".to_string()], dungeon.modifiers);
+
+    let wave = WaveConfig {
+        id: "after-draft".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id: RoomId(2),
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+    let report = validate(&dungeon, &wave);
+    assert!(!report.has_errors(), "{report}");
+
+    let before = dungeon.clone();
+    for bad in [pick(9, Some(0)), pick(1, None), pick(2, Some(7))] {
+        let err = apply_pick(&mut dungeon, &content, &offer, &bad).expect_err("pick is invalid");
+        assert!(matches!(err, SimError::InvalidDraftPick(_)), "{err:?}");
+    }
+    assert_eq!(before, dungeon, "failed picks leave the dungeon untouched");
+}
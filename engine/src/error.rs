@@ -1,4 +1,4 @@
-#[derive(Debug, thiserror::Error)]
+#[derive(Clone, Debug, thiserror::Error)]
 pub enum SimError {
     #[error("Entity limit exceeded")]
     EntityLimit,
@@ -6,6 +6,16 @@ pub enum SimError {
     EventLimit,
     #[error("Tick limit exceeded")]
     TickLimit,
-    #[error("Invalid dungeon: {0}")]
-    InvalidDungeon(String),
+    #[error("Hero limit exceeded")]
+    HeroLimit,
+    #[error("Room limit exceeded")]
+    RoomLimit,
+    #[error("Edge limit exceeded")]
+    EdgeLimit,
+    #[error("Trap limit exceeded")]
+    TrapLimit,
+    #[error("Invalid draft pick: {0}")]
+    InvalidDraftPick(String),
+    #[error("Invalid input:\n{0}")]
+    InvalidInput(crate::sim::validation::ValidationReport),
 }
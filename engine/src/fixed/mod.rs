@@ -0,0 +1,180 @@
+//! Fixed-point numbers for rules math.
+//!
+//! Float rules math is only as portable as every float operation it will ever use
+//! (transcendental functions differ between libm implementations, for instance), and the
+//! backend must reproduce the client bit for bit. Rules math therefore uses [`Fixed`], a
+//! decimal fixed-point number with a resolution of one thousandth, and names its rounding
+//! mode wherever it converts back to integers. JSON keeps plain decimal numbers (`1.1`,
+//! `0.25`); they are rounded to the nearest thousandth when parsed, which is the only
+//! place a float is involved.
+
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub};
+
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::Schema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ts_rs::TS;
+
+/// Units per 1.0.
+pub const SCALE: i64 = 1000;
+
+/// How a result that falls between two representable values is resolved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// To the nearest value, halves away from zero (`2.5 -> 3`, `-2.5 -> -3`).
+    HalfAwayFromZero,
+    /// Drop the fraction (`2.9 -> 2`, `-2.9 -> -2`).
+    TowardZero,
+    /// Toward negative infinity (`2.9 -> 2`, `-2.1 -> -3`).
+    Floor,
+}
+
+/// A signed decimal number stored as thousandths in an `i64`. Arithmetic saturates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, TS)]
+#[ts(type = "number")]
+pub struct Fixed(i64);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(SCALE);
+
+    pub const fn from_int(value: i32) -> Self {
+        Fixed(value as i64 * SCALE)
+    }
+
+    /// `milli` thousandths, e.g. `from_milli(1100)` is 1.1.
+    pub const fn from_milli(milli: i64) -> Self {
+        Fixed(milli)
+    }
+
+    pub const fn milli(self) -> i64 {
+        self.0
+    }
+
+    /// Nearest thousandth to `value`, halves away from zero. Non-finite values map to
+    /// zero; out-of-range values saturate.
+    pub fn from_f64(value: f64) -> Self {
+        if !value.is_finite() {
+            return Fixed::ZERO;
+        }
+        Fixed((value * SCALE as f64).round() as i64)
+    }
+
+    /// For display and serialization only; never feed the result back into rules math.
+    pub fn to_f64(self) -> f64 {
+        self.0 as f64 / SCALE as f64
+    }
+
+    /// `self * rhs`, rounded to a thousandth with `rounding`.
+    pub fn mul(self, rhs: Fixed, rounding: Rounding) -> Fixed {
+        Fixed(div_round(
+            i128::from(self.0) * i128::from(rhs.0),
+            i128::from(SCALE),
+            rounding,
+        ))
+    }
+
+    /// Integer value of `self` under `rounding`, saturating at the `i32` bounds.
+    pub fn to_int(self, rounding: Rounding) -> i32 {
+        let value = div_round(i128::from(self.0), i128::from(SCALE), rounding);
+        value.clamp(i64::from(i32::MIN), i64::from(i32::MAX)) as i32
+    }
+}
+
+fn div_round(numerator: i128, denominator: i128, rounding: Rounding) -> i64 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    let rounded = match rounding {
+        Rounding::TowardZero => quotient,
+        Rounding::Floor if remainder < 0 => quotient - 1,
+        Rounding::Floor => quotient,
+        Rounding::HalfAwayFromZero if remainder.abs() * 2 >= denominator => {
+            quotient + numerator.signum()
+        }
+        Rounding::HalfAwayFromZero => quotient,
+    };
+    rounded.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+}
+
+impl Add for Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Fixed {
+    fn add_assign(&mut self, rhs: Fixed) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Fixed) -> Fixed {
+        Fixed(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed(self.0.saturating_neg())
+    }
+}
+
+impl Sum for Fixed {
+    fn sum<I: Iterator<Item = Fixed>>(iter: I) -> Fixed {
+        iter.fold(Fixed::ZERO, Add::add)
+    }
+}
+
+impl fmt::Display for Fixed {
+    /// Shortest decimal form: `6`, `3.5`, `-0.125`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        let scale = SCALE as u64;
+        let (whole, frac) = (abs / scale, abs % scale);
+        if frac == 0 {
+            return write!(f, "{sign}{whole}");
+        }
+        let digits = format!("{frac:03}");
+        write!(f, "{sign}{whole}.{}", digits.trim_end_matches('0'))
+    }
+}
+
+impl Serialize for Fixed {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+impl<'de> Deserialize<'de> for Fixed {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Fixed::from_f64)
+    }
+}
+
+impl JsonSchema for Fixed {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        "Fixed".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        f64::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,44 @@
+use crate::fixed::{Fixed, Rounding};
+use crate::model::status::StatusInstance;
+
+#[test]
+fn fixed_point_rounds_explicitly_and_keeps_json_numbers() {
+    let value = |milli| Fixed::from_milli(milli);
+    for (milli, half, toward_zero, floor) in [
+        (2500, 3, 2, 2),
+        (-2500, -3, -2, -3),
+        (2499, 2, 2, 2),
+        (-2100, -2, -2, -3),
+        (4000, 4, 4, 4),
+    ] {
+        assert_eq!(
+            half,
+            value(milli).to_int(Rounding::HalfAwayFromZero),
+            "{milli}"
+        );
+        assert_eq!(
+            toward_zero,
+            value(milli).to_int(Rounding::TowardZero),
+            "{milli}"
+        );
+        assert_eq!(floor, value(milli).to_int(Rounding::Floor), "{milli}");
+    }
+    assert_eq!(
+        value(990),
+        value(1100).mul(value(900), Rounding::HalfAwayFromZero)
+    );
+    assert_eq!(
+        value(333),
+        value(1000).mul(value(333), Rounding::TowardZero)
+    );
+
+    let status: StatusInstance =
+        serde_json::from_str(r#"{ "kind": "slow", "remaining_ticks": 2, "magnitude": 0.1 }"#)
+            .unwrap();
+    assert_eq!(value(100), status.magnitude);
+    assert_eq!(
+        r#"{"kind":"slow","remaining_ticks":2,"magnitude":0.1}"#,
+        serde_json::to_string(&status).unwrap()
+    );
+    assert_eq!("-0.125", value(-125).to_string());
+}
@@ -1,10 +1,19 @@
+pub mod draft;
 pub mod error;
+pub mod fixed;
 pub mod model;
 pub mod rng;
+pub mod schema;
+pub mod service;
 pub mod sim;
+pub mod typescript;
+pub mod verify;
 
 pub use model::{DungeonState, SimulationResult, WaveConfig};
-pub use sim::simulate_wave;
+pub use sim::{
+    BatchJob, RunRules, SimulationConfig, ValidationReport, WaveSimulation, simulate_batch,
+    simulate_run, simulate_wave, validate,
+};
 
 /// Semantic version of the engine, taken from Cargo.toml
 pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,226 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use super::{DungeonState, StatusInstance, TrapTriggerType, UnitStats, WaveConfig};
+use crate::draft::DraftRules;
+use crate::fixed::Fixed;
+use crate::sim::RunRules;
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    TS,
+)]
+#[serde(rename_all = "snake_case")]
+/// Draft rarity of a content entry; see [`crate::draft::RarityWeights`].
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Authoring definition of a trap type.
+///
+/// # JSON example
+/// ```json
+/// {
+///   "id": "trap_fire_glyph",
+///   "name": "Fire Glyph",
+///   "base_damage": 15,
+///   "cooldown_ticks": 10,
+///   "trigger_type": "on_enter",
+///   "max_charges": 999,
+///   "tags": ["fire", "aoe"],
+///   "rarity": "common",
+///   "unlock_tier": 0
+/// }
+/// ```
+pub struct TrapConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// First run tier at which the trap can be drafted.
+    #[serde(default)]
+    pub unlock_tier: u32,
+    pub base_damage: i32,
+    pub cooldown_ticks: u32,
+    pub trigger_type: TrapTriggerType,
+    #[serde(default)]
+    pub max_charges: Option<u32>,
+    #[serde(default)]
+    pub status_on_hit: Option<StatusInstance>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Authoring definition of a monster type. Stats are inlined next to the id.
+pub struct MonsterConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub unlock_tier: u32,
+    #[serde(flatten)]
+    pub stats: UnitStats,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Authoring definition of a hero type. Stats are inlined next to the id.
+pub struct HeroConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub role: String,
+    #[serde(flatten)]
+    pub stats: UnitStats,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Authoring definition of a room type.
+pub struct RoomConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub unlock_tier: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Authoring definition of a relic, drafted as a global modifier.
+///
+/// The simulation does not interpret relic effects yet; drafting one records its id in
+/// [`crate::model::DungeonState::modifiers`].
+///
+/// # JSON example
+/// ```json
+/// {
+///   "id": "relic_furnace_core",
+///   "name": "Furnace Core",
+///   "effect_type": "global_multiplier",
+///   "effect_target_tag": "fire",
+///   "effect_multiplier": 1.2,
+///   "rarity": "rare"
+/// }
+/// ```
+pub struct RelicConfig {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub effect_type: String,
+    #[serde(default)]
+    pub effect_target_tag: Option<String>,
+    #[serde(default)]
+    pub effect_multiplier: Option<Fixed>,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub unlock_tier: u32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// The run every player of a content pack plays: the dungeon they start with, the waves in
+/// order, and the rules. A draft precedes every wave, at the tier
+/// [`RunRules::tier_for_wave`] gives; see [`crate::verify`].
+pub struct RunTemplate {
+    pub starting_dungeon: DungeonState,
+    pub waves: Vec<WaveConfig>,
+    #[serde(default)]
+    pub rules: RunRules,
+    #[serde(default)]
+    pub draft_rules: DraftRules,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Compiled content pack (`content_pack.vX.json`), the single source of truth for
+/// content ids, names and base stats.
+///
+/// # JSON example
+/// ```json
+/// {
+///   "version": "1.0.0",
+///   "content_hash": "sha256:abc123",
+///   "traps": [],
+///   "monsters": [],
+///   "heroes": [],
+///   "rooms": [],
+///   "relics": []
+/// }
+/// ```
+pub struct ContentPack {
+    pub version: String,
+    #[serde(default)]
+    pub content_hash: String,
+    #[serde(default)]
+    pub traps: Vec<TrapConfig>,
+    #[serde(default)]
+    pub monsters: Vec<MonsterConfig>,
+    #[serde(default)]
+    pub heroes: Vec<HeroConfig>,
+    #[serde(default)]
+    pub rooms: Vec<RoomConfig>,
+    #[serde(default)]
+    pub relics: Vec<RelicConfig>,
+    /// Run used to verify submitted runs; packs without one cannot verify runs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run: Option<RunTemplate>,
+}
+
+impl ContentPack {
+    pub fn trap(&self, id: &str) -> Option<&TrapConfig> {
+        self.traps.iter().find(|t| t.id == id)
+    }
+
+    pub fn monster(&self, id: &str) -> Option<&MonsterConfig> {
+        self.monsters.iter().find(|m| m.id == id)
+    }
+
+    pub fn hero(&self, id: &str) -> Option<&HeroConfig> {
+        self.heroes.iter().find(|h| h.id == id)
+    }
+
+    pub fn room(&self, id: &str) -> Option<&RoomConfig> {
+        self.rooms.iter().find(|r| r.id == id)
+    }
+
+    pub fn relic(&self, id: &str) -> Option<&RelicConfig> {
+        self.relics.iter().find(|r| r.id == id)
+    }
+}
@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 use super::{RoomId, TrapInstance, UnitInstance};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// State for an individual room, including its occupants and tags.
 ///
@@ -21,9 +22,12 @@ pub struct RoomState {
     pub traps: Vec<TrapInstance>,
     pub monsters: Vec<UnitInstance>,
     pub tags: Vec<String>,
+    /// Content pack id this room was instantiated from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Top-level dungeon description used for simulations.
 ///
@@ -40,7 +44,8 @@ pub struct RoomState {
 ///   ],
 ///   "edges": [[1, 2], [2, 3]],
 ///   "core_room_id": 1,
-///   "core_hp": 250
+///   "core_hp": 250,
+///   "modifiers": ["relic_furnace_core"]
 /// }
 /// ```
 pub struct DungeonState {
@@ -48,4 +53,7 @@ pub struct DungeonState {
     pub edges: Vec<(RoomId, RoomId)>,
     pub core_room_id: RoomId,
     pub core_hp: i32,
+    /// Relic ids drafted as global modifiers, in pick order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<String>,
 }
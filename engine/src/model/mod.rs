@@ -1,38 +1,65 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
+pub mod content;
 pub mod dungeon;
 pub mod status;
 pub mod trap;
 pub mod unit;
 pub mod wave;
 
+pub use content::{
+    ContentPack, HeroConfig, MonsterConfig, Rarity, RelicConfig, RoomConfig, RunTemplate,
+    TrapConfig,
+};
 pub use dungeon::{DungeonState, RoomState};
 pub use status::{StatusInstance, StatusKind};
 pub use trap::{TrapInstance, TrapTriggerType};
 pub use unit::{AiBehavior, Faction, UnitInstance, UnitStats};
 pub use wave::{HeroSpawn, WaveConfig};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, TS,
+)]
 #[serde(transparent)]
 pub struct RoomId(pub u32);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, TS,
+)]
 #[serde(transparent)]
 pub struct UnitId(pub u32);
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, TS,
+)]
 #[serde(transparent)]
 pub struct TrapId(pub u32);
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 pub enum SimulationOutcome {
     DungeonWin,
     HeroesWin,
     Timeout,
+    /// The wave provably cannot end in a win for either side, so it stopped early.
+    Stalemate(StalemateReason),
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// Why a wave ended in [`SimulationOutcome::Stalemate`]. In every case every spawn has
+/// happened, no unit carries a status effect and nothing can deal damage any more.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum StalemateReason {
+    /// No living hero has a path to the core room.
+    CoreUnreachable,
+    /// Heroes can reach the core but cannot move (e.g. zero move speed).
+    HeroesImmobile,
+    /// Heroes stand in the core room but cannot damage the core or anything else, e.g.
+    /// because an invulnerable monster draws their attacks.
+    NoDamagePossible,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 pub struct SimulationStats {
     pub ticks_run: u32,
     pub heroes_spawned: u32,
@@ -41,12 +68,17 @@ pub struct SimulationStats {
     pub total_damage_to_core: i32,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 pub struct SimulationResult {
     pub outcome: SimulationOutcome,
     pub final_dungeon: DungeonState,
     pub final_heroes: Vec<UnitInstance>,
     pub stats: SimulationStats,
     pub events: Vec<crate::sim::events::SimulationEvent>,
+    pub config: crate::sim::SimulationConfig,
+    pub score: crate::sim::scoring::WaveScore,
+    /// State checksums taken every `config.checksum_interval` ticks; empty when disabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checksums: Vec<crate::sim::checksum::TickChecksum>,
     pub engine_version: String,
 }
@@ -1,7 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+use crate::fixed::Fixed;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Types of status effects that can be applied to a unit.
 pub enum StatusKind {
@@ -13,7 +16,7 @@ pub enum StatusKind {
     BuffArmor,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// A concrete status effect applied to a unit.
 ///
@@ -28,5 +31,5 @@ pub enum StatusKind {
 pub struct StatusInstance {
     pub kind: StatusKind,
     pub remaining_ticks: u32,
-    pub magnitude: f32,
+    pub magnitude: Fixed,
 }
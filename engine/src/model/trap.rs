@@ -1,9 +1,10 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 use super::{StatusInstance, TrapId};
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// The condition under which a trap triggers.
 pub enum TrapTriggerType {
@@ -12,7 +13,7 @@ pub enum TrapTriggerType {
     Timed,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// A placed trap instance with runtime state.
 ///
@@ -27,7 +28,8 @@ pub enum TrapTriggerType {
 ///   "charges_used": 1,
 ///   "damage": 10,
 ///   "status_on_hit": null,
-///   "tags": ["aoe"]
+///   "tags": ["aoe"],
+///   "template_id": "trap_fire_glyph"
 /// }
 /// ```
 pub struct TrapInstance {
@@ -40,4 +42,7 @@ pub struct TrapInstance {
     pub damage: i32,
     pub status_on_hit: Option<StatusInstance>,
     pub tags: Vec<String>,
+    /// Content pack id this trap was instantiated from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
 }
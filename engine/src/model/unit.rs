@@ -1,9 +1,11 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
 use super::{RoomId, StatusInstance, UnitId};
+use crate::fixed::Fixed;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Team allegiance for a unit.
 ///
@@ -13,7 +15,7 @@ pub enum Faction {
     Monster,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Basic AI behavior for a unit.
 pub enum AiBehavior {
@@ -22,19 +24,20 @@ pub enum AiBehavior {
     Defensive,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Immutable stat block for a unit instance.
 pub struct UnitStats {
     pub max_hp: i32,
     pub armor: i32,
-    pub move_speed: f32,
+    /// Rooms per tick; see [`crate::fixed`].
+    pub move_speed: Fixed,
     pub attack_damage: i32,
     pub attack_interval_ticks: u32,
     pub attack_range: u32,
 }
 
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 /// Fully instantiated unit with runtime state.
 ///
@@ -55,7 +58,8 @@ pub struct UnitStats {
 ///   "room_id": 2,
 ///   "status_effects": [],
 ///   "ai_behavior": "aggressive",
-///   "attack_cooldown": 0
+///   "attack_cooldown": 0,
+///   "template_id": "monster_ember_guard"
 /// }
 /// ```
 pub struct UnitInstance {
@@ -67,4 +71,7 @@ pub struct UnitInstance {
     pub status_effects: Vec<StatusInstance>,
     pub ai_behavior: AiBehavior,
     pub attack_cooldown: u32,
+    /// Content pack id this unit was instantiated from, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template_id: Option<String>,
 }
@@ -1,6 +1,19 @@
+use std::collections::BTreeMap;
+
 use rand::{Rng as RandTrait, SeedableRng};
 use rand_pcg::Pcg64Mcg;
 
+use crate::model::UnitId;
+
+/// Derive an independent seed for sub-stream `stream` of `seed` (SplitMix64 finalizer),
+/// e.g. one seed per wave of a run.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 pub struct Rng {
     inner: Pcg64Mcg,
 }
@@ -16,10 +29,92 @@ impl Rng {
         RandTrait::gen_range(&mut self.inner, range)
     }
 
+    /// Uniform value in `0..bound`. `bound` must be non-zero.
+    pub fn gen_below(&mut self, bound: u64) -> u64 {
+        RandTrait::gen_range(&mut self.inner, 0..bound)
+    }
+
+    /// Uniform index into `slice`. Sampled as a `u64` so 32-bit targets such as wasm32
+    /// draw the same index as 64-bit ones.
     pub fn choose_index<T>(&mut self, slice: &[T]) -> Option<usize> {
         if slice.is_empty() {
             return None;
         }
-        Some(RandTrait::gen_range(&mut self.inner, 0..slice.len()))
+        Some(self.gen_below(slice.len() as u64) as usize)
+    }
+}
+
+/// A subsystem with its own random stream inside a wave.
+///
+/// Each subsystem draws only from its own stream, so adding a roll to one (a crit, a
+/// spawn jitter) leaves every other subsystem's draws, and the snapshots built on them,
+/// unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    /// Monster and hero target selection. Seeded with the wave seed itself, which keeps
+    /// results recorded before streams existed reproducible.
+    Targeting,
+    Spawns,
+    Traps,
+    Abilities,
+}
+
+impl RngStream {
+    /// Stable id mixed into the wave seed. Never renumber an existing stream.
+    fn id(self) -> u64 {
+        match self {
+            RngStream::Targeting => 0,
+            RngStream::Spawns => 1,
+            RngStream::Traps => 2,
+            RngStream::Abilities => 3,
+        }
+    }
+}
+
+/// Stream id under which per-unit streams are derived.
+const UNIT_STREAM: u64 = 0x554e_4954;
+
+/// The named random streams of one wave, all derived from the wave seed.
+pub struct RngStreams {
+    seed: u64,
+    targeting: Rng,
+    spawns: Rng,
+    traps: Rng,
+    abilities: Rng,
+    /// Created on first use, so units that never roll cost nothing.
+    units: BTreeMap<UnitId, Rng>,
+}
+
+impl RngStreams {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            targeting: Rng::new(seed),
+            spawns: Rng::new(derive_seed(seed, RngStream::Spawns.id())),
+            traps: Rng::new(derive_seed(seed, RngStream::Traps.id())),
+            abilities: Rng::new(derive_seed(seed, RngStream::Abilities.id())),
+            units: BTreeMap::new(),
+        }
+    }
+
+    pub fn stream(&mut self, stream: RngStream) -> &mut Rng {
+        match stream {
+            RngStream::Targeting => &mut self.targeting,
+            RngStream::Spawns => &mut self.spawns,
+            RngStream::Traps => &mut self.traps,
+            RngStream::Abilities => &mut self.abilities,
+        }
+    }
+
+    /// Stream owned by a single unit. Its draws depend only on the wave seed, the unit id
+    /// and how often this unit has rolled, not on any other unit.
+    pub fn unit(&mut self, unit: UnitId) -> &mut Rng {
+        let seed = self.seed;
+        self.units.entry(unit).or_insert_with(|| {
+            Rng::new(derive_seed(
+                derive_seed(seed, UNIT_STREAM),
+                u64::from(unit.0),
+            ))
+        })
     }
 }
@@ -0,0 +1,91 @@
+//! JSON Schema bundle for every type that crosses the engine boundary: the inputs callers
+//! send (dungeons, waves, configs, content packs, submissions) and the outputs they get
+//! back (results, summaries, reports, offers, and the error objects of the CLI, worker
+//! and HTTP server).
+//!
+//! `sim_cli schema --out <dir>` writes [`schema_bundle`] to `<dir>/`[`BUNDLE_FILE`]; the
+//! copy checked in under [`SCHEMA_DIR`] is what content tooling validates against, and a
+//! test fails while it is stale, so every schema change shows up in review.
+
+use schemars::JsonSchema;
+use schemars::r#gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::Schema;
+use serde::Serialize;
+
+use crate::draft::{DraftOffer, DraftPick, DraftRules};
+use crate::model::{ContentPack, DungeonState, SimulationResult, WaveConfig};
+use crate::service::{BatchReply, ErrorReply, VersionInfo};
+use crate::sim::stats::StressReport;
+use crate::sim::{RunResult, RunRules, SimulationConfig, ValidationReport, WaveSummary, WaveView};
+use crate::verify::{RunSubmission, VerificationReport};
+
+/// Version of the bundle's format. Bump it when a change can reject JSON the previous
+/// schemas accepted (a field removed, renamed or made required, a variant dropped).
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The checked-in bundle's directory, relative to the engine crate.
+pub const SCHEMA_DIR: &str = "../content/schema";
+
+pub const BUNDLE_FILE: &str = "engine.schema.json";
+
+/// The bundle: a draft-07 document whose `definitions` hold every boundary type and the
+/// types they reference, so any of them can be validated with
+/// `{"$ref": "#/definitions/<Type>"}`.
+#[derive(Serialize)]
+struct SchemaBundle {
+    #[serde(rename = "$schema")]
+    meta_schema: String,
+    title: &'static str,
+    schema_version: u32,
+    /// The boundary types, i.e. the definitions callers validate against.
+    roots: Vec<String>,
+    definitions: schemars::Map<String, Schema>,
+}
+
+/// The bundle as pretty-printed JSON, with a trailing newline.
+pub fn schema_bundle() -> String {
+    let settings = SchemaSettings::draft07();
+    let meta_schema = settings.meta_schema.clone().unwrap_or_default();
+    let mut generator = settings.into_generator();
+    let mut roots = Vec::new();
+    // Inputs.
+    root::<DungeonState>(&mut generator, &mut roots);
+    root::<WaveConfig>(&mut generator, &mut roots);
+    root::<SimulationConfig>(&mut generator, &mut roots);
+    root::<RunRules>(&mut generator, &mut roots);
+    root::<ContentPack>(&mut generator, &mut roots);
+    root::<DraftRules>(&mut generator, &mut roots);
+    root::<DraftPick>(&mut generator, &mut roots);
+    root::<RunSubmission>(&mut generator, &mut roots);
+    // Outputs.
+    root::<SimulationResult>(&mut generator, &mut roots);
+    root::<RunResult>(&mut generator, &mut roots);
+    root::<WaveView>(&mut generator, &mut roots);
+    root::<DraftOffer>(&mut generator, &mut roots);
+    root::<VerificationReport>(&mut generator, &mut roots);
+    root::<ValidationReport>(&mut generator, &mut roots);
+    root::<WaveSummary>(&mut generator, &mut roots);
+    root::<BatchReply>(&mut generator, &mut roots);
+    root::<StressReport>(&mut generator, &mut roots);
+    root::<VersionInfo>(&mut generator, &mut roots);
+    root::<ErrorReply>(&mut generator, &mut roots);
+
+    let bundle = SchemaBundle {
+        meta_schema,
+        title: "Engine boundary types",
+        schema_version: SCHEMA_VERSION,
+        roots,
+        definitions: generator.take_definitions(),
+    };
+    let mut json = serde_json::to_string_pretty(&bundle).expect("schemas serialize");
+    json.push('\n');
+    json
+}
+
+fn root<T: JsonSchema>(generator: &mut SchemaGenerator, roots: &mut Vec<String>) {
+    generator.subschema_for::<T>();
+    roots.push(T::schema_name());
+}
+
+#[cfg(test)]
+mod tests;
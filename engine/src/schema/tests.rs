@@ -0,0 +1,196 @@
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::schema_bundle;
+use crate::ENGINE_VERSION;
+use crate::draft::{DraftPick, generate_offer};
+use crate::error::SimError;
+use crate::model::{ContentPack, DungeonState, RoomId, SimulationOutcome, WaveConfig};
+use crate::service::{BatchParams, ErrorInfo, ErrorReply, version_info};
+use crate::sim::stats::StressStats;
+use crate::sim::{
+    RunRules, SimulationConfig, WaveSimulation, simulate_run, simulate_wave, test_fixtures,
+    validate,
+};
+use crate::verify::{DraftChoice, RunSubmission, SubmittedRunSummary, verify_run};
+
+const CONFORMANCE_CORPUS: &str = include_str!("../sim/test_fixtures/conformance_corpus.json");
+
+#[test]
+fn checked_in_schema_bundle_is_current() {
+    let checked_in = include_str!("../../../content/schema/engine.schema.json");
+    assert!(
+        checked_in == schema_bundle(),
+        "content/schema/engine.schema.json is stale; run `cargo run --bin sim_cli -- schema --out ../content/schema` in engine/"
+    );
+}
+
+/// Check `value` against the bundle's definition of `root`.
+fn assert_matches_schema(bundle: &Value, root: &str, value: &impl Serialize) {
+    let schema = json!({
+        "$schema": bundle["$schema"],
+        "$ref": format!("#/definitions/{root}"),
+        "definitions": bundle["definitions"],
+    });
+    let validator = jsonschema::validator_for(&schema).expect("bundle is a valid schema");
+    let instance = serde_json::to_value(value).expect("value serializes");
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|err| format!("{}: {err}", err.instance_path))
+        .collect();
+    assert!(
+        errors.is_empty(),
+        "{root} does not match its schema: {errors:#?}"
+    );
+}
+
+#[test]
+fn engine_inputs_and_outputs_match_their_schemas() {
+    let bundle: Value = serde_json::from_str(&schema_bundle()).expect("bundle parses");
+    let roots = bundle["roots"].as_array().expect("bundle lists its roots");
+    for root in roots {
+        let root = root.as_str().expect("root names are strings");
+        assert!(
+            bundle["definitions"].get(root).is_some(),
+            "root {root} has no definition"
+        );
+    }
+
+    let config = SimulationConfig {
+        checksum_interval: 10,
+        ..SimulationConfig::default()
+    };
+    assert_matches_schema(&bundle, "SimulationConfig", &config);
+    assert_matches_schema(&bundle, "RunRules", &RunRules::default());
+
+    let cases: Vec<Value> = serde_json::from_str(CONFORMANCE_CORPUS).expect("corpus parses");
+    for case in cases.iter().take(16) {
+        let dungeon: DungeonState =
+            serde_json::from_value(case["dungeon"].clone()).expect("dungeon parses");
+        let wave: WaveConfig = serde_json::from_value(case["wave"].clone()).expect("wave parses");
+        let seed = case["seed"].as_u64().expect("seed is a u64");
+        assert_matches_schema(&bundle, "DungeonState", &dungeon);
+        assert_matches_schema(&bundle, "WaveConfig", &wave);
+
+        let mut stepped =
+            WaveSimulation::new(dungeon.clone(), wave.clone(), seed, 250, &config).unwrap();
+        stepped.step(20).unwrap();
+        assert_matches_schema(&bundle, "WaveView", &stepped.view());
+
+        let result = simulate_wave(dungeon.clone(), wave.clone(), seed, 250, &config).unwrap();
+        assert_matches_schema(&bundle, "SimulationResult", &result);
+
+        let run = simulate_run(dungeon, &[wave.clone(), wave], seed, &RunRules::default()).unwrap();
+        assert_matches_schema(&bundle, "RunResult", &run);
+    }
+
+    let empty = DungeonState {
+        rooms: Vec::new(),
+        edges: Vec::new(),
+        core_room_id: crate::model::RoomId(0),
+        core_hp: 0,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "empty".into(),
+        entries: Vec::new(),
+        modifiers: Vec::new(),
+    };
+    let report = validate(&empty, &wave);
+    assert!(report.has_errors());
+    assert_matches_schema(&bundle, "ValidationReport", &report);
+}
+
+#[test]
+fn run_verification_and_service_replies_match_their_schemas() {
+    let bundle: Value = serde_json::from_str(&schema_bundle()).expect("bundle parses");
+    let content: ContentPack = serde_json::from_value(json!({
+        "version": "1.0.0",
+        "rooms": [{ "id": "room_corridor", "name": "Corridor" }],
+        "traps": [{ "id": "trap_spikes", "name": "Spikes", "base_damage": 4,
+                    "cooldown_ticks": 2, "trigger_type": "on_enter" }],
+        "monsters": [{ "id": "monster_rat", "name": "Rat", "max_hp": 8, "armor": 0,
+                       "move_speed": 0.5, "attack_damage": 2, "attack_interval_ticks": 1,
+                       "attack_range": 0 }],
+        "relics": [{ "id": "relic_furnace_core", "name": "Furnace Core",
+                     "effect_type": "score_multiplier", "effect_multiplier": 1.25 }],
+        "run": {
+            "starting_dungeon": {
+                "rooms": [
+                    { "id": 0, "traps": [], "monsters": [], "tags": [] },
+                    { "id": 1, "traps": [], "monsters": [], "tags": [] }
+                ],
+                "edges": [[0, 1]],
+                "core_room_id": 1,
+                "core_hp": 100
+            },
+            "waves": [{ "id": "w1", "entries": [{ "hero_template_id": "h1", "count": 1,
+                        "spawn_room_id": 0, "delay_ticks": 0 }], "modifiers": [] }],
+            "rules": { "max_ticks_per_wave": 20 }
+        }
+    }))
+    .expect("content pack parses");
+    assert_matches_schema(&bundle, "ContentPack", &content);
+
+    let template = content.run.as_ref().expect("pack has a run");
+    let seed = u64::MAX;
+    let offer = generate_offer(&content, seed, 0, 1, &template.draft_rules);
+    assert_matches_schema(&bundle, "DraftOffer", &offer);
+
+    let submission = RunSubmission {
+        content_version: content.version.clone(),
+        engine_version: ENGINE_VERSION.to_string(),
+        seed,
+        challenge_id: Some("daily".into()),
+        score: 0,
+        outcome: SimulationOutcome::HeroesWin,
+        run_summary: SubmittedRunSummary {
+            waves_cleared: 1,
+            core_hp_remaining: 0,
+            draft_choices: vec![DraftChoice {
+                tier: 1,
+                pick: DraftPick {
+                    option: 0,
+                    target_room: Some(RoomId(0)),
+                },
+                picked_id: offer.options[0].content_id().to_string(),
+            }],
+        },
+    };
+    assert_matches_schema(&bundle, "RunSubmission", &submission);
+    let report = verify_run(&submission, &content);
+    assert!(report.recomputed.is_some() && !report.mismatches.is_empty());
+    assert_matches_schema(&bundle, "VerificationReport", &report);
+
+    let fixture = test_fixtures::core_room_duel();
+    let batch = BatchParams {
+        wave: fixture.wave.clone(),
+        start_seed: 1,
+        runs: 8,
+        max_ticks: Some(50),
+        config: SimulationConfig::default(),
+        threads: 1,
+    }
+    .run(&fixture.dungeon)
+    .unwrap();
+    assert_matches_schema(&bundle, "WaveSummary", &batch.summaries[0]);
+    assert_matches_schema(&bundle, "BatchReply", &batch);
+
+    let mut stats = StressStats::default();
+    for summary in &batch.summaries {
+        stats.record(summary);
+    }
+    assert_matches_schema(&bundle, "StressReport", &stats.report(0.95).unwrap());
+    assert_matches_schema(&bundle, "VersionInfo", &version_info());
+
+    let mut error = ErrorInfo::from(SimError::InvalidInput(validate(
+        &fixture.dungeon,
+        &WaveConfig {
+            id: "empty".into(),
+            entries: Vec::new(),
+            modifiers: Vec::new(),
+        },
+    )));
+    error.exit_code = Some(4);
+    assert_matches_schema(&bundle, "ErrorReply", &ErrorReply { error });
+}
@@ -0,0 +1,200 @@
+//! JSON request handlers shared by the native entry points (`sim_cli serve-stdio` and the
+//! `sim_server` HTTP binary).
+//!
+//! Parameter structs leave out the dungeon or content pack, which each transport supplies
+//! its own way (inline, or from a cache keyed by id), so every entry point accepts the
+//! same fields and returns the same JSON. Failures are reported as an [`ErrorReply`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::ENGINE_VERSION;
+use crate::error::SimError;
+use crate::model::{ContentPack, DungeonState, SimulationResult, WaveConfig};
+use crate::sim::{
+    BatchJob, SCORE_VERSION, SimulationConfig, ValidationReport, WaveSummary, simulate_batch,
+    simulate_wave, validate,
+};
+use crate::verify::{RunSubmission, VerificationReport, verify_run};
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SimulateParams {
+    pub wave: WaveConfig,
+    pub seed: u64,
+    /// Defaults to `config.limits.max_ticks`.
+    pub max_ticks: Option<u32>,
+    #[serde(default)]
+    pub config: SimulationConfig,
+    /// Reply with a [`WaveSummary`] instead of the full result.
+    #[serde(default)]
+    pub summary_only: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
+pub enum SimulateReply {
+    Full(Box<SimulationResult>),
+    Summary(WaveSummary),
+}
+
+impl SimulateParams {
+    pub fn run(self, dungeon: DungeonState) -> Result<SimulateReply, SimError> {
+        let max_ticks = self.max_ticks.unwrap_or(self.config.limits.max_ticks);
+        let result = simulate_wave(dungeon, self.wave, self.seed, max_ticks, &self.config)?;
+        if !self.summary_only {
+            return Ok(SimulateReply::Full(Box::new(result)));
+        }
+        Ok(SimulateReply::Summary(WaveSummary {
+            seed: self.seed,
+            outcome: result.outcome,
+            stats: result.stats,
+            core_hp_remaining: result.final_dungeon.core_hp,
+            score: result.score.total,
+        }))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ValidateParams {
+    pub wave: WaveConfig,
+}
+
+impl ValidateParams {
+    pub fn run(&self, dungeon: &DungeonState) -> ValidationReport {
+        validate(dungeon, &self.wave)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BatchParams {
+    pub wave: WaveConfig,
+    #[serde(default = "default_start_seed")]
+    pub start_seed: u64,
+    pub runs: u64,
+    pub max_ticks: Option<u32>,
+    #[serde(default)]
+    pub config: SimulationConfig,
+    /// Worker threads; `0` uses all cores. Servers may cap it.
+    #[serde(default)]
+    pub threads: usize,
+}
+
+fn default_start_seed() -> u64 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct BatchReply {
+    /// One summary per seed, in seed order.
+    pub summaries: Vec<WaveSummary>,
+}
+
+impl BatchParams {
+    /// Fails with the first job's error, if any.
+    pub fn run(&self, dungeon: &DungeonState) -> Result<BatchReply, SimError> {
+        let max_ticks = self.max_ticks.unwrap_or(self.config.limits.max_ticks);
+        let jobs = BatchJob::seed_sweep(
+            dungeon,
+            &self.wave,
+            self.start_seed,
+            self.runs,
+            max_ticks,
+            &self.config,
+        );
+        let summaries = simulate_batch(&jobs, self.threads)
+            .into_iter()
+            .collect::<Result<_, _>>()?;
+        Ok(BatchReply { summaries })
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct VerifyParams {
+    pub submission: RunSubmission,
+}
+
+impl VerifyParams {
+    pub fn run(&self, content: &ContentPack) -> VerificationReport {
+        verify_run(&self.submission, content)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct VersionInfo {
+    pub engine_version: &'static str,
+    pub score_version: u32,
+}
+
+pub fn version_info() -> VersionInfo {
+    VersionInfo {
+        engine_version: ENGINE_VERSION,
+        score_version: SCORE_VERSION,
+    }
+}
+
+/// Why a request failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// `sim_cli` only: the command ran and its verdict is negative (validation errors, an
+    /// invalid run, diverging results). The verdict is the output; no error object is
+    /// printed.
+    Failed,
+    /// Bad command line or worker request.
+    Usage,
+    /// An input file could not be read or parsed.
+    Input,
+    /// The engine rejected the inputs.
+    Simulation,
+    /// `sim_server`: the request body could not be read or parsed.
+    Request,
+    /// `sim_server`: the request is over one of the server's limits.
+    TooLarge,
+    /// `sim_server`: unknown route.
+    NotFound,
+    /// `sim_server`: known route, wrong method.
+    MethodNotAllowed,
+}
+
+/// A failure as `sim_cli`, its `serve-stdio` worker and `sim_server` report it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorInfo {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// `sim_cli`'s exit code; absent from worker and HTTP replies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<u8>,
+    /// Every validation issue, when the engine rejected invalid inputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationReport>,
+}
+
+impl ErrorInfo {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            exit_code: None,
+            validation: None,
+        }
+    }
+}
+
+impl From<SimError> for ErrorInfo {
+    fn from(err: SimError) -> Self {
+        match err {
+            SimError::InvalidInput(report) => Self {
+                validation: Some(report),
+                ..Self::new(ErrorKind::Simulation, "invalid input")
+            },
+            err => Self::new(ErrorKind::Simulation, err.to_string()),
+        }
+    }
+}
+
+/// `{"error": ...}`, the body of every failure `sim_cli --format json` prints and
+/// `sim_server` replies with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorReply {
+    pub error: ErrorInfo,
+}
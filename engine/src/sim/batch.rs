@@ -0,0 +1,176 @@
+//! Parallel batch simulation for balancing sweeps (contract/phase1.md §6.1).
+//!
+//! Jobs borrow their dungeon, wave and config, so a sweep over thousands of seeds shares
+//! one copy of each input; a worker clones only the dungeon, which the wave mutates, when
+//! it starts the job. Workers pull jobs from a shared counter and send compact [`WaveSummary`]s back,
+//! which are handed to the caller in job order whatever the thread count, so a sweep is
+//! reproducible.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::error::SimError;
+use crate::model::{DungeonState, SimulationOutcome, SimulationStats, WaveConfig};
+use crate::sim::{SimulationConfig, WaveContext, run_wave};
+
+/// One wave to simulate.
+#[derive(Clone, Copy, Debug)]
+pub struct BatchJob<'a> {
+    pub dungeon: &'a DungeonState,
+    pub wave: &'a WaveConfig,
+    pub seed: u64,
+    pub max_ticks: u32,
+    pub config: &'a SimulationConfig,
+}
+
+impl<'a> BatchJob<'a> {
+    /// `runs` jobs over consecutive seeds starting at `start_seed`, all sharing the inputs.
+    pub fn seed_sweep(
+        dungeon: &'a DungeonState,
+        wave: &'a WaveConfig,
+        start_seed: u64,
+        runs: u64,
+        max_ticks: u32,
+        config: &'a SimulationConfig,
+    ) -> Vec<Self> {
+        (0..runs)
+            .map(|offset| BatchJob {
+                dungeon,
+                wave,
+                seed: start_seed.wrapping_add(offset),
+                max_ticks,
+                config,
+            })
+            .collect()
+    }
+}
+
+/// What a batch keeps of a [`crate::SimulationResult`]: no event log, dungeon or heroes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WaveSummary {
+    pub seed: u64,
+    pub outcome: SimulationOutcome,
+    pub stats: SimulationStats,
+    pub core_hp_remaining: i32,
+    pub score: i64,
+}
+
+/// Simulate every job on up to `threads` worker threads (`0` uses all available cores)
+/// and return one entry per job, in job order.
+pub fn simulate_batch(jobs: &[BatchJob<'_>], threads: usize) -> Vec<Result<WaveSummary, SimError>> {
+    let mut summaries = Vec::with_capacity(jobs.len());
+    simulate_batch_with(jobs, threads, |_, summary| summaries.push(summary));
+    summaries
+}
+
+/// [`simulate_batch`], streaming each summary to `on_summary` together with its job index
+/// as soon as every earlier job has finished.
+pub fn simulate_batch_with<F>(jobs: &[BatchJob<'_>], threads: usize, on_summary: F)
+where
+    F: FnMut(usize, Result<WaveSummary, SimError>),
+{
+    simulate_batch_on(
+        jobs,
+        threads,
+        |scope, worker| thread::Builder::new().spawn_scoped(scope, worker).map(drop),
+        on_summary,
+    );
+}
+
+/// A worker body, as handed to the spawner of [`simulate_batch_on`].
+pub(crate) type Worker<'scope> = Box<dyn FnOnce() + Send + 'scope>;
+
+/// [`simulate_batch_with`] with the thread spawner injected, so tests can make every spawn
+/// fail the way it does on targets without threads.
+pub(crate) fn simulate_batch_on<S, F>(
+    jobs: &[BatchJob<'_>],
+    threads: usize,
+    spawn: S,
+    mut on_summary: F,
+) where
+    S: for<'scope, 'env> Fn(&'scope thread::Scope<'scope, 'env>, Worker<'scope>) -> io::Result<()>,
+    F: FnMut(usize, Result<WaveSummary, SimError>),
+{
+    let threads = worker_count(threads).min(jobs.len());
+    if threads <= 1 {
+        for (index, job) in jobs.iter().enumerate() {
+            on_summary(index, run_job(job));
+        }
+        return;
+    }
+
+    let next_job = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        let mut spawned = 0;
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let next_job = &next_job;
+            let worker = spawn(
+                scope,
+                Box::new(move || {
+                    loop {
+                        let index = next_job.fetch_add(1, Ordering::Relaxed);
+                        let Some(job) = jobs.get(index) else { break };
+                        if sender.send((index, run_job(job))).is_err() {
+                            break;
+                        }
+                    }
+                }),
+            );
+            spawned += usize::from(worker.is_ok());
+        }
+        drop(sender);
+        if spawned == 0 {
+            // No threads on this target (e.g. wasm32-wasip1): run the batch here instead.
+            for (index, job) in jobs.iter().enumerate() {
+                on_summary(index, run_job(job));
+            }
+            return;
+        }
+
+        // Workers finish out of order; hold results back until their turn comes.
+        let mut pending = BTreeMap::new();
+        let mut next_out = 0;
+        for (index, summary) in receiver {
+            pending.insert(index, summary);
+            while let Some(summary) = pending.remove(&next_out) {
+                on_summary(next_out, summary);
+                next_out += 1;
+            }
+        }
+    });
+}
+
+fn worker_count(requested: usize) -> usize {
+    if requested > 0 {
+        return requested;
+    }
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+fn run_job(job: &BatchJob<'_>) -> Result<WaveSummary, SimError> {
+    let (result, _) = run_wave(
+        job.dungeon.clone(),
+        Cow::Borrowed(job.wave),
+        job.seed,
+        job.max_ticks,
+        job.config,
+        WaveContext::default(),
+    )?;
+    Ok(WaveSummary {
+        seed: job.seed,
+        outcome: result.outcome,
+        stats: result.stats,
+        core_hp_remaining: result.final_dungeon.core_hp,
+        score: result.score.total,
+    })
+}
@@ -0,0 +1,198 @@
+//! Per-tick state checksums for desync detection.
+//!
+//! With [`crate::SimulationConfig::checksum_interval`] set, every N-th tick (and the last
+//! one) records a [`StateChecksum`] of the mutable simulation state in
+//! [`crate::SimulationResult::checksums`]. When a client replay and the server disagree,
+//! [`first_divergence`] names the first tick whose state differs instead of leaving a
+//! byte-diff of two large results.
+
+use std::fmt;
+
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaGenerator;
+use schemars::schema::{InstanceType, Schema, SchemaObject, StringValidation};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use ts_rs::TS;
+
+use crate::model::UnitInstance;
+use crate::sim::tick::SimState;
+
+/// FNV-1a hash of the simulation state after a tick. Serialized as 16 hex digits so
+/// JavaScript clients do not lose precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TS)]
+#[ts(type = "string")]
+pub struct StateChecksum(pub u64);
+
+impl fmt::Display for StateChecksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl Serialize for StateChecksum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateChecksum {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        u64::from_str_radix(&hex, 16)
+            .map(StateChecksum)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl JsonSchema for StateChecksum {
+    fn schema_name() -> String {
+        "StateChecksum".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[0-9a-f]{16}$".into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct TickChecksum {
+    /// Ticks completed when the checksum was taken.
+    pub tick: u32,
+    pub checksum: StateChecksum,
+}
+
+/// The first tick at which two checksum sequences disagree. A missing side means that run
+/// had already ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumDivergence {
+    pub tick: u32,
+    pub left: Option<StateChecksum>,
+    pub right: Option<StateChecksum>,
+}
+
+/// Find the first tick where `left` and `right` disagree.
+///
+/// Ticks present in only one sequence are skipped while the other still has later
+/// checksums, so sequences recorded at different intervals compare on their common ticks.
+pub fn first_divergence(
+    left: &[TickChecksum],
+    right: &[TickChecksum],
+) -> Option<ChecksumDivergence> {
+    let (mut l, mut r) = (0, 0);
+    loop {
+        match (left.get(l), right.get(r)) {
+            (None, None) => return None,
+            (Some(a), None) => {
+                return Some(ChecksumDivergence {
+                    tick: a.tick,
+                    left: Some(a.checksum),
+                    right: None,
+                });
+            }
+            (None, Some(b)) => {
+                return Some(ChecksumDivergence {
+                    tick: b.tick,
+                    left: None,
+                    right: Some(b.checksum),
+                });
+            }
+            (Some(a), Some(b)) if a.tick == b.tick => {
+                if a.checksum != b.checksum {
+                    return Some(ChecksumDivergence {
+                        tick: a.tick,
+                        left: Some(a.checksum),
+                        right: Some(b.checksum),
+                    });
+                }
+                l += 1;
+                r += 1;
+            }
+            (Some(a), Some(b)) if a.tick < b.tick => l += 1,
+            (Some(_), Some(_)) => r += 1,
+        }
+    }
+}
+
+/// Hash the state that changes during a wave: tick, core HP, trap cooldowns and charges,
+/// spawn progress and every unit's room, HP, cooldown and statuses. Static stats and the
+/// event log are left out to keep this cheap.
+pub(crate) fn checksum_state(state: &SimState) -> StateChecksum {
+    let mut hasher = Fnv::default();
+    hasher.u32(state.tick);
+    hasher.i32(state.dungeon.core_hp);
+    for room in &state.dungeon.rooms {
+        hasher.u32(room.id.0);
+        for trap in &room.traps {
+            hasher.u32(trap.id.0);
+            hasher.u32(trap.cooldown_remaining);
+            hasher.u32(trap.charges_used);
+        }
+        hasher.len(room.monsters.len());
+        for monster in &room.monsters {
+            hash_unit(&mut hasher, monster);
+        }
+    }
+    hasher.len(state.heroes.len());
+    for hero in &state.heroes {
+        hash_unit(&mut hasher, hero);
+    }
+    for progress in &state.spawn_progress {
+        hasher.u32(*progress);
+    }
+    hasher.u64(state.next_unit_id());
+    StateChecksum(hasher.0)
+}
+
+fn hash_unit(hasher: &mut Fnv, unit: &UnitInstance) {
+    hasher.u32(unit.id.0);
+    hasher.u32(unit.room_id.0);
+    hasher.i32(unit.hp);
+    hasher.u32(unit.attack_cooldown);
+    hasher.len(unit.status_effects.len());
+    for status in &unit.status_effects {
+        hasher.u32(status.kind.clone() as u32);
+        hasher.u32(status.remaining_ticks);
+        hasher.u64(status.magnitude.milli() as u64);
+    }
+}
+
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Fnv(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn bytes(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    /// Lengths are hashed as `u32` so 32- and 64-bit targets agree.
+    fn len(&mut self, len: usize) {
+        self.u32(len as u32);
+    }
+}
@@ -0,0 +1,104 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Hard caps enforced before and during a simulation. Exceeding any of them fails fast
+/// with a [`crate::error::SimError`] instead of running unbounded work.
+pub struct SimulationLimits {
+    /// Maximum number of live units (monsters plus heroes) at any time.
+    pub max_units: usize,
+    /// Maximum `max_ticks` a caller may request.
+    pub max_ticks: u32,
+    /// Maximum number of events in a single result.
+    pub max_events: usize,
+    /// Maximum total hero count across all spawn entries of a wave.
+    pub max_heroes_per_wave: u32,
+    /// Maximum monsters plus traps placed in a single room.
+    pub max_entities_per_room: usize,
+    pub max_rooms: usize,
+    pub max_edges: usize,
+    /// Maximum traps across the whole dungeon.
+    pub max_traps: usize,
+}
+
+impl Default for SimulationLimits {
+    fn default() -> Self {
+        Self {
+            max_units: 512,
+            max_ticks: 60_000,
+            max_events: 10_000,
+            max_heroes_per_wave: 512,
+            max_entities_per_room: 512,
+            max_rooms: 1_024,
+            max_edges: 4_096,
+            max_traps: 2_048,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Switches for movement and targeting rules. Defaults reproduce the original rules.
+pub struct RuleToggles {
+    /// Units may hit targets in other rooms up to their `attack_range`; when off, units
+    /// only attack inside their own room.
+    pub cross_room_attacks: bool,
+    /// Heroes attack monsters in range before the core; when off, a hero with the core in
+    /// range always attacks the core.
+    pub heroes_target_monsters_first: bool,
+    /// Any positive move speed moves a hero at least one room per tick; when off, speeds
+    /// below 1.0 leave the hero in place.
+    pub min_one_step_per_tick: bool,
+}
+
+impl Default for RuleToggles {
+    fn default() -> Self {
+        Self {
+            cross_room_attacks: true,
+            heroes_target_monsters_first: true,
+            min_one_step_per_tick: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Tunable simulation parameters, echoed back in [`crate::SimulationResult`].
+///
+/// Every field has a default, so a partial JSON object only overrides what it names.
+///
+/// # JSON example
+/// ```json
+/// {
+///   "limits": { "max_ticks": 20000, "max_heroes_per_wave": 100 },
+///   "min_damage": 1,
+///   "tick_duration_ms": 100,
+///   "rules": { "cross_room_attacks": false },
+///   "checksum_interval": 10
+/// }
+/// ```
+pub struct SimulationConfig {
+    pub limits: SimulationLimits,
+    /// Lowest damage a hit can deal after armor.
+    pub min_damage: i32,
+    /// Real-time length of one tick, for clients converting ticks to wall-clock time.
+    pub tick_duration_ms: u32,
+    pub rules: RuleToggles,
+    /// Record a state checksum every this many ticks, plus one for the final tick, in
+    /// [`crate::SimulationResult::checksums`]. `0` records none.
+    pub checksum_interval: u32,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            limits: SimulationLimits::default(),
+            min_damage: 1,
+            tick_duration_ms: 100,
+            rules: RuleToggles::default(),
+            checksum_interval: 0,
+        }
+    }
+}
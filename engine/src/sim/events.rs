@@ -1,43 +1,216 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::error::SimError;
+use crate::fixed::Fixed;
 
 use crate::model::{RoomId, StatusKind, TrapId, UnitId};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// Sequential identifier of an event within a single simulation's log.
+///
+/// Ids start at 0 and match the event's index in [`crate::SimulationResult::events`].
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, JsonSchema, TS,
+)]
+#[serde(transparent)]
+pub struct EventId(pub u32);
+
+/// What dealt a point of damage.
+///
+/// Serialized with the same external tagging as [`SimulationEvent`], e.g.
+/// `{"Unit": 3}`, `{"Trap": 0}`, `{"Status": "poison"}` or `"Environment"`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub enum DamageSource {
+    /// A melee or ranged attack from another unit.
+    Unit(UnitId),
+    /// A trap firing on the target.
+    Trap(TrapId),
+    /// A damage-over-time status ticking on the target.
+    Status(StatusKind),
+    /// Anything not attributable to a unit, trap or status.
+    Environment,
+}
+
+impl DamageSource {
+    /// The unit responsible for the damage, if any.
+    pub fn unit(&self) -> Option<UnitId> {
+        match self {
+            DamageSource::Unit(unit_id) => Some(*unit_id),
+            _ => None,
+        }
+    }
+}
+
+/// A single entry in the simulation's event log.
+///
+/// Every event carries a sequential `id` and, when it was set off by an earlier
+/// event, the `caused_by` id of that event. Following `caused_by` links walks a
+/// trigger chain such as trap -> damage -> status -> death without matching ticks.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
 pub enum SimulationEvent {
     UnitSpawned {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         unit_id: UnitId,
         room_id: RoomId,
+        hp: i32,
+        template_id: Option<String>,
     },
     UnitMoved {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         unit_id: UnitId,
         from: RoomId,
         to: RoomId,
     },
     TrapTriggered {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         trap_id: TrapId,
         room_id: RoomId,
     },
     DamageApplied {
         tick: u32,
-        source: Option<UnitId>,
+        id: EventId,
+        caused_by: Option<EventId>,
+        source: DamageSource,
         target: UnitId,
         amount: i32,
+        hp_before: i32,
+        hp_after: i32,
     },
     StatusApplied {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         target: UnitId,
         kind: StatusKind,
+        magnitude: Fixed,
+        duration_ticks: u32,
     },
     UnitDied {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         unit_id: UnitId,
+        /// Unit that landed the killing blow, if it was a unit.
+        killer: Option<UnitId>,
+        cause: DamageSource,
     },
     CoreDamaged {
         tick: u32,
+        id: EventId,
+        caused_by: Option<EventId>,
         amount: i32,
         core_hp_after: i32,
     },
 }
+
+impl SimulationEvent {
+    /// Tick the event was emitted on.
+    pub fn tick(&self) -> u32 {
+        match self {
+            SimulationEvent::UnitSpawned { tick, .. }
+            | SimulationEvent::UnitMoved { tick, .. }
+            | SimulationEvent::TrapTriggered { tick, .. }
+            | SimulationEvent::DamageApplied { tick, .. }
+            | SimulationEvent::StatusApplied { tick, .. }
+            | SimulationEvent::UnitDied { tick, .. }
+            | SimulationEvent::CoreDamaged { tick, .. } => *tick,
+        }
+    }
+
+    /// Sequential id of the event within its log.
+    pub fn id(&self) -> EventId {
+        match self {
+            SimulationEvent::UnitSpawned { id, .. }
+            | SimulationEvent::UnitMoved { id, .. }
+            | SimulationEvent::TrapTriggered { id, .. }
+            | SimulationEvent::DamageApplied { id, .. }
+            | SimulationEvent::StatusApplied { id, .. }
+            | SimulationEvent::UnitDied { id, .. }
+            | SimulationEvent::CoreDamaged { id, .. } => *id,
+        }
+    }
+
+    /// Id of the event that set this one off, if any.
+    pub fn caused_by(&self) -> Option<EventId> {
+        match self {
+            SimulationEvent::UnitSpawned { caused_by, .. }
+            | SimulationEvent::UnitMoved { caused_by, .. }
+            | SimulationEvent::TrapTriggered { caused_by, .. }
+            | SimulationEvent::DamageApplied { caused_by, .. }
+            | SimulationEvent::StatusApplied { caused_by, .. }
+            | SimulationEvent::UnitDied { caused_by, .. }
+            | SimulationEvent::CoreDamaged { caused_by, .. } => *caused_by,
+        }
+    }
+}
+
+/// Walk `caused_by` links from `id` back to the root event that started the chain.
+///
+/// The returned chain starts with the root and ends with the event for `id`. Returns an
+/// empty vector if `id` is not part of `events`.
+pub fn cause_chain(events: &[SimulationEvent], id: EventId) -> Vec<&SimulationEvent> {
+    let mut chain = Vec::new();
+    let mut current = events.get(id.0 as usize);
+    while let Some(event) = current {
+        chain.push(event);
+        // Causes always precede their effects, which also rules out cycles.
+        current = event
+            .caused_by()
+            .filter(|parent| *parent < event.id())
+            .and_then(|parent| events.get(parent.0 as usize));
+    }
+    chain.reverse();
+    chain
+}
+
+/// Append-only event log that assigns ids and enforces the configured event cap.
+#[derive(Clone, Debug, Default)]
+pub struct EventLog {
+    events: Vec<SimulationEvent>,
+    limit: usize,
+}
+
+impl EventLog {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            events: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Append an event built by `make`, which receives the id the event is stored under.
+    pub fn push(
+        &mut self,
+        make: impl FnOnce(EventId) -> SimulationEvent,
+    ) -> Result<EventId, SimError> {
+        if self.events.len() >= self.limit {
+            return Err(SimError::EventLimit);
+        }
+        let id = EventId(self.events.len() as u32);
+        self.events.push(make(id));
+        Ok(id)
+    }
+
+    pub fn as_slice(&self) -> &[SimulationEvent] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn into_events(self) -> Vec<SimulationEvent> {
+        self.events
+    }
+}
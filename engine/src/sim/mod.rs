@@ -1,73 +1,115 @@
+pub mod batch;
+pub mod checksum;
+pub mod config;
 pub mod events;
+pub mod narrative;
 pub mod pathfinding;
+pub mod reducer;
+pub mod run;
+pub mod scoring;
+pub mod stats;
+pub mod stepper;
 pub mod test_fixtures;
 pub mod tick;
+pub mod validation;
 
-use crate::ENGINE_VERSION;
-use crate::error::SimError;
-use crate::model::{DungeonState, SimulationOutcome, SimulationResult, WaveConfig};
-use tick::{SimState, step_tick};
+use std::borrow::Cow;
 
-pub const MAX_UNITS: usize = 512;
-pub const MAX_TICKS: u32 = 60_000;
-pub const MAX_EVENTS: usize = 10_000;
+use crate::error::SimError;
+use crate::model::{DungeonState, SimulationResult, UnitInstance, WaveConfig};
+pub use batch::{BatchJob, WaveSummary, simulate_batch, simulate_batch_with};
+pub use checksum::{ChecksumDivergence, StateChecksum, TickChecksum, first_divergence};
+pub use config::{RuleToggles, SimulationConfig, SimulationLimits};
+pub use run::{BetweenWaveRules, RunResult, RunRules, RunState, RunSummary, simulate_run};
+pub use scoring::{RunScore, SCORE_VERSION, ScoreContext, WaveScore};
+pub use stepper::{WaveSimulation, WaveView};
+pub use validation::{ValidationReport, validate};
 
 /// Simulate a wave against the provided dungeon layout.
+///
+/// `config` supplies the enforced limits and rule switches; pass
+/// `&SimulationConfig::default()` for the standard rules. Inputs over a limit fail with
+/// the matching [`SimError`]; inputs for which [`validate`] reports errors fail with
+/// [`SimError::InvalidInput`] carrying the full report.
 pub fn simulate_wave(
     dungeon: DungeonState,
     wave: WaveConfig,
     seed: u64,
     max_ticks: u32,
+    config: &SimulationConfig,
 ) -> Result<SimulationResult, SimError> {
-    validate_dungeon(&dungeon)?;
-    if max_ticks > MAX_TICKS {
-        return Err(SimError::TickLimit);
-    }
-
-    let mut state = SimState::new(dungeon, &wave, seed)?;
+    run_wave(
+        dungeon,
+        Cow::Owned(wave),
+        seed,
+        max_ticks,
+        config,
+        WaveContext::default(),
+    )
+    .map(|(result, _)| result)
+}
 
-    let mut outcome = SimulationOutcome::Timeout;
-    for _ in 0..max_ticks {
-        if let Some(result) = step_tick(&mut state, &wave)? {
-            outcome = result;
-            break;
-        }
-    }
+/// What a wave inherits from the run it is part of.
+#[derive(Default)]
+pub(crate) struct WaveContext {
+    pub score: ScoreContext,
+    /// Heroes still alive from the previous wave.
+    pub carried_heroes: Vec<UnitInstance>,
+    /// Lowest id the wave may give a new hero.
+    pub next_unit_id: u64,
+}
 
-    Ok(SimulationResult {
-        outcome,
-        final_dungeon: state.dungeon,
-        final_heroes: state.heroes,
-        stats: state.stats,
-        events: state.events,
-        engine_version: ENGINE_VERSION.to_string(),
-    })
+/// [`simulate_wave`] as one wave of a run. Also returns the next free hero id so ids stay
+/// unique across the whole run.
+pub(crate) fn run_wave(
+    dungeon: DungeonState,
+    wave: Cow<'_, WaveConfig>,
+    seed: u64,
+    max_ticks: u32,
+    config: &SimulationConfig,
+    context: WaveContext,
+) -> Result<(SimulationResult, u64), SimError> {
+    WaveSimulation::with_context(dungeon, wave, seed, max_ticks, config, context)?
+        .finish_with_next_id()
 }
 
-fn validate_dungeon(dungeon: &DungeonState) -> Result<(), SimError> {
-    if dungeon.rooms.is_empty() {
-        return Err(SimError::InvalidDungeon("No rooms".into()));
+fn check_dungeon_limits(dungeon: &DungeonState, limits: &SimulationLimits) -> Result<(), SimError> {
+    if dungeon.rooms.len() > limits.max_rooms {
+        return Err(SimError::RoomLimit);
     }
-    if !dungeon.rooms.iter().any(|r| r.id == dungeon.core_room_id) {
-        return Err(SimError::InvalidDungeon("Missing core room".into()));
+    if dungeon.edges.len() > limits.max_edges {
+        return Err(SimError::EdgeLimit);
     }
-    for (a, b) in &dungeon.edges {
-        let a_exists = dungeon.rooms.iter().any(|r| r.id == *a);
-        let b_exists = dungeon.rooms.iter().any(|r| r.id == *b);
-        if !a_exists || !b_exists {
-            return Err(SimError::InvalidDungeon(
-                "Edge references unknown room".into(),
-            ));
-        }
+    let total_traps: usize = dungeon.rooms.iter().map(|r| r.traps.len()).sum();
+    if total_traps > limits.max_traps {
+        return Err(SimError::TrapLimit);
+    }
+    if dungeon
+        .rooms
+        .iter()
+        .any(|r| r.monsters.len() + r.traps.len() > limits.max_entities_per_room)
+    {
+        return Err(SimError::EntityLimit);
     }
 
     let total_units: usize = dungeon.rooms.iter().map(|r| r.monsters.len()).sum();
-    if total_units > MAX_UNITS {
+    if total_units > limits.max_units {
         return Err(SimError::EntityLimit);
     }
 
     Ok(())
 }
 
+fn check_wave_limits(wave: &WaveConfig, limits: &SimulationLimits) -> Result<(), SimError> {
+    let total_heroes = wave
+        .entries
+        .iter()
+        .try_fold(0u32, |total, entry| total.checked_add(entry.count));
+    match total_heroes {
+        Some(total) if total <= limits.max_heroes_per_wave => Ok(()),
+        _ => Err(SimError::HeroLimit),
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,344 @@
+//! Human-readable rendering of a simulation's event log.
+//!
+//! Produces lines in the style of contract/phase1.md, e.g.
+//! `T=23: Hero#4 triggered trap 'Fire Glyph' in Room#12 – took 15 dmg (HP 35 → 20)`.
+//! Names are resolved through the optional [`ContentPack`], falling back to template ids
+//! and finally to bare ids, so the same renderer serves the CLI, tests and bug reports.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::model::{
+    ContentPack, DungeonState, RoomId, SimulationResult, StatusKind, TrapId, UnitId,
+};
+use crate::sim::events::{DamageSource, EventId, SimulationEvent};
+
+/// Output flavour for [`render_narrative`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NarrativeFormat {
+    /// One line per entry, names in single quotes.
+    PlainText,
+    /// A bullet list with bold ticks and names in code spans.
+    Markdown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum UnitKind {
+    Hero,
+    Monster,
+}
+
+struct UnitLabel {
+    kind: UnitKind,
+    template_id: Option<String>,
+}
+
+/// Resolves ids in an event log to display names and renders the log line by line.
+pub struct Narrator<'a> {
+    content: Option<&'a ContentPack>,
+    format: NarrativeFormat,
+    units: HashMap<UnitId, UnitLabel>,
+    traps: HashMap<TrapId, Option<String>>,
+    rooms: HashMap<RoomId, (Option<String>, Vec<String>)>,
+}
+
+impl<'a> Narrator<'a> {
+    /// Create a narrator for a simulation that started from `initial`.
+    pub fn new(
+        initial: &DungeonState,
+        content: Option<&'a ContentPack>,
+        format: NarrativeFormat,
+    ) -> Self {
+        let mut units = HashMap::new();
+        let mut traps = HashMap::new();
+        let mut rooms = HashMap::new();
+        for room in &initial.rooms {
+            rooms.insert(room.id, (room.template_id.clone(), room.tags.clone()));
+            for trap in &room.traps {
+                traps.insert(trap.id, trap.template_id.clone());
+            }
+            for monster in &room.monsters {
+                units.insert(
+                    monster.id,
+                    UnitLabel {
+                        kind: UnitKind::Monster,
+                        template_id: monster.template_id.clone(),
+                    },
+                );
+            }
+        }
+
+        Self {
+            content,
+            format,
+            units,
+            traps,
+            rooms,
+        }
+    }
+
+    /// Render every event in order, merging trap triggers with the damage they caused.
+    pub fn lines(&mut self, events: &[SimulationEvent]) -> Vec<String> {
+        let mut consumed: HashSet<EventId> = HashSet::new();
+        let mut lines = Vec::new();
+        for (idx, event) in events.iter().enumerate() {
+            if consumed.contains(&event.id()) {
+                continue;
+            }
+            if let SimulationEvent::UnitSpawned {
+                unit_id,
+                template_id,
+                ..
+            } = event
+            {
+                self.units.insert(
+                    *unit_id,
+                    UnitLabel {
+                        kind: UnitKind::Hero,
+                        template_id: template_id.clone(),
+                    },
+                );
+            }
+
+            if let SimulationEvent::TrapTriggered {
+                tick,
+                id,
+                caused_by,
+                trap_id,
+                room_id,
+            } = event
+            {
+                let hits: Vec<&SimulationEvent> = events[idx + 1..]
+                    .iter()
+                    .take_while(|e| e.tick() == *tick)
+                    .filter(|e| {
+                        e.caused_by() == Some(*id)
+                            && matches!(e, SimulationEvent::DamageApplied { .. })
+                    })
+                    .collect();
+                if hits.is_empty() {
+                    lines.push(self.line(
+                        *tick,
+                        format!("{} fired in {}", self.trap(*trap_id), self.room(*room_id)),
+                    ));
+                }
+                for hit in hits {
+                    let SimulationEvent::DamageApplied {
+                        target,
+                        amount,
+                        hp_before,
+                        hp_after,
+                        ..
+                    } = hit
+                    else {
+                        continue;
+                    };
+                    consumed.insert(hit.id());
+                    let verb = if caused_by.is_some() {
+                        format!("{} triggered", self.unit(*target))
+                    } else {
+                        format!("{} caught", self.unit(*target))
+                    };
+                    lines.push(self.line(
+                        *tick,
+                        format!(
+                            "{verb} {} in {} – took {amount} dmg (HP {hp_before} → {hp_after})",
+                            self.trap(*trap_id),
+                            self.room(*room_id)
+                        ),
+                    ));
+                }
+                continue;
+            }
+
+            lines.push(self.line(event.tick(), self.describe(event)));
+        }
+        lines
+    }
+
+    fn describe(&self, event: &SimulationEvent) -> String {
+        match event {
+            SimulationEvent::UnitSpawned {
+                unit_id,
+                room_id,
+                hp,
+                ..
+            } => format!(
+                "{} spawned in {} (HP {hp})",
+                self.unit(*unit_id),
+                self.room(*room_id)
+            ),
+            SimulationEvent::UnitMoved {
+                unit_id, from, to, ..
+            } => format!(
+                "{} moved {} → {}",
+                self.unit(*unit_id),
+                self.room(*from),
+                self.room(*to)
+            ),
+            SimulationEvent::TrapTriggered {
+                trap_id, room_id, ..
+            } => format!("{} fired in {}", self.trap(*trap_id), self.room(*room_id)),
+            SimulationEvent::DamageApplied {
+                source,
+                target,
+                amount,
+                hp_before,
+                hp_after,
+                ..
+            } => match source {
+                DamageSource::Unit(attacker) => format!(
+                    "{} hit {} for {amount} dmg (HP {hp_before} → {hp_after})",
+                    self.unit(*attacker),
+                    self.unit(*target)
+                ),
+                DamageSource::Trap(trap_id) => format!(
+                    "{} took {amount} dmg from {} (HP {hp_before} → {hp_after})",
+                    self.unit(*target),
+                    self.trap(*trap_id)
+                ),
+                DamageSource::Status(kind) => format!(
+                    "{} took {amount} {} dmg (HP {hp_before} → {hp_after})",
+                    self.unit(*target),
+                    status_name(kind)
+                ),
+                DamageSource::Environment => format!(
+                    "{} took {amount} dmg (HP {hp_before} → {hp_after})",
+                    self.unit(*target)
+                ),
+            },
+            SimulationEvent::StatusApplied {
+                target,
+                kind,
+                magnitude,
+                duration_ticks,
+                ..
+            } => format!(
+                "{} is afflicted with {} (magnitude {magnitude}, {duration_ticks} ticks)",
+                self.unit(*target),
+                status_name(kind)
+            ),
+            SimulationEvent::UnitDied { unit_id, cause, .. } => {
+                let by = match cause {
+                    DamageSource::Unit(killer) => self.unit(*killer),
+                    DamageSource::Trap(trap_id) => self.trap(*trap_id),
+                    DamageSource::Status(kind) => status_name(kind).to_string(),
+                    DamageSource::Environment => "the environment".to_string(),
+                };
+                format!("{} died (killed by {by})", self.unit(*unit_id))
+            }
+            SimulationEvent::CoreDamaged {
+                amount,
+                core_hp_after,
+                ..
+            } => format!(
+                "Core took {amount} dmg (HP {} → {core_hp_after})",
+                core_hp_after + amount
+            ),
+        }
+    }
+
+    fn line(&self, tick: u32, text: String) -> String {
+        match self.format {
+            NarrativeFormat::PlainText => format!("T={tick}: {text}"),
+            NarrativeFormat::Markdown => format!("- **T={tick}**: {text}"),
+        }
+    }
+
+    fn quote(&self, name: &str) -> String {
+        match self.format {
+            NarrativeFormat::PlainText => format!("'{name}'"),
+            NarrativeFormat::Markdown => format!("`{name}`"),
+        }
+    }
+
+    fn unit(&self, unit_id: UnitId) -> String {
+        let Some(label) = self.units.get(&unit_id) else {
+            return format!("Unit#{}", unit_id.0);
+        };
+        let (prefix, name) = match label.kind {
+            UnitKind::Hero => (
+                "Hero",
+                label.template_id.as_deref().map(|id| {
+                    self.content
+                        .and_then(|c| c.hero(id))
+                        .map_or(id, |h| h.name.as_str())
+                }),
+            ),
+            UnitKind::Monster => (
+                "Monster",
+                label.template_id.as_deref().map(|id| {
+                    self.content
+                        .and_then(|c| c.monster(id))
+                        .map_or(id, |m| m.name.as_str())
+                }),
+            ),
+        };
+        match name {
+            Some(name) => format!("{prefix}#{} {}", unit_id.0, self.quote(name)),
+            None => format!("{prefix}#{}", unit_id.0),
+        }
+    }
+
+    fn trap(&self, trap_id: TrapId) -> String {
+        let name = self
+            .traps
+            .get(&trap_id)
+            .and_then(|template| template.as_deref())
+            .map(|id| {
+                self.content
+                    .and_then(|c| c.trap(id))
+                    .map_or(id, |t| t.name.as_str())
+            });
+        match name {
+            Some(name) => format!("trap {}", self.quote(name)),
+            None => format!("trap#{}", trap_id.0),
+        }
+    }
+
+    fn room(&self, room_id: RoomId) -> String {
+        let mut label = format!("Room#{}", room_id.0);
+        let Some((template, tags)) = self.rooms.get(&room_id) else {
+            return label;
+        };
+        let room_config = template
+            .as_deref()
+            .and_then(|id| self.content.and_then(|c| c.room(id)));
+        if let Some(name) = room_config.map(|r| r.name.as_str()).or(template.as_deref()) {
+            label.push(' ');
+            label.push_str(&self.quote(name));
+        }
+        let mut seen = HashSet::new();
+        let tags: Vec<&str> = tags
+            .iter()
+            .chain(room_config.into_iter().flat_map(|r| r.tags.iter()))
+            .map(String::as_str)
+            .filter(|tag| seen.insert(*tag))
+            .collect();
+        if !tags.is_empty() {
+            label.push_str(&format!(" [{}]", tags.join(", ")));
+        }
+        label
+    }
+}
+
+fn status_name(kind: &StatusKind) -> &'static str {
+    match kind {
+        StatusKind::Poison => "poison",
+        StatusKind::Burn => "burn",
+        StatusKind::Slow => "slow",
+        StatusKind::Stun => "stun",
+        StatusKind::BuffDamage => "damage buff",
+        StatusKind::BuffArmor => "armor buff",
+    }
+}
+
+/// Render the full event log of `result` as a narrative, one entry per line.
+pub fn render_narrative(
+    initial: &DungeonState,
+    result: &SimulationResult,
+    content: Option<&ContentPack>,
+    format: NarrativeFormat,
+) -> String {
+    let mut narrator = Narrator::new(initial, content, format);
+    narrator.lines(&result.events).join("\n")
+}
@@ -0,0 +1,535 @@
+//! Event-sourced reconstruction of simulation state.
+//!
+//! The client animates purely from the event log, so the log has to be complete: folding
+//! every [`SimulationEvent`] over the initial [`DungeonState`] must land on the same
+//! `final_dungeon`/`final_heroes` the engine reports. [`check_consistency`] performs that
+//! fold and lists every place where the two disagree.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::fixed::Fixed;
+use crate::model::{
+    DungeonState, RoomId, SimulationResult, StatusInstance, StatusKind, TrapId, UnitId,
+    UnitInstance,
+};
+use crate::sim::events::{EventId, SimulationEvent};
+
+/// A unit as seen through the event log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UnitView {
+    pub room_id: RoomId,
+    pub hp: i32,
+    pub statuses: Vec<StatusView>,
+}
+
+/// A status effect as seen through the event log.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StatusView {
+    pub kind: StatusKind,
+    pub remaining_ticks: u32,
+    pub magnitude: Fixed,
+}
+
+/// The slice of simulation state that can be rebuilt from events alone.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StateView {
+    pub units: BTreeMap<UnitId, UnitView>,
+    pub trap_charges: BTreeMap<TrapId, u32>,
+    pub core_hp: i32,
+}
+
+impl StateView {
+    /// Build the view of a dungeon before any events have been applied.
+    pub fn from_dungeon(dungeon: &DungeonState) -> Self {
+        let mut view = StateView {
+            core_hp: dungeon.core_hp,
+            ..StateView::default()
+        };
+        for room in &dungeon.rooms {
+            for trap in &room.traps {
+                view.trap_charges.insert(trap.id, trap.charges_used);
+            }
+            for monster in &room.monsters {
+                view.units.insert(monster.id, UnitView::from_unit(monster));
+            }
+        }
+        view
+    }
+}
+
+impl UnitView {
+    fn from_unit(unit: &UnitInstance) -> Self {
+        UnitView {
+            room_id: unit.room_id,
+            hp: unit.hp,
+            statuses: unit.status_effects.iter().map(StatusView::from).collect(),
+        }
+    }
+}
+
+impl From<&StatusInstance> for StatusView {
+    fn from(status: &StatusInstance) -> Self {
+        StatusView {
+            kind: status.kind.clone(),
+            remaining_ticks: status.remaining_ticks,
+            magnitude: status.magnitude,
+        }
+    }
+}
+
+/// A disagreement between the event log and the state the engine reported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Divergence {
+    /// An event refers to a unit that does not exist at that point in the log.
+    UnknownUnit {
+        event: EventId,
+        unit_id: UnitId,
+    },
+    /// An event refers to a trap that is not part of the dungeon.
+    UnknownTrap {
+        event: EventId,
+        trap_id: TrapId,
+    },
+    /// A unit was spawned under an id that is already in use.
+    DuplicateUnit {
+        event: Option<EventId>,
+        unit_id: UnitId,
+    },
+    /// An event's ids are out of order or point forward in the log.
+    BadEventId {
+        index: usize,
+        event: EventId,
+    },
+    /// A damage event's `hp_before` disagrees with the HP tracked so far.
+    HpBefore {
+        event: EventId,
+        unit_id: UnitId,
+        tracked: i32,
+        reported: i32,
+    },
+    /// A core damage event's `core_hp_after` disagrees with the tracked core HP.
+    CoreHpAfter {
+        event: EventId,
+        tracked: i32,
+        reported: i32,
+    },
+    /// The final state contains a unit that the events never produced.
+    MissingFromEvents {
+        unit_id: UnitId,
+    },
+    /// The events leave a unit alive that the final state does not contain.
+    MissingFromResult {
+        unit_id: UnitId,
+    },
+    UnitRoom {
+        unit_id: UnitId,
+        reconstructed: RoomId,
+        actual: RoomId,
+    },
+    UnitHp {
+        unit_id: UnitId,
+        reconstructed: i32,
+        actual: i32,
+    },
+    UnitStatuses {
+        unit_id: UnitId,
+        reconstructed: Vec<StatusView>,
+        actual: Vec<StatusView>,
+    },
+    TrapCharges {
+        trap_id: TrapId,
+        reconstructed: u32,
+        actual: u32,
+    },
+    CoreHp {
+        reconstructed: i32,
+        actual: i32,
+    },
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Divergence::UnknownUnit { event, unit_id } => {
+                write!(f, "event #{} refers to unknown unit {}", event.0, unit_id.0)
+            }
+            Divergence::UnknownTrap { event, trap_id } => {
+                write!(f, "event #{} refers to unknown trap {}", event.0, trap_id.0)
+            }
+            Divergence::DuplicateUnit { event, unit_id } => match event {
+                Some(event) => write!(f, "event #{} reuses unit id {}", event.0, unit_id.0),
+                None => write!(f, "unit id {} appears more than once", unit_id.0),
+            },
+            Divergence::BadEventId { index, event } => {
+                write!(f, "event at index {index} has id #{}", event.0)
+            }
+            Divergence::HpBefore {
+                event,
+                unit_id,
+                tracked,
+                reported,
+            } => write!(
+                f,
+                "event #{} reports unit {} at {reported} hp but events imply {tracked}",
+                event.0, unit_id.0
+            ),
+            Divergence::CoreHpAfter {
+                event,
+                tracked,
+                reported,
+            } => write!(
+                f,
+                "event #{} reports core at {reported} hp but events imply {tracked}",
+                event.0
+            ),
+            Divergence::MissingFromEvents { unit_id } => {
+                write!(
+                    f,
+                    "unit {} is in the result but not in the events",
+                    unit_id.0
+                )
+            }
+            Divergence::MissingFromResult { unit_id } => {
+                write!(
+                    f,
+                    "unit {} is alive in the events but not in the result",
+                    unit_id.0
+                )
+            }
+            Divergence::UnitRoom {
+                unit_id,
+                reconstructed,
+                actual,
+            } => write!(
+                f,
+                "unit {} ends in room {} but events place it in room {}",
+                unit_id.0, actual.0, reconstructed.0
+            ),
+            Divergence::UnitHp {
+                unit_id,
+                reconstructed,
+                actual,
+            } => write!(
+                f,
+                "unit {} ends at {actual} hp but events imply {reconstructed}",
+                unit_id.0
+            ),
+            Divergence::UnitStatuses {
+                unit_id,
+                reconstructed,
+                actual,
+            } => write!(
+                f,
+                "unit {} ends with statuses {actual:?} but events imply {reconstructed:?}",
+                unit_id.0
+            ),
+            Divergence::TrapCharges {
+                trap_id,
+                reconstructed,
+                actual,
+            } => write!(
+                f,
+                "trap {} ends with {actual} charges used but events imply {reconstructed}",
+                trap_id.0
+            ),
+            Divergence::CoreHp {
+                reconstructed,
+                actual,
+            } => write!(
+                f,
+                "core ends at {actual} hp but events imply {reconstructed}"
+            ),
+        }
+    }
+}
+
+/// Every divergence found while checking a simulation result against its events.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConsistencyReport {
+    pub divergences: Vec<Divergence>,
+}
+
+impl ConsistencyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// Folds events over an initial [`StateView`], one at a time.
+///
+/// Status durations are not represented by events, so the reducer replays the engine's
+/// per-tick status countdown itself. Within a tick the engine spawns heroes before it
+/// ticks statuses; events caused by a spawn therefore land before that tick's countdown,
+/// everything else after it.
+pub struct EventReducer {
+    view: StateView,
+    divergences: Vec<Divergence>,
+    /// Number of ticks whose status countdown has been applied.
+    status_ticks_applied: u32,
+    /// Events of the current tick that descend from a `UnitSpawned`.
+    spawn_phase: HashSet<EventId>,
+    current_tick: u32,
+    next_index: usize,
+}
+
+impl EventReducer {
+    pub fn new(initial: &DungeonState) -> Self {
+        let mut divergences = Vec::new();
+        let mut seen = HashSet::new();
+        for monster in initial.rooms.iter().flat_map(|room| room.monsters.iter()) {
+            if !seen.insert(monster.id) {
+                divergences.push(Divergence::DuplicateUnit {
+                    event: None,
+                    unit_id: monster.id,
+                });
+            }
+        }
+
+        Self {
+            view: StateView::from_dungeon(initial),
+            divergences,
+            status_ticks_applied: 0,
+            spawn_phase: HashSet::new(),
+            current_tick: 0,
+            next_index: 0,
+        }
+    }
+
+    pub fn view(&self) -> &StateView {
+        &self.view
+    }
+
+    pub fn apply(&mut self, event: &SimulationEvent) {
+        let id = event.id();
+        if id.0 as usize != self.next_index || event.caused_by().is_some_and(|parent| parent >= id)
+        {
+            self.divergences.push(Divergence::BadEventId {
+                index: self.next_index,
+                event: id,
+            });
+        }
+        self.next_index += 1;
+
+        let tick = event.tick();
+        if tick != self.current_tick {
+            self.current_tick = tick;
+            self.spawn_phase.clear();
+        }
+        let in_spawn_phase = matches!(event, SimulationEvent::UnitSpawned { .. })
+            || event
+                .caused_by()
+                .is_some_and(|parent| self.spawn_phase.contains(&parent));
+        if in_spawn_phase {
+            self.spawn_phase.insert(id);
+            self.tick_statuses_until(tick);
+        } else {
+            self.tick_statuses_until(tick + 1);
+        }
+
+        match event {
+            SimulationEvent::UnitSpawned {
+                unit_id,
+                room_id,
+                hp,
+                ..
+            } => {
+                let unit = UnitView {
+                    room_id: *room_id,
+                    hp: *hp,
+                    statuses: Vec::new(),
+                };
+                if self.view.units.insert(*unit_id, unit).is_some() {
+                    self.divergences.push(Divergence::DuplicateUnit {
+                        event: Some(id),
+                        unit_id: *unit_id,
+                    });
+                }
+            }
+            SimulationEvent::UnitMoved { unit_id, to, .. } => {
+                if let Some(unit) = self.unit_mut(id, *unit_id) {
+                    unit.room_id = *to;
+                }
+            }
+            SimulationEvent::TrapTriggered { trap_id, .. } => {
+                match self.view.trap_charges.get_mut(trap_id) {
+                    Some(charges) => *charges += 1,
+                    None => self.divergences.push(Divergence::UnknownTrap {
+                        event: id,
+                        trap_id: *trap_id,
+                    }),
+                }
+            }
+            SimulationEvent::DamageApplied {
+                target,
+                hp_before,
+                hp_after,
+                ..
+            } => {
+                let mismatch = self.unit_mut(id, *target).and_then(|unit| {
+                    let tracked = unit.hp;
+                    unit.hp = *hp_after;
+                    (tracked != *hp_before).then_some(tracked)
+                });
+                if let Some(tracked) = mismatch {
+                    self.divergences.push(Divergence::HpBefore {
+                        event: id,
+                        unit_id: *target,
+                        tracked,
+                        reported: *hp_before,
+                    });
+                }
+            }
+            SimulationEvent::StatusApplied {
+                target,
+                kind,
+                magnitude,
+                duration_ticks,
+                ..
+            } => {
+                if let Some(unit) = self.unit_mut(id, *target) {
+                    unit.statuses.push(StatusView {
+                        kind: kind.clone(),
+                        remaining_ticks: *duration_ticks,
+                        magnitude: *magnitude,
+                    });
+                }
+            }
+            SimulationEvent::UnitDied { unit_id, .. } => {
+                if self.view.units.remove(unit_id).is_none() {
+                    self.divergences.push(Divergence::UnknownUnit {
+                        event: id,
+                        unit_id: *unit_id,
+                    });
+                }
+            }
+            SimulationEvent::CoreDamaged {
+                amount,
+                core_hp_after,
+                ..
+            } => {
+                let tracked = self.view.core_hp - amount;
+                if tracked != *core_hp_after {
+                    self.divergences.push(Divergence::CoreHpAfter {
+                        event: id,
+                        tracked,
+                        reported: *core_hp_after,
+                    });
+                }
+                self.view.core_hp = *core_hp_after;
+            }
+        }
+    }
+
+    /// Apply the remaining status countdowns for a run of `ticks_run` ticks and return the
+    /// reconstructed view together with any divergences found along the way.
+    pub fn finish(mut self, ticks_run: u32) -> (StateView, Vec<Divergence>) {
+        self.tick_statuses_until(ticks_run);
+        (self.view, self.divergences)
+    }
+
+    fn unit_mut(&mut self, event: EventId, unit_id: UnitId) -> Option<&mut UnitView> {
+        let unit = self.view.units.get_mut(&unit_id);
+        if unit.is_none() {
+            self.divergences
+                .push(Divergence::UnknownUnit { event, unit_id });
+        }
+        unit
+    }
+
+    fn tick_statuses_until(&mut self, tick: u32) {
+        while self.status_ticks_applied < tick {
+            for unit in self.view.units.values_mut() {
+                for status in unit.statuses.iter_mut() {
+                    status.remaining_ticks = status.remaining_ticks.saturating_sub(1);
+                }
+                unit.statuses.retain(|s| s.remaining_ticks > 0);
+            }
+            self.status_ticks_applied += 1;
+        }
+    }
+}
+
+/// Fold every event in `result` over `initial` and compare the outcome with the final
+/// state reported in `result`.
+pub fn check_consistency(initial: &DungeonState, result: &SimulationResult) -> ConsistencyReport {
+    let mut reducer = EventReducer::new(initial);
+    for event in &result.events {
+        reducer.apply(event);
+    }
+    let (view, mut divergences) = reducer.finish(result.stats.ticks_run);
+
+    let mut actual_units: BTreeMap<UnitId, UnitView> = BTreeMap::new();
+    let final_units = result
+        .final_dungeon
+        .rooms
+        .iter()
+        .flat_map(|room| room.monsters.iter())
+        .chain(result.final_heroes.iter());
+    for unit in final_units {
+        if actual_units
+            .insert(unit.id, UnitView::from_unit(unit))
+            .is_some()
+        {
+            divergences.push(Divergence::DuplicateUnit {
+                event: None,
+                unit_id: unit.id,
+            });
+        }
+    }
+
+    for (unit_id, actual) in &actual_units {
+        let Some(reconstructed) = view.units.get(unit_id) else {
+            divergences.push(Divergence::MissingFromEvents { unit_id: *unit_id });
+            continue;
+        };
+        if reconstructed.room_id != actual.room_id {
+            divergences.push(Divergence::UnitRoom {
+                unit_id: *unit_id,
+                reconstructed: reconstructed.room_id,
+                actual: actual.room_id,
+            });
+        }
+        if reconstructed.hp != actual.hp {
+            divergences.push(Divergence::UnitHp {
+                unit_id: *unit_id,
+                reconstructed: reconstructed.hp,
+                actual: actual.hp,
+            });
+        }
+        if reconstructed.statuses != actual.statuses {
+            divergences.push(Divergence::UnitStatuses {
+                unit_id: *unit_id,
+                reconstructed: reconstructed.statuses.clone(),
+                actual: actual.statuses.clone(),
+            });
+        }
+    }
+    for unit_id in view.units.keys() {
+        if !actual_units.contains_key(unit_id) {
+            divergences.push(Divergence::MissingFromResult { unit_id: *unit_id });
+        }
+    }
+
+    for room in &result.final_dungeon.rooms {
+        for trap in &room.traps {
+            let reconstructed = view.trap_charges.get(&trap.id).copied().unwrap_or(0);
+            if reconstructed != trap.charges_used {
+                divergences.push(Divergence::TrapCharges {
+                    trap_id: trap.id,
+                    reconstructed,
+                    actual: trap.charges_used,
+                });
+            }
+        }
+    }
+
+    if view.core_hp != result.final_dungeon.core_hp {
+        divergences.push(Divergence::CoreHp {
+            reconstructed: view.core_hp,
+            actual: result.final_dungeon.core_hp,
+        });
+    }
+
+    ConsistencyReport { divergences }
+}
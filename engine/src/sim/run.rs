@@ -0,0 +1,358 @@
+//! Multi-wave runs.
+//!
+//! [`simulate_run`] chains [`crate::simulate_wave`]-style waves over one dungeon. The final
+//! dungeon of each wave, after the [`BetweenWaveRules`] are applied, is the starting dungeon
+//! of the next, and surviving heroes stay where they are. Every wave seed is derived from
+//! the run seed, so a run is reproducible from `(dungeon, waves, run_seed, rules)` alone.
+//! [`RunState`] exposes the same loop one wave at a time for flows that draft in between.
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::ENGINE_VERSION;
+use crate::draft::{self, DraftOffer, DraftPick};
+use crate::error::SimError;
+use crate::model::{
+    ContentPack, DungeonState, SimulationOutcome, SimulationResult, UnitId, UnitInstance,
+    WaveConfig,
+};
+use crate::rng::derive_seed;
+use crate::sim::WaveContext;
+use crate::sim::config::SimulationConfig;
+use crate::sim::scoring::{NEUTRAL_MULTIPLIER, RunScore, ScoreContext, relic_multiplier_permille};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// What happens to the dungeon between two waves of a run. Monster attack cooldowns are
+/// always reset so every wave starts with the defenders ready.
+pub struct BetweenWaveRules {
+    /// Reset every trap's charges and cooldown.
+    pub recharge_traps: bool,
+    /// Bring monsters killed during the wave back at full HP in the room they started it in.
+    pub respawn_monsters: bool,
+    /// Heal surviving monsters by this percentage of their max HP.
+    pub monster_heal_percent: u32,
+    /// Core HP restored, capped at the core HP the run started with.
+    pub core_repair: i32,
+    /// Heroes alive at the end of a wave stay in the dungeon for the next one.
+    pub carry_over_heroes: bool,
+}
+
+impl Default for BetweenWaveRules {
+    fn default() -> Self {
+        Self {
+            recharge_traps: true,
+            respawn_monsters: false,
+            monster_heal_percent: 0,
+            core_repair: 0,
+            carry_over_heroes: true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", default)]
+/// Rules for a whole run: per-wave simulation settings plus the between-wave rules.
+///
+/// # JSON example
+/// ```json
+/// {
+///   "max_ticks_per_wave": 2000,
+///   "waves_per_tier": 5,
+///   "between_waves": { "respawn_monsters": true, "core_repair": 10 }
+/// }
+/// ```
+pub struct RunRules {
+    pub config: SimulationConfig,
+    pub max_ticks_per_wave: u32,
+    /// Waves per tier; waves `0..waves_per_tier` are tier 1, the next block tier 2, and so on.
+    pub waves_per_tier: u32,
+    pub between_waves: BetweenWaveRules,
+}
+
+impl RunRules {
+    /// Tier of wave `wave_index`, which is also the tier of the draft before it.
+    pub fn tier_for_wave(&self, wave_index: usize) -> u32 {
+        let tier = wave_index / self.waves_per_tier.max(1) as usize + 1;
+        u32::try_from(tier).unwrap_or(u32::MAX)
+    }
+}
+
+impl Default for RunRules {
+    fn default() -> Self {
+        let config = SimulationConfig::default();
+        Self {
+            max_ticks_per_wave: config.limits.max_ticks,
+            waves_per_tier: 5,
+            config,
+            between_waves: BetweenWaveRules::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct RunSummary {
+    /// `DungeonWin` if the core survived every wave, `HeroesWin` if a wave destroyed it.
+    pub outcome: SimulationOutcome,
+    /// Waves that ended with the core still standing.
+    pub waves_cleared: u32,
+    pub core_hp_remaining: i32,
+    pub ticks_run: u32,
+    pub heroes_spawned: u32,
+    pub heroes_killed: u32,
+    pub monsters_killed: u32,
+    pub total_damage_to_core: i32,
+    pub score: RunScore,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+pub struct RunResult {
+    /// One result per simulated wave, in order. Waves after the core falls are not run.
+    pub waves: Vec<SimulationResult>,
+    pub summary: RunSummary,
+    /// The dungeon as the last simulated wave left it.
+    pub final_dungeon: DungeonState,
+    pub engine_version: String,
+}
+
+/// A run in progress, advanced one wave at a time.
+///
+/// [`simulate_run`] is the common case; `RunState` exists for run flows that change the
+/// dungeon between waves, such as drafting. After each wave the [`BetweenWaveRules`] are
+/// applied, so [`RunState::dungeon`] is always the dungeon the next wave will start from.
+#[derive(Clone, Debug)]
+pub struct RunState {
+    run_seed: u64,
+    rules: RunRules,
+    starting_core_hp: i32,
+    current: DungeonState,
+    heroes: Vec<UnitInstance>,
+    next_unit_id: u64,
+    /// Relic score multiplier of the current dungeon, in thousandths.
+    score_multiplier: u32,
+    waves: Vec<SimulationResult>,
+    summary: RunSummary,
+}
+
+impl RunState {
+    pub fn new(dungeon: DungeonState, run_seed: u64, rules: RunRules) -> Self {
+        let summary = RunSummary {
+            outcome: SimulationOutcome::DungeonWin,
+            waves_cleared: 0,
+            core_hp_remaining: dungeon.core_hp,
+            ticks_run: 0,
+            heroes_spawned: 0,
+            heroes_killed: 0,
+            monsters_killed: 0,
+            total_damage_to_core: 0,
+            score: RunScore::default(),
+        };
+        Self {
+            run_seed,
+            rules,
+            starting_core_hp: dungeon.core_hp,
+            current: dungeon,
+            heroes: Vec::new(),
+            next_unit_id: 0,
+            score_multiplier: NEUTRAL_MULTIPLIER,
+            waves: Vec::new(),
+            summary,
+        }
+    }
+
+    /// The dungeon the next wave starts from.
+    pub fn dungeon(&self) -> &DungeonState {
+        &self.current
+    }
+
+    /// Number of waves simulated so far; also the index of the next wave.
+    pub fn waves_played(&self) -> usize {
+        self.waves.len()
+    }
+
+    /// True once a wave has destroyed the core.
+    pub fn is_over(&self) -> bool {
+        self.summary.outcome == SimulationOutcome::HeroesWin
+    }
+
+    /// Apply a draft pick to the dungeon the next wave starts from.
+    ///
+    /// Drafted monsters get ids above every hero id handed out so far, so they never collide
+    /// with heroes carried into the next wave. The relic score multiplier is recomputed from
+    /// `content` for every relic in the dungeon.
+    pub fn apply_pick(
+        &mut self,
+        content: &ContentPack,
+        offer: &DraftOffer,
+        pick: &DraftPick,
+    ) -> Result<(), SimError> {
+        let existing: HashSet<UnitId> = monster_ids(&self.current).collect();
+        let mut dungeon = self.current.clone();
+        draft::apply_pick(&mut dungeon, content, offer, pick)?;
+        for monster in dungeon.rooms.iter_mut().flat_map(|r| r.monsters.iter_mut()) {
+            if existing.contains(&monster.id) || u64::from(monster.id.0) >= self.next_unit_id {
+                continue;
+            }
+            monster.id =
+                UnitId(u32::try_from(self.next_unit_id).map_err(|_| SimError::EntityLimit)?);
+            self.next_unit_id += 1;
+        }
+        self.score_multiplier = relic_multiplier_permille(&dungeon.modifiers, content);
+        self.current = dungeon;
+        Ok(())
+    }
+
+    /// Simulate `wave` as the next wave of the run.
+    ///
+    /// Wave `i` runs with seed `derive_seed(run_seed, i)`. Playing a wave after the core has
+    /// fallen is a no-op that returns the last result.
+    pub fn play_wave(&mut self, wave: &WaveConfig) -> Result<&SimulationResult, SimError> {
+        if self.is_over() {
+            return Ok(self.waves.last().expect("a finished run has played a wave"));
+        }
+        let wave_start = self.current.clone();
+        let context = WaveContext {
+            score: ScoreContext {
+                tier: self.rules.tier_for_wave(self.waves.len()),
+                multiplier_permille: self.score_multiplier,
+            },
+            carried_heroes: std::mem::take(&mut self.heroes),
+            next_unit_id: self.next_unit_id,
+        };
+        let (result, next_id) = super::run_wave(
+            self.current.clone(),
+            Cow::Borrowed(wave),
+            derive_seed(self.run_seed, self.waves.len() as u64),
+            self.rules.max_ticks_per_wave,
+            &self.rules.config,
+            context,
+        )?;
+        self.next_unit_id = next_id;
+
+        let summary = &mut self.summary;
+        summary.ticks_run += result.stats.ticks_run;
+        summary.heroes_spawned += result.stats.heroes_spawned;
+        summary.heroes_killed += result.stats.heroes_killed;
+        summary.monsters_killed += result.stats.monsters_killed;
+        summary.total_damage_to_core += result.stats.total_damage_to_core;
+        summary.core_hp_remaining = result.final_dungeon.core_hp;
+        summary.score.add(&result.score);
+        if result.outcome == SimulationOutcome::HeroesWin {
+            summary.outcome = SimulationOutcome::HeroesWin;
+        } else {
+            summary.waves_cleared += 1;
+        }
+
+        self.current = result.final_dungeon.clone();
+        if self.rules.between_waves.carry_over_heroes {
+            self.heroes = result.final_heroes.clone();
+        }
+        apply_between_wave_rules(
+            &mut self.current,
+            &wave_start,
+            self.starting_core_hp,
+            &self.rules.between_waves,
+        );
+        self.waves.push(result);
+        Ok(self.waves.last().expect("wave was just pushed"))
+    }
+
+    pub fn finish(self) -> RunResult {
+        let final_dungeon = match self.waves.last() {
+            Some(last) => last.final_dungeon.clone(),
+            None => self.current,
+        };
+        RunResult {
+            waves: self.waves,
+            summary: self.summary,
+            final_dungeon,
+            engine_version: ENGINE_VERSION.to_string(),
+        }
+    }
+}
+
+/// Simulate `waves` in order against `dungeon`, carrying state from wave to wave.
+///
+/// Wave `i` runs with seed `derive_seed(run_seed, i)`. The run stops after the first wave
+/// that destroys the core. Any wave error aborts the run.
+pub fn simulate_run(
+    dungeon: DungeonState,
+    waves: &[WaveConfig],
+    run_seed: u64,
+    rules: &RunRules,
+) -> Result<RunResult, SimError> {
+    let mut run = RunState::new(dungeon, run_seed, rules.clone());
+    for wave in waves {
+        run.play_wave(wave)?;
+        if run.is_over() {
+            break;
+        }
+    }
+    Ok(run.finish())
+}
+
+fn monster_ids(dungeon: &DungeonState) -> impl Iterator<Item = UnitId> + '_ {
+    dungeon
+        .rooms
+        .iter()
+        .flat_map(|r| r.monsters.iter().map(|m| m.id))
+}
+
+/// `wave_start` is the dungeon the wave began with; monsters missing from `dungeon` that
+/// were in it count as killed during the wave.
+fn apply_between_wave_rules(
+    dungeon: &mut DungeonState,
+    wave_start: &DungeonState,
+    starting_core_hp: i32,
+    rules: &BetweenWaveRules,
+) {
+    if rules.core_repair > 0 {
+        dungeon.core_hp = dungeon
+            .core_hp
+            .saturating_add(rules.core_repair)
+            .min(starting_core_hp.max(dungeon.core_hp));
+    }
+
+    for room in dungeon.rooms.iter_mut() {
+        if rules.recharge_traps {
+            for trap in room.traps.iter_mut() {
+                trap.charges_used = 0;
+                trap.cooldown_remaining = 0;
+            }
+        }
+
+        for monster in room.monsters.iter_mut() {
+            let heal =
+                i64::from(monster.stats.max_hp) * i64::from(rules.monster_heal_percent) / 100;
+            let healed = (i64::from(monster.hp) + heal).min(i64::from(monster.stats.max_hp));
+            monster.hp = monster.hp.max(healed as i32);
+            monster.attack_cooldown = 0;
+        }
+
+        if rules.respawn_monsters
+            && let Some(start) = wave_start.rooms.iter().find(|r| r.id == room.id)
+        {
+            let alive: HashSet<UnitId> = room.monsters.iter().map(|m| m.id).collect();
+            for original in &start.monsters {
+                if !alive.contains(&original.id) {
+                    let mut monster = original.clone();
+                    monster.hp = monster.stats.max_hp;
+                    monster.status_effects.clear();
+                    monster.attack_cooldown = 0;
+                    room.monsters.push(monster);
+                }
+            }
+            room.monsters.sort_by_key(|m| {
+                start
+                    .monsters
+                    .iter()
+                    .position(|o| o.id == m.id)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+    }
+}
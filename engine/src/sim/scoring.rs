@@ -0,0 +1,178 @@
+//! Deterministic wave and run scores.
+//!
+//! The score feeds run verification (contract/phase4.md §6.2) and leaderboards
+//! (contract/phase5.md), so client, backend and leaderboard must compute it identically.
+//! Everything is integer arithmetic with fixed constants; any change to the formula bumps
+//! [`SCORE_VERSION`] so stored scores can be told apart.
+//!
+//! A wave scores, before multipliers:
+//! - `HERO_KILL_POINTS * tier` per hero killed,
+//! - up to `CORE_HP_POINTS` for the share of core HP still standing, if the core survived,
+//! - up to `EFFICIENCY_POINTS` for a `DungeonWin`, scaled by the unused share of
+//!   `max_ticks`.
+//!
+//! The subtotal is then scaled by the wave's relic multiplier. A run's score is the sum of
+//! its wave scores.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::fixed::Fixed;
+use crate::model::{ContentPack, SimulationOutcome, SimulationStats};
+
+/// Version of the scoring formula.
+pub const SCORE_VERSION: u32 = 1;
+
+/// Points per hero killed at tier 1.
+pub const HERO_KILL_POINTS: i64 = 10;
+/// Points for ending a wave with the core at the HP it started with.
+pub const CORE_HP_POINTS: i64 = 100;
+/// Points for a `DungeonWin` on the very first tick.
+pub const EFFICIENCY_POINTS: i64 = 50;
+/// Relic `effect_type` whose `effect_multiplier` scales the score.
+pub const SCORE_MULTIPLIER_EFFECT: &str = "score_multiplier";
+/// A multiplier of 1, in thousandths.
+pub const NEUTRAL_MULTIPLIER: u32 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+/// Run context a wave is scored in; [`crate::simulate_wave`] uses the default.
+pub struct ScoreContext {
+    /// Tier of the wave, weighting hero kills. Tier 0 is scored as tier 1.
+    pub tier: u32,
+    /// Product of the dungeon's relic score multipliers, in thousandths.
+    pub multiplier_permille: u32,
+}
+
+impl Default for ScoreContext {
+    fn default() -> Self {
+        Self {
+            tier: 1,
+            multiplier_permille: NEUTRAL_MULTIPLIER,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+/// Score of one wave, component by component.
+pub struct WaveScore {
+    pub version: u32,
+    pub tier: u32,
+    #[ts(type = "number")]
+    pub hero_kills: i64,
+    #[ts(type = "number")]
+    pub core_hp: i64,
+    #[ts(type = "number")]
+    pub efficiency: i64,
+    pub multiplier_permille: u32,
+    /// Points added (or removed) by the relic multiplier.
+    #[ts(type = "number")]
+    pub relic_bonus: i64,
+    #[ts(type = "number")]
+    pub total: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+/// Score of a run: each component summed over its waves.
+pub struct RunScore {
+    pub version: u32,
+    #[ts(type = "number")]
+    pub hero_kills: i64,
+    #[ts(type = "number")]
+    pub core_hp: i64,
+    #[ts(type = "number")]
+    pub efficiency: i64,
+    #[ts(type = "number")]
+    pub relic_bonus: i64,
+    #[ts(type = "number")]
+    pub total: i64,
+}
+
+impl Default for RunScore {
+    fn default() -> Self {
+        Self {
+            version: SCORE_VERSION,
+            hero_kills: 0,
+            core_hp: 0,
+            efficiency: 0,
+            relic_bonus: 0,
+            total: 0,
+        }
+    }
+}
+
+impl RunScore {
+    pub fn add(&mut self, wave: &WaveScore) {
+        self.hero_kills = self.hero_kills.saturating_add(wave.hero_kills);
+        self.core_hp = self.core_hp.saturating_add(wave.core_hp);
+        self.efficiency = self.efficiency.saturating_add(wave.efficiency);
+        self.relic_bonus = self.relic_bonus.saturating_add(wave.relic_bonus);
+        self.total = self.total.saturating_add(wave.total);
+    }
+}
+
+/// Score a finished wave. `starting_core_hp` is the core HP the wave began with.
+pub fn score_wave(
+    outcome: &SimulationOutcome,
+    stats: &SimulationStats,
+    starting_core_hp: i32,
+    core_hp_remaining: i32,
+    max_ticks: u32,
+    context: ScoreContext,
+) -> WaveScore {
+    let tier = context.tier.max(1);
+    let hero_kills = i64::from(stats.heroes_killed)
+        .saturating_mul(HERO_KILL_POINTS)
+        .saturating_mul(i64::from(tier));
+
+    let core_hp = if *outcome == SimulationOutcome::HeroesWin || starting_core_hp <= 0 {
+        0
+    } else {
+        let remaining = i64::from(core_hp_remaining.clamp(0, starting_core_hp));
+        CORE_HP_POINTS * remaining / i64::from(starting_core_hp)
+    };
+
+    let efficiency = if *outcome == SimulationOutcome::DungeonWin && max_ticks > 0 {
+        let unused = i64::from(max_ticks.saturating_sub(stats.ticks_run));
+        EFFICIENCY_POINTS * unused / i64::from(max_ticks)
+    } else {
+        0
+    };
+
+    let subtotal = hero_kills.saturating_add(core_hp + efficiency);
+    let scaled = i128::from(subtotal) * i128::from(context.multiplier_permille)
+        / i128::from(NEUTRAL_MULTIPLIER);
+    let total = i64::try_from(scaled).unwrap_or(i64::MAX);
+    WaveScore {
+        version: SCORE_VERSION,
+        tier,
+        hero_kills,
+        core_hp,
+        efficiency,
+        multiplier_permille: context.multiplier_permille,
+        relic_bonus: total.saturating_sub(subtotal),
+        total,
+    }
+}
+
+/// Combined score multiplier of the relics in `modifiers`, in thousandths.
+///
+/// Each relic with the [`SCORE_MULTIPLIER_EFFECT`] effect contributes its
+/// `effect_multiplier` (a [`Fixed`], so already in thousandths); unknown ids and other effects count as 1.
+pub fn relic_multiplier_permille(modifiers: &[String], content: &ContentPack) -> u32 {
+    let mut permille = u64::from(NEUTRAL_MULTIPLIER);
+    for relic in modifiers.iter().filter_map(|id| content.relic(id)) {
+        if relic.effect_type != SCORE_MULTIPLIER_EFFECT {
+            continue;
+        }
+        let Some(multiplier) = relic.effect_multiplier else {
+            continue;
+        };
+        let relic_permille = multiplier.max(Fixed::ZERO).milli().unsigned_abs();
+        permille = permille.saturating_mul(relic_permille) / u64::from(NEUTRAL_MULTIPLIER);
+    }
+    u32::try_from(permille).unwrap_or(u32::MAX)
+}
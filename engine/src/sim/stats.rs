@@ -0,0 +1,267 @@
+//! Statistics over batches of wave summaries, for balancing reports.
+//!
+//! This is reporting, not rules math, so floats are fine here. Win rates carry Wilson
+//! score intervals, which stay inside `[0, 1]` and behave at rates near 0% or 100% where
+//! the normal approximation does not. Distributions report nearest-rank percentiles and an
+//! equal-width histogram.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::SimulationOutcome;
+use crate::sim::batch::WaveSummary;
+
+/// Bins in a [`DistributionSummary::histogram`].
+pub const HISTOGRAM_BINS: usize = 10;
+
+/// Two-sided confidence interval around an observed rate.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ConfidenceInterval {
+    pub estimate: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn half_width(&self) -> f64 {
+        (self.high - self.low) / 2.0
+    }
+}
+
+/// Wilson score interval for `successes` out of `trials` at normal quantile `z`.
+/// No trials give the uninformative `[0, 1]`.
+pub fn wilson_interval(successes: u64, trials: u64, z: f64) -> ConfidenceInterval {
+    if trials == 0 {
+        return ConfidenceInterval {
+            estimate: 0.0,
+            low: 0.0,
+            high: 1.0,
+        };
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = z * z;
+    let denominator = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denominator;
+    let margin = z * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denominator;
+    ConfidenceInterval {
+        estimate: p,
+        low: (center - margin).max(0.0),
+        high: (center + margin).min(1.0),
+    }
+}
+
+/// Two-sided normal quantile for a confidence level in `(0, 1)`, e.g. `0.95 -> 1.96`.
+/// `None` outside that range.
+pub fn z_for_confidence(confidence: f64) -> Option<f64> {
+    if !(confidence > 0.0 && confidence < 1.0) {
+        return None;
+    }
+    Some(normal_quantile(0.5 + confidence / 2.0))
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation, relative error below
+/// 1.2e-9), for `p` in `(0, 1)`.
+fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const LOW: f64 = 0.024_25;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HistogramBin {
+    /// Inclusive bounds.
+    pub low: i64,
+    pub high: i64,
+    pub count: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DistributionSummary {
+    pub count: u64,
+    pub min: i64,
+    pub max: i64,
+    pub mean: f64,
+    pub p10: i64,
+    pub p50: i64,
+    pub p90: i64,
+    pub p99: i64,
+    /// Up to [`HISTOGRAM_BINS`] equal-width bins from `min` to `max`.
+    pub histogram: Vec<HistogramBin>,
+}
+
+/// Samples of one integer metric.
+#[derive(Clone, Debug, Default)]
+pub struct Distribution {
+    samples: Vec<i64>,
+}
+
+impl Distribution {
+    pub fn record(&mut self, value: i64) {
+        self.samples.push(value);
+    }
+
+    /// `None` until a sample has been recorded.
+    pub fn summary(&self) -> Option<DistributionSummary> {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let (&min, &max) = (sorted.first()?, sorted.last()?);
+        let count = sorted.len();
+        let percentile = |pct: usize| sorted[(pct * count).div_ceil(100).clamp(1, count) - 1];
+        let mean = sorted.iter().map(|&v| v as f64).sum::<f64>() / count as f64;
+
+        // Bins at least one unit wide, so small integer ranges get one bin per value.
+        let span = (max - min) as u64 + 1;
+        let width = span.div_ceil(HISTOGRAM_BINS as u64);
+        let bins = span.div_ceil(width);
+        let mut histogram: Vec<HistogramBin> = (0..bins)
+            .map(|bin| {
+                let low = min + (bin * width) as i64;
+                HistogramBin {
+                    low,
+                    high: (low + width as i64 - 1).min(max),
+                    count: 0,
+                }
+            })
+            .collect();
+        for &value in &sorted {
+            histogram[((value - min) as u64 / width) as usize].count += 1;
+        }
+
+        Some(DistributionSummary {
+            count: count as u64,
+            min,
+            max,
+            mean,
+            p10: percentile(10),
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            histogram,
+        })
+    }
+}
+
+/// Running statistics over a stress sweep, fed one [`WaveSummary`] at a time.
+#[derive(Clone, Debug, Default)]
+pub struct StressStats {
+    pub runs: u64,
+    pub dungeon_wins: u64,
+    pub hero_wins: u64,
+    pub timeouts: u64,
+    pub stalemates: u64,
+    pub core_damage: Distribution,
+    pub ticks: Distribution,
+    pub heroes_killed: Distribution,
+    pub monsters_lost: Distribution,
+}
+
+impl StressStats {
+    pub fn record(&mut self, summary: &WaveSummary) {
+        self.runs += 1;
+        match summary.outcome {
+            SimulationOutcome::DungeonWin => self.dungeon_wins += 1,
+            SimulationOutcome::HeroesWin => self.hero_wins += 1,
+            SimulationOutcome::Timeout => self.timeouts += 1,
+            SimulationOutcome::Stalemate(_) => self.stalemates += 1,
+        }
+        let stats = &summary.stats;
+        self.core_damage
+            .record(i64::from(stats.total_damage_to_core));
+        self.ticks.record(i64::from(stats.ticks_run));
+        self.heroes_killed.record(i64::from(stats.heroes_killed));
+        self.monsters_lost.record(i64::from(stats.monsters_killed));
+    }
+
+    pub fn dungeon_win_rate(&self, z: f64) -> ConfidenceInterval {
+        wilson_interval(self.dungeon_wins, self.runs, z)
+    }
+
+    pub fn hero_win_rate(&self, z: f64) -> ConfidenceInterval {
+        wilson_interval(self.hero_wins, self.runs, z)
+    }
+
+    /// Whether both win-rate intervals are at most `half_width` wide on either side.
+    pub fn is_precise(&self, z: f64, half_width: f64) -> bool {
+        self.runs > 0
+            && self.dungeon_win_rate(z).half_width() <= half_width
+            && self.hero_win_rate(z).half_width() <= half_width
+    }
+
+    pub fn report(&self, confidence: f64) -> Option<StressReport> {
+        let z = z_for_confidence(confidence)?;
+        Some(StressReport {
+            runs: self.runs,
+            confidence,
+            dungeon_wins: self.dungeon_wins,
+            hero_wins: self.hero_wins,
+            timeouts: self.timeouts,
+            stalemates: self.stalemates,
+            dungeon_win_rate: self.dungeon_win_rate(z),
+            hero_win_rate: self.hero_win_rate(z),
+            core_damage: self.core_damage.summary(),
+            ticks: self.ticks.summary(),
+            heroes_killed: self.heroes_killed.summary(),
+            monsters_lost: self.monsters_lost.summary(),
+        })
+    }
+}
+
+/// Snapshot of [`StressStats`] at a confidence level.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct StressReport {
+    pub runs: u64,
+    pub confidence: f64,
+    pub dungeon_wins: u64,
+    pub hero_wins: u64,
+    pub timeouts: u64,
+    pub stalemates: u64,
+    pub dungeon_win_rate: ConfidenceInterval,
+    pub hero_win_rate: ConfidenceInterval,
+    pub core_damage: Option<DistributionSummary>,
+    pub ticks: Option<DistributionSummary>,
+    pub heroes_killed: Option<DistributionSummary>,
+    pub monsters_lost: Option<DistributionSummary>,
+}
@@ -0,0 +1,231 @@
+//! A wave simulated a few ticks at a time, for callers that show the wave while it runs.
+//!
+//! [`simulate_wave`](super::simulate_wave) is this stepper run to the end, so stepping in
+//! any chunk sizes produces the same result as one call.
+
+use std::borrow::Cow;
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use ts_rs::TS;
+
+use crate::ENGINE_VERSION;
+use crate::error::SimError;
+use crate::model::{
+    DungeonState, SimulationOutcome, SimulationResult, SimulationStats, UnitInstance, WaveConfig,
+};
+use crate::sim::checksum::{TickChecksum, checksum_state};
+use crate::sim::config::SimulationConfig;
+use crate::sim::events::SimulationEvent;
+use crate::sim::scoring::score_wave;
+use crate::sim::tick::{SimState, step_tick};
+use crate::sim::{WaveContext, check_dungeon_limits, check_wave_limits, validate};
+
+/// One wave in progress. Build with [`WaveSimulation::new`], advance with [`Self::step`].
+///
+/// Waves started through the public API own their [`WaveConfig`]; batches borrow it so a
+/// seed sweep does not copy the wave per job.
+pub struct WaveSimulation<'w> {
+    state: SimState,
+    wave: Cow<'w, WaveConfig>,
+    max_ticks: u32,
+    starting_core_hp: i32,
+    context: WaveContext,
+    checksums: Vec<TickChecksum>,
+    outcome: Option<SimulationOutcome>,
+    /// Set when a tick failed; the state is then mid-tick and must not advance.
+    error: Option<SimError>,
+}
+
+/// Snapshot of a wave in progress, as returned by [`WaveSimulation::view`].
+#[derive(Clone, Debug, Serialize, JsonSchema, TS)]
+pub struct WaveView<'a> {
+    /// Ticks completed so far.
+    pub tick: u32,
+    pub max_ticks: u32,
+    pub dungeon: &'a DungeonState,
+    pub heroes: &'a [UnitInstance],
+    pub stats: &'a SimulationStats,
+    /// Events logged so far; the cursor for the next [`WaveSimulation::events_since`].
+    pub event_count: usize,
+    /// Set once the wave has finished.
+    #[ts(optional = nullable)]
+    pub outcome: Option<SimulationOutcome>,
+}
+
+impl WaveSimulation<'static> {
+    /// Check the inputs like [`simulate_wave`](super::simulate_wave) and set up tick 0.
+    pub fn new(
+        dungeon: DungeonState,
+        wave: WaveConfig,
+        seed: u64,
+        max_ticks: u32,
+        config: &SimulationConfig,
+    ) -> Result<Self, SimError> {
+        Self::with_context(
+            dungeon,
+            Cow::Owned(wave),
+            seed,
+            max_ticks,
+            config,
+            WaveContext::default(),
+        )
+    }
+}
+
+impl<'w> WaveSimulation<'w> {
+    pub(crate) fn with_context(
+        dungeon: DungeonState,
+        wave: Cow<'w, WaveConfig>,
+        seed: u64,
+        max_ticks: u32,
+        config: &SimulationConfig,
+        mut context: WaveContext,
+    ) -> Result<Self, SimError> {
+        check_dungeon_limits(&dungeon, &config.limits)?;
+        check_wave_limits(&wave, &config.limits)?;
+        if max_ticks > config.limits.max_ticks {
+            return Err(SimError::TickLimit);
+        }
+        let report = validate(&dungeon, &wave);
+        if report.has_errors() {
+            return Err(SimError::InvalidInput(report));
+        }
+
+        let starting_core_hp = dungeon.core_hp;
+        let mut state = SimState::with_config(dungeon, &wave, seed, config.clone())?;
+        let carried_heroes = std::mem::take(&mut context.carried_heroes);
+        state.carry_over(carried_heroes, context.next_unit_id)?;
+        Ok(Self {
+            state,
+            wave,
+            max_ticks,
+            starting_core_hp,
+            context,
+            checksums: Vec::new(),
+            outcome: None,
+            error: None,
+        })
+    }
+
+    /// Run up to `ticks` more ticks, stopping early when the wave finishes. Returns the
+    /// number of ticks run, which is 0 once finished. A failed tick's error is returned
+    /// again by every later call.
+    pub fn step(&mut self, ticks: u32) -> Result<u32, SimError> {
+        if let Some(err) = &self.error {
+            return Err(err.clone());
+        }
+        let mut ran = 0;
+        while ran < ticks && !self.is_finished() {
+            let finished = match step_tick(&mut self.state, &self.wave) {
+                Ok(finished) => finished,
+                Err(err) => {
+                    self.error = Some(err.clone());
+                    return Err(err);
+                }
+            };
+            ran += 1;
+            let interval = self.state.config.checksum_interval;
+            if interval > 0 && self.state.tick.is_multiple_of(interval) {
+                self.push_checksum();
+            }
+            self.outcome = finished;
+        }
+        Ok(ran)
+    }
+
+    /// True once the wave has an outcome, ran `max_ticks` ticks, or failed.
+    pub fn is_finished(&self) -> bool {
+        self.outcome.is_some() || self.state.tick >= self.max_ticks || self.error.is_some()
+    }
+
+    /// Ticks completed so far.
+    pub fn tick(&self) -> u32 {
+        self.state.tick
+    }
+
+    /// The outcome, once finished; a wave that ran out of ticks is a
+    /// [`SimulationOutcome::Timeout`].
+    pub fn outcome(&self) -> Option<SimulationOutcome> {
+        match &self.outcome {
+            Some(outcome) => Some(outcome.clone()),
+            None if self.error.is_none() && self.state.tick >= self.max_ticks => {
+                Some(SimulationOutcome::Timeout)
+            }
+            None => None,
+        }
+    }
+
+    /// Every event logged so far.
+    pub fn events(&self) -> &[SimulationEvent] {
+        self.state.events.as_slice()
+    }
+
+    /// Events logged after the first `cursor`; pass the previous view's `event_count`.
+    pub fn events_since(&self, cursor: usize) -> &[SimulationEvent] {
+        let events = self.events();
+        &events[cursor.min(events.len())..]
+    }
+
+    pub fn view(&self) -> WaveView<'_> {
+        WaveView {
+            tick: self.state.tick,
+            max_ticks: self.max_ticks,
+            dungeon: &self.state.dungeon,
+            heroes: &self.state.heroes,
+            stats: &self.state.stats,
+            event_count: self.state.events.len(),
+            outcome: self.outcome(),
+        }
+    }
+
+    /// Run the remaining ticks and build the result.
+    pub fn finish(self) -> Result<SimulationResult, SimError> {
+        self.finish_with_next_id().map(|(result, _)| result)
+    }
+
+    /// [`Self::finish`], also returning the next free hero id for the following wave.
+    pub(crate) fn finish_with_next_id(mut self) -> Result<(SimulationResult, u64), SimError> {
+        self.step(u32::MAX)?;
+        let interval = self.state.config.checksum_interval;
+        if interval > 0
+            && self
+                .checksums
+                .last()
+                .is_none_or(|last| last.tick != self.state.tick)
+        {
+            self.push_checksum();
+        }
+
+        let outcome = self.outcome().unwrap_or(SimulationOutcome::Timeout);
+        let state = self.state;
+        let next_unit_id = state.next_unit_id();
+        let score = score_wave(
+            &outcome,
+            &state.stats,
+            self.starting_core_hp,
+            state.dungeon.core_hp,
+            self.max_ticks,
+            self.context.score,
+        );
+        let result = SimulationResult {
+            outcome,
+            final_dungeon: state.dungeon,
+            final_heroes: state.heroes,
+            stats: state.stats,
+            events: state.events.into_events(),
+            config: state.config,
+            score,
+            checksums: self.checksums,
+            engine_version: ENGINE_VERSION.to_string(),
+        };
+        Ok((result, next_unit_id))
+    }
+
+    fn push_checksum(&mut self) {
+        self.checksums.push(TickChecksum {
+            tick: self.state.tick,
+            checksum: checksum_state(&self.state),
+        });
+    }
+}
@@ -1,9 +1,10 @@
 use crate::error::SimError;
+use crate::fixed::Fixed;
 use crate::model::{
     AiBehavior, DungeonState, Faction, HeroSpawn, RoomId, RoomState, StatusInstance, StatusKind,
     TrapId, TrapInstance, TrapTriggerType, UnitId, UnitInstance, UnitStats, WaveConfig,
 };
-use crate::sim::simulate_wave;
+use crate::sim::{SimulationConfig, simulate_wave};
 
 #[derive(Clone)]
 pub struct ScenarioFixture {
@@ -22,6 +23,7 @@ impl ScenarioFixture {
             self.wave.clone(),
             self.seed,
             self.max_ticks,
+            &SimulationConfig::default(),
         )
     }
 
@@ -40,6 +42,7 @@ pub fn movement_to_core() -> ScenarioFixture {
         edges,
         core_room_id: RoomId(2),
         core_hp: 15,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -76,9 +79,10 @@ pub fn trapped_entry_hall() -> ScenarioFixture {
         status_on_hit: Some(StatusInstance {
             kind: StatusKind::Poison,
             remaining_ticks: 2,
-            magnitude: 6.0,
+            magnitude: Fixed::from_milli(6000),
         }),
         tags: Vec::new(),
+        template_id: None,
     });
     let core = room(1);
 
@@ -87,6 +91,7 @@ pub fn trapped_entry_hall() -> ScenarioFixture {
         edges: vec![(RoomId(0), RoomId(1))],
         core_room_id: RoomId(1),
         core_hp: 50,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -115,7 +120,7 @@ pub fn core_room_duel() -> ScenarioFixture {
     let monster_stats = UnitStats {
         max_hp: 25,
         armor: 1,
-        move_speed: 0.0,
+        move_speed: Fixed::ZERO,
         attack_damage: 7,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -130,6 +135,7 @@ pub fn core_room_duel() -> ScenarioFixture {
         status_effects: Vec::new(),
         ai_behavior: AiBehavior::Aggressive,
         attack_cooldown: 0,
+        template_id: None,
     });
 
     let dungeon = DungeonState {
@@ -137,6 +143,7 @@ pub fn core_room_duel() -> ScenarioFixture {
         edges: Vec::new(),
         core_room_id: core_room.id,
         core_hp: 40,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -170,5 +177,6 @@ fn room(id: u32) -> RoomState {
         traps: Vec::new(),
         monsters: Vec::new(),
         tags: Vec::new(),
+        template_id: None,
     }
 }
@@ -1,12 +1,30 @@
-use crate::model::SimulationOutcome;
+use crate::draft::{DraftOffer, DraftOption, DraftPick};
+use crate::error::SimError;
+use crate::fixed::Fixed;
 use crate::model::{
     AiBehavior, DungeonState, Faction, RoomId, StatusKind, TrapId, UnitId, UnitInstance,
     WaveConfig, dungeon::RoomState, status::StatusInstance, trap::TrapInstance,
     trap::TrapTriggerType, unit::UnitStats, wave::HeroSpawn,
 };
+use crate::model::{
+    ContentPack, Rarity, SimulationOutcome, SimulationResult, SimulationStats, StalemateReason,
+};
+use crate::rng::{Rng, RngStream, RngStreams};
+use crate::sim::batch::simulate_batch_on;
+use crate::sim::events::{DamageSource, EventId, SimulationEvent, cause_chain};
+use crate::sim::narrative::{NarrativeFormat, render_narrative};
+use crate::sim::reducer::{Divergence, check_consistency};
+use crate::sim::scoring::{relic_multiplier_permille, score_wave};
+use crate::sim::stats::{Distribution, StressStats, wilson_interval, z_for_confidence};
 use crate::sim::tick::{SimState, step_tick};
-use crate::sim::{simulate_wave, test_fixtures};
+use crate::sim::validation::{IssueCode, Severity, validate};
+use crate::sim::{
+    BatchJob, RunRules, RunState, SCORE_VERSION, ScoreContext, SimulationConfig, TickChecksum,
+    WaveScore, WaveSimulation, first_divergence, simulate_batch, simulate_batch_with, simulate_run,
+    simulate_wave, test_fixtures,
+};
 use proptest::prelude::*;
+use proptest::strategy::ValueTree;
 use std::collections::HashSet;
 
 fn basic_room(id: u32) -> RoomState {
@@ -15,6 +33,7 @@ fn basic_room(id: u32) -> RoomState {
         traps: Vec::new(),
         monsters: Vec::new(),
         tags: Vec::new(),
+        template_id: None,
     }
 }
 
@@ -28,6 +47,7 @@ fn monster(id: u32, room_id: RoomId, stats: UnitStats, hp: i32) -> UnitInstance
         status_effects: Vec::new(),
         ai_behavior: AiBehavior::Aggressive,
         attack_cooldown: 0,
+        template_id: None,
     }
 }
 
@@ -41,6 +61,7 @@ fn heroes_can_destroy_core() {
         edges: vec![(room0.id, room1.id)],
         core_room_id: room1.id,
         core_hp: 5,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -54,7 +75,8 @@ fn heroes_can_destroy_core() {
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 1, 10).expect("simulation should succeed");
+    let result = simulate_wave(dungeon, wave, 1, 10, &SimulationConfig::default())
+        .expect("simulation should succeed");
     assert_eq!(SimulationOutcome::HeroesWin, result.outcome);
     assert!(result.final_dungeon.core_hp <= 0);
     assert_eq!(1, result.stats.heroes_spawned);
@@ -70,6 +92,7 @@ fn simulations_can_timeout() {
         edges: vec![(room0.id, room1.id)],
         core_room_id: room1.id,
         core_hp: 1_000,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -83,7 +106,8 @@ fn simulations_can_timeout() {
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 42, 1).expect("simulation should succeed");
+    let result = simulate_wave(dungeon, wave, 42, 1, &SimulationConfig::default())
+        .expect("simulation should succeed");
     assert_eq!(SimulationOutcome::Timeout, result.outcome);
     assert_eq!(1, result.stats.heroes_spawned);
     assert_eq!(1, result.stats.ticks_run);
@@ -95,7 +119,7 @@ fn monsters_can_defend_core() {
     let monster_stats = UnitStats {
         max_hp: 30,
         armor: 0,
-        move_speed: 0.0,
+        move_speed: Fixed::ZERO,
         attack_damage: 25,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -109,6 +133,7 @@ fn monsters_can_defend_core() {
         edges: vec![],
         core_room_id: core_room.id,
         core_hp: 50,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -122,7 +147,8 @@ fn monsters_can_defend_core() {
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 2, 5).expect("simulation should succeed");
+    let result = simulate_wave(dungeon, wave, 2, 5, &SimulationConfig::default())
+        .expect("simulation should succeed");
     assert_eq!(SimulationOutcome::DungeonWin, result.outcome);
     assert_eq!(1, result.stats.heroes_killed);
 }
@@ -137,6 +163,7 @@ fn heroes_use_template_stats_when_spawning() {
         edges: vec![(room0.id, room1.id)],
         core_room_id: room1.id,
         core_hp: 25,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -166,7 +193,7 @@ fn heroes_use_template_stats_when_spawning() {
     let h1_stats = UnitStats {
         max_hp: 20,
         armor: 0,
-        move_speed: 1.0,
+        move_speed: Fixed::ONE,
         attack_damage: 5,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -174,7 +201,7 @@ fn heroes_use_template_stats_when_spawning() {
     let h2_stats = UnitStats {
         max_hp: 24,
         armor: 1,
-        move_speed: 0.9,
+        move_speed: Fixed::from_milli(900),
         attack_damage: 6,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -200,6 +227,7 @@ fn wave_modifiers_are_applied_to_heroes() {
         edges: vec![(room0.id, room1.id)],
         core_room_id: room1.id,
         core_hp: 30,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -240,9 +268,10 @@ fn traps_apply_status_and_kill() {
         status_on_hit: Some(StatusInstance {
             kind: StatusKind::Poison,
             remaining_ticks: 1,
-            magnitude: 15.0,
+            magnitude: Fixed::from_milli(15000),
         }),
         tags: Vec::new(),
+        template_id: None,
     });
     let room1 = basic_room(1);
 
@@ -251,6 +280,7 @@ fn traps_apply_status_and_kill() {
         edges: vec![(room0.id, room1.id)],
         core_room_id: room1.id,
         core_hp: 50,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -264,11 +294,105 @@ fn traps_apply_status_and_kill() {
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 3, 5).expect("simulation should succeed");
+    let result = simulate_wave(dungeon, wave, 3, 5, &SimulationConfig::default())
+        .expect("simulation should succeed");
     assert_eq!(SimulationOutcome::DungeonWin, result.outcome);
     assert_eq!(1, result.stats.heroes_killed);
 }
 
+#[test]
+fn deaths_are_attributed_to_the_killing_blow() {
+    let mut room0 = basic_room(0);
+    room0.traps.push(TrapInstance {
+        id: TrapId(7),
+        trigger_type: TrapTriggerType::OnEnter,
+        cooldown_ticks: 0,
+        cooldown_remaining: 0,
+        max_charges: None,
+        charges_used: 0,
+        damage: 50,
+        status_on_hit: None,
+        tags: Vec::new(),
+        template_id: None,
+    });
+    let room1 = basic_room(1);
+
+    let dungeon = DungeonState {
+        rooms: vec![room0.clone(), room1.clone()],
+        edges: vec![(room0.id, room1.id)],
+        core_room_id: room1.id,
+        core_hp: 50,
+        modifiers: Vec::new(),
+    };
+
+    let wave = WaveConfig {
+        id: "trap-kill".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id: room0.id,
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+
+    let result = simulate_wave(dungeon, wave, 3, 5, &SimulationConfig::default())
+        .expect("simulation should succeed");
+
+    assert!(result.events.iter().any(|e| matches!(
+        e,
+        SimulationEvent::DamageApplied {
+            source: DamageSource::Trap(TrapId(7)),
+            hp_before: 20,
+            hp_after: -30,
+            ..
+        }
+    )));
+    assert!(result.events.iter().any(|e| matches!(
+        e,
+        SimulationEvent::UnitDied {
+            killer: None,
+            cause: DamageSource::Trap(TrapId(7)),
+            ..
+        }
+    )));
+}
+
+#[test]
+fn trap_chains_link_trigger_damage_status_and_death() {
+    let fixture = test_fixtures::trapped_entry_hall();
+    let result = fixture.run().expect("simulation should succeed");
+
+    for (idx, event) in result.events.iter().enumerate() {
+        assert_eq!(EventId(idx as u32), event.id(), "ids follow log order");
+        if let Some(parent) = event.caused_by() {
+            assert!(parent < event.id(), "causes precede their effects");
+        }
+    }
+
+    let death = result
+        .events
+        .iter()
+        .find(|e| matches!(e, SimulationEvent::UnitDied { .. }))
+        .expect("hero should die");
+    let chain: Vec<&str> = cause_chain(&result.events, death.id())
+        .into_iter()
+        .map(|event| match event {
+            SimulationEvent::UnitSpawned { .. } => "spawned",
+            SimulationEvent::TrapTriggered { .. } => "trap",
+            SimulationEvent::DamageApplied { .. } => "damage",
+            SimulationEvent::StatusApplied { .. } => "status",
+            SimulationEvent::UnitDied { .. } => "died",
+            _ => "other",
+        })
+        .collect();
+
+    assert_eq!(
+        vec!["spawned", "trap", "damage", "status", "damage", "died"],
+        chain
+    );
+}
+
 #[test]
 fn timed_traps_apply_status_and_damage() {
     let mut room0 = basic_room(0);
@@ -283,9 +407,10 @@ fn timed_traps_apply_status_and_damage() {
         status_on_hit: Some(StatusInstance {
             kind: StatusKind::Burn,
             remaining_ticks: 2,
-            magnitude: 6.0,
+            magnitude: Fixed::from_milli(6000),
         }),
         tags: Vec::new(),
+        template_id: None,
     });
 
     let dungeon = DungeonState {
@@ -293,6 +418,7 @@ fn timed_traps_apply_status_and_damage() {
         edges: vec![],
         core_room_id: room0.id,
         core_hp: 50,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -306,7 +432,8 @@ fn timed_traps_apply_status_and_damage() {
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 5, 3).expect("simulation should succeed");
+    let result = simulate_wave(dungeon, wave, 5, 3, &SimulationConfig::default())
+        .expect("simulation should succeed");
 
     assert_eq!(SimulationOutcome::DungeonWin, result.outcome);
     assert_eq!(1, result.stats.heroes_spawned);
@@ -324,6 +451,68 @@ fn timed_traps_apply_status_and_damage() {
     )));
 }
 
+#[test]
+fn poison_and_burn_are_mitigated_once_and_stop_at_death() {
+    let stats = UnitStats {
+        max_hp: 20,
+        armor: 3,
+        move_speed: Fixed::ZERO,
+        attack_damage: 0,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    let dot = |kind: StatusKind, magnitude: i64| StatusInstance {
+        kind,
+        remaining_ticks: 5,
+        magnitude: Fixed::from_milli(magnitude * 1000),
+    };
+    let mut room0 = basic_room(0);
+    for (id, hp) in [(0, 20), (1, 2)] {
+        let mut unit = monster(id, room0.id, stats.clone(), hp);
+        unit.status_effects = vec![dot(StatusKind::Poison, 4), dot(StatusKind::Burn, 4)];
+        room0.monsters.push(unit);
+    }
+    let dungeon = DungeonState {
+        rooms: vec![room0],
+        edges: vec![],
+        core_room_id: RoomId(0),
+        core_hp: 50,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "dots".into(),
+        entries: Vec::new(),
+        modifiers: Vec::new(),
+    };
+
+    let mut state = SimState::new(dungeon, &wave, 1).expect("state should initialize");
+    step_tick(&mut state, &wave).expect("tick should succeed");
+
+    let status_damage = |unit: UnitId| -> Vec<(StatusKind, i32)> {
+        state
+            .events
+            .as_slice()
+            .iter()
+            .filter_map(|e| match e {
+                SimulationEvent::DamageApplied {
+                    source: DamageSource::Status(kind),
+                    target,
+                    amount,
+                    ..
+                } if *target == unit => Some((kind.clone(), *amount)),
+                _ => None,
+            })
+            .collect()
+    };
+    // 4 + 4 raw, armor 3 taken once: 5 damage, split 2 poison / 3 burn.
+    assert_eq!(
+        vec![(StatusKind::Poison, 2), (StatusKind::Burn, 3)],
+        status_damage(UnitId(0))
+    );
+    // The poison share already kills the second monster, so burn does not hit it.
+    assert_eq!(vec![(StatusKind::Poison, 2)], status_damage(UnitId(1)));
+}
+
 #[test]
 fn adjacent_rooms_allow_ranged_attacks() {
     let core_room = basic_room(0);
@@ -332,7 +521,7 @@ fn adjacent_rooms_allow_ranged_attacks() {
     let monster_stats = UnitStats {
         max_hp: 10,
         armor: 0,
-        move_speed: 0.0,
+        move_speed: Fixed::ZERO,
         attack_damage: 5,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -347,6 +536,7 @@ fn adjacent_rooms_allow_ranged_attacks() {
         edges: vec![(core_room.id, monster_room.id)],
         core_room_id: core_room.id,
         core_hp: 100,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -379,6 +569,7 @@ fn heroes_can_attack_core_from_adjacent_room() {
         edges: vec![(rooms[0].id, rooms[1].id), (rooms[1].id, rooms[2].id)],
         core_room_id: rooms[2].id,
         core_hp: 15,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
@@ -406,134 +597,1227 @@ fn heroes_can_attack_core_from_adjacent_room() {
 }
 
 #[test]
-fn traps_recover_cooldown_without_additional_entries() {
-    let mut room0 = basic_room(0);
-    room0.traps.push(TrapInstance {
-        id: TrapId(0),
-        trigger_type: TrapTriggerType::OnEnter,
-        cooldown_ticks: 2,
-        cooldown_remaining: 0,
-        max_charges: None,
-        charges_used: 0,
-        damage: 50,
-        status_on_hit: None,
-        tags: Vec::new(),
-    });
-    let room1 = basic_room(1);
+fn cross_room_attacks_can_be_disabled() {
+    let core_room = basic_room(0);
+    let mut monster_room = basic_room(1);
+
+    let monster_stats = UnitStats {
+        max_hp: 10,
+        armor: 0,
+        move_speed: Fixed::ZERO,
+        attack_damage: 5,
+        attack_interval_ticks: 1,
+        attack_range: 1,
+    };
+
+    monster_room
+        .monsters
+        .push(monster(0, monster_room.id, monster_stats, 10));
 
     let dungeon = DungeonState {
-        rooms: vec![room0.clone(), room1.clone()],
-        edges: vec![(room0.id, room1.id)],
-        core_room_id: room1.id,
+        rooms: vec![core_room.clone(), monster_room.clone()],
+        edges: vec![(core_room.id, monster_room.id)],
+        core_room_id: core_room.id,
         core_hp: 100,
+        modifiers: Vec::new(),
     };
 
     let wave = WaveConfig {
-        id: "wave-cooldowns".into(),
-        entries: vec![
-            HeroSpawn {
-                hero_template_id: "h1".into(),
-                count: 1,
-                spawn_room_id: room0.id,
-                delay_ticks: 0,
-            },
-            HeroSpawn {
-                hero_template_id: "h2".into(),
-                count: 1,
-                spawn_room_id: room0.id,
-                delay_ticks: 3,
-            },
+        id: "same-room-only".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "archer".into(),
+            count: 1,
+            spawn_room_id: core_room.id,
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+
+    let mut config = SimulationConfig::default();
+    config.rules.cross_room_attacks = false;
+    let mut state =
+        SimState::with_config(dungeon, &wave, 7, config).expect("state should initialize");
+    step_tick(&mut state, &wave).expect("tick should succeed");
+
+    assert_eq!(20, state.heroes[0].hp, "monster should not reach the hero");
+    assert_eq!(10, state.dungeon.rooms[1].monsters[0].hp);
+    assert_eq!(
+        95, state.dungeon.core_hp,
+        "hero should attack the core instead"
+    );
+}
+
+#[test]
+fn configured_limits_fail_fast() {
+    let dungeon = DungeonState {
+        rooms: vec![basic_room(0), basic_room(1)],
+        edges: vec![(RoomId(0), RoomId(1))],
+        core_room_id: RoomId(1),
+        core_hp: 1_000,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "limits".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 3,
+            spawn_room_id: RoomId(0),
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+
+    let mut config = SimulationConfig::default();
+    config.limits.max_heroes_per_wave = 2;
+    let err = simulate_wave(dungeon.clone(), wave.clone(), 1, 10, &config)
+        .expect_err("wave over the hero cap should be rejected");
+    assert!(matches!(err, SimError::HeroLimit), "{err:?}");
+
+    let mut config = SimulationConfig::default();
+    config.limits.max_ticks = 5;
+    let err = simulate_wave(dungeon.clone(), wave.clone(), 1, 10, &config)
+        .expect_err("max_ticks over the configured cap should be rejected");
+    assert!(matches!(err, SimError::TickLimit), "{err:?}");
+
+    let mut config = SimulationConfig::default();
+    config.limits.max_events = 4;
+    let err = simulate_wave(dungeon, wave, 1, 10, &config)
+        .expect_err("event log over the cap should be rejected");
+    assert!(matches!(err, SimError::EventLimit), "{err:?}");
+}
+
+#[test]
+fn validation_reports_every_problem_with_paths() {
+    let stats = UnitStats {
+        max_hp: 10,
+        armor: 0,
+        move_speed: Fixed::ONE,
+        attack_damage: 1,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    let mut room0 = basic_room(0);
+    room0
+        .monsters
+        .push(monster(5, RoomId(0), stats.clone(), 12));
+    let mut dup_room = basic_room(0);
+    dup_room.monsters.push(monster(5, RoomId(1), stats, 10));
+    let island = basic_room(2);
+
+    let dungeon = DungeonState {
+        rooms: vec![room0, dup_room, island],
+        edges: vec![
+            (RoomId(0), RoomId(0)),
+            (RoomId(0), RoomId(7)),
+            (RoomId(0), RoomId(2)),
+            (RoomId(2), RoomId(0)),
         ],
+        core_room_id: RoomId(9),
+        core_hp: 10,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "broken".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id: RoomId(4),
+            delay_ticks: 0,
+        }],
         modifiers: Vec::new(),
     };
 
-    let result = simulate_wave(dungeon, wave, 4, 10).expect("simulation should succeed");
+    let report = validate(&dungeon, &wave);
+    let found: Vec<(IssueCode, &str)> = report
+        .errors()
+        .map(|issue| (issue.code, issue.path.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            (IssueCode::HpExceedsMax, "dungeon.rooms[0].monsters[0].hp"),
+            (IssueCode::DuplicateRoomId, "dungeon.rooms[1].id"),
+            (
+                IssueCode::DuplicateUnitId,
+                "dungeon.rooms[1].monsters[0].id"
+            ),
+            (
+                IssueCode::MonsterRoomMismatch,
+                "dungeon.rooms[1].monsters[0].room_id"
+            ),
+            (IssueCode::MissingCoreRoom, "dungeon.core_room_id"),
+            (IssueCode::SelfLoopEdge, "dungeon.edges[0]"),
+            (IssueCode::UnknownEdgeRoom, "dungeon.edges[1][1]"),
+            (IssueCode::DuplicateEdge, "dungeon.edges[3]"),
+            (IssueCode::UnknownSpawnRoom, "wave.entries[0].spawn_room_id"),
+        ],
+        found
+    );
+
+    let err = simulate_wave(dungeon, wave, 1, 10, &SimulationConfig::default())
+        .expect_err("invalid input should be refused");
+    match err {
+        SimError::InvalidInput(refused) => assert_eq!(report, refused),
+        other => panic!("unexpected error {other:?}"),
+    }
+}
 
-    assert_eq!(2, result.stats.heroes_spawned);
-    assert_eq!(2, result.stats.heroes_killed);
+#[test]
+fn unreachable_core_is_an_error_and_stranded_spawns_warn() {
+    let dungeon = DungeonState {
+        rooms: vec![basic_room(0), basic_room(1), basic_room(2), basic_room(3)],
+        edges: vec![(RoomId(0), RoomId(1)), (RoomId(2), RoomId(3))],
+        core_room_id: RoomId(3),
+        core_hp: 10,
+        modifiers: Vec::new(),
+    };
+    let spawn = |room: u32| HeroSpawn {
+        hero_template_id: "h1".into(),
+        count: 1,
+        spawn_room_id: RoomId(room),
+        delay_ticks: 0,
+    };
+    let mut wave = WaveConfig {
+        id: "stranded".into(),
+        entries: vec![spawn(0)],
+        modifiers: Vec::new(),
+    };
 
-    let trap = result
-        .final_dungeon
-        .rooms
+    let report = validate(&dungeon, &wave);
+    let codes: Vec<(Severity, IssueCode)> = report
+        .issues
         .iter()
-        .find(|room| room.id == room0.id)
-        .and_then(|room| room.traps.first())
-        .expect("trap should remain in dungeon");
+        .map(|issue| (issue.severity, issue.code))
+        .collect();
+    assert_eq!(
+        vec![
+            (Severity::Warning, IssueCode::SpawnCannotReachCore),
+            (Severity::Error, IssueCode::CoreUnreachable),
+        ],
+        codes
+    );
 
-    assert_eq!(2, trap.charges_used);
+    wave.entries.push(spawn(2));
+    let report = validate(&dungeon, &wave);
+    assert!(!report.has_errors(), "{report}");
+    assert_eq!(1, report.warnings().count());
 }
 
-fn unit_stats_strategy() -> impl Strategy<Value = UnitStats> {
-    (
-        5..=50i32,
-        0..=10i32,
-        0.5f32..=2.5,
-        5..=25i32,
-        1..=5u32,
-        1..=3u32,
-    )
-        .prop_map(
-            |(max_hp, armor, move_speed, attack_damage, attack_interval_ticks, attack_range)| {
-                UnitStats {
-                    max_hp,
-                    armor,
-                    move_speed,
-                    attack_damage,
-                    attack_interval_ticks,
-                    attack_range,
-                }
-            },
-        )
-}
+#[test]
+fn a_destroyed_core_warns_and_the_heroes_win_on_the_first_tick() {
+    let fixture = test_fixtures::movement_to_core();
+    let mut dungeon = fixture.dungeon.clone();
+    dungeon.core_hp = 0;
+
+    let report = validate(&dungeon, &fixture.wave);
+    assert!(!report.has_errors(), "{report}");
+    let warnings: Vec<(IssueCode, &str)> = report
+        .warnings()
+        .map(|issue| (issue.code, issue.path.as_str()))
+        .collect();
+    assert_eq!(
+        vec![(IssueCode::NonPositiveCoreHp, "dungeon.core_hp")],
+        warnings
+    );
 
-fn monster_group_strategy() -> impl Strategy<Value = Vec<UnitInstance>> {
-    prop::collection::vec((unit_stats_strategy(), 1..=50i32), 0..=3).prop_map(|monsters| {
-        monsters
-            .into_iter()
-            .enumerate()
-            .map(|(idx, (stats, hp_guess))| UnitInstance {
-                id: UnitId(idx as u32),
-                faction: Faction::Monster,
-                stats: stats.clone(),
-                hp: hp_guess.clamp(1, stats.max_hp.max(1)),
-                room_id: RoomId(0),
-                status_effects: Vec::new(),
-                ai_behavior: AiBehavior::Aggressive,
-                attack_cooldown: 0,
-            })
-            .collect()
-    })
+    let result = simulate_wave(dungeon, fixture.wave, 1, 10, &SimulationConfig::default())
+        .expect("a destroyed core is still simulated");
+    assert_eq!(SimulationOutcome::HeroesWin, result.outcome);
+    assert_eq!(1, result.stats.ticks_run);
 }
 
-fn dungeon_and_wave_strategy() -> impl Strategy<Value = (DungeonState, WaveConfig, u64)> {
-    const MAX_ROOMS: usize = 4;
-    prop::collection::vec(monster_group_strategy(), 1..=MAX_ROOMS).prop_flat_map(|monster_groups| {
-        let room_count = monster_groups.len();
-        let entries_strategy = prop::collection::vec((1..=3u32, 0..room_count, 0..=10u32), 1..=3);
+#[test]
+fn hero_ids_start_above_pre_placed_monsters() {
+    let fixture = test_fixtures::core_room_duel();
+    let result = fixture.run().expect("simulation should succeed");
+
+    let monster_ids: HashSet<UnitId> = fixture
+        .dungeon
+        .rooms
+        .iter()
+        .flat_map(|r| r.monsters.iter().map(|m| m.id))
+        .collect();
+    let hero_ids: Vec<UnitId> = result
+        .events
+        .iter()
+        .filter_map(|event| match event {
+            SimulationEvent::UnitSpawned { unit_id, .. } => Some(*unit_id),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(vec![UnitId(1)], hero_ids);
+    assert!(hero_ids.iter().all(|id| !monster_ids.contains(id)));
+}
+
+#[test]
+fn hero_ids_past_u32_max_are_rejected() {
+    let stats = UnitStats {
+        max_hp: 10,
+        armor: 0,
+        move_speed: Fixed::ZERO,
+        attack_damage: 1,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    let mut room0 = basic_room(0);
+    room0.monsters.push(monster(u32::MAX, room0.id, stats, 10));
+    let room1 = basic_room(1);
+
+    let dungeon = DungeonState {
+        rooms: vec![room0, room1],
+        edges: vec![(RoomId(0), RoomId(1))],
+        core_room_id: RoomId(1),
+        core_hp: 10,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "id-overflow".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id: RoomId(0),
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+
+    let err = simulate_wave(dungeon, wave, 1, 10, &SimulationConfig::default())
+        .expect_err("hero id would wrap");
+    assert!(matches!(err, SimError::EntityLimit), "{err:?}");
+}
+
+#[test]
+fn stranded_heroes_end_in_stalemate() {
+    let mut core_room = basic_room(2);
+    let guard_stats = UnitStats {
+        max_hp: 500,
+        armor: 0,
+        move_speed: Fixed::ZERO,
+        attack_damage: 100,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    core_room
+        .monsters
+        .push(monster(0, core_room.id, guard_stats, 500));
+
+    let dungeon = DungeonState {
+        rooms: vec![basic_room(0), basic_room(1), core_room],
+        edges: vec![(RoomId(1), RoomId(2))],
+        core_room_id: RoomId(2),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let spawn = |room: u32| HeroSpawn {
+        hero_template_id: "h1".into(),
+        count: 1,
+        spawn_room_id: RoomId(room),
+        delay_ticks: 0,
+    };
+    let wave = WaveConfig {
+        id: "stranded".into(),
+        entries: vec![spawn(0), spawn(1)],
+        modifiers: Vec::new(),
+    };
+
+    let result = simulate_wave(dungeon, wave, 3, 1_000, &SimulationConfig::default())
+        .expect("simulation should succeed");
+
+    assert_eq!(
+        SimulationOutcome::Stalemate(StalemateReason::CoreUnreachable),
+        result.outcome
+    );
+    assert_eq!(1, result.stats.heroes_killed);
+    assert_eq!(1, result.stats.ticks_run);
+}
+
+#[test]
+fn immobile_heroes_end_in_stalemate_once_timed_traps_are_spent() {
+    let mut entry = basic_room(0);
+    entry.traps.push(TrapInstance {
+        id: TrapId(0),
+        trigger_type: TrapTriggerType::Timed,
+        cooldown_ticks: 3,
+        cooldown_remaining: 0,
+        max_charges: Some(2),
+        charges_used: 0,
+        damage: 1,
+        status_on_hit: None,
+        tags: Vec::new(),
+        template_id: None,
+    });
+    let dungeon = DungeonState {
+        rooms: vec![entry, basic_room(1), basic_room(2)],
+        edges: vec![(RoomId(0), RoomId(1)), (RoomId(1), RoomId(2))],
+        core_room_id: RoomId(2),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let wave = WaveConfig {
+        id: "immobile".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h2".into(),
+            count: 1,
+            spawn_room_id: RoomId(0),
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+
+    let mut config = SimulationConfig::default();
+    config.rules.min_one_step_per_tick = false;
+    let result =
+        simulate_wave(dungeon, wave, 5, 1_000, &config).expect("simulation should succeed");
+
+    assert_eq!(
+        SimulationOutcome::Stalemate(StalemateReason::HeroesImmobile),
+        result.outcome
+    );
+    assert_eq!(
+        4, result.stats.ticks_run,
+        "second trap charge fires on tick 3"
+    );
+    assert_eq!(22, result.final_heroes[0].hp);
+}
+
+#[test]
+fn heroes_that_cannot_hurt_anything_in_the_core_room_stalemate() {
+    let mut core_room = basic_room(0);
+    let wall_stats = UnitStats {
+        max_hp: 100,
+        armor: 1_000,
+        move_speed: Fixed::ZERO,
+        attack_damage: 0,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    core_room
+        .monsters
+        .push(monster(0, core_room.id, wall_stats, 100));
+    let dungeon = DungeonState {
+        rooms: vec![core_room],
+        edges: vec![],
+        core_room_id: RoomId(0),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+
+    let config = SimulationConfig {
+        min_damage: 0,
+        ..SimulationConfig::default()
+    };
+    let result = simulate_wave(
+        dungeon,
+        single_hero_wave("no-damage", RoomId(0)),
+        5,
+        1_000,
+        &config,
+    )
+    .expect("simulation should succeed");
+
+    assert_eq!(
+        SimulationOutcome::Stalemate(StalemateReason::NoDamagePossible),
+        result.outcome
+    );
+    assert_eq!(1, result.stats.ticks_run);
+    assert_eq!(100, result.final_dungeon.core_hp);
+}
+
+fn single_hero_wave(id: &str, spawn_room_id: RoomId) -> WaveConfig {
+    WaveConfig {
+        id: id.into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id,
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    }
+}
+
+#[test]
+fn runs_apply_between_wave_rules() {
+    let mut entry = basic_room(0);
+    entry.traps.push(TrapInstance {
+        id: TrapId(0),
+        trigger_type: TrapTriggerType::OnEnter,
+        cooldown_ticks: 0,
+        cooldown_remaining: 0,
+        max_charges: Some(1),
+        charges_used: 0,
+        damage: 5,
+        status_on_hit: None,
+        tags: Vec::new(),
+        template_id: None,
+    });
+    let mut core_room = basic_room(1);
+    let guard_stats = UnitStats {
+        max_hp: 40,
+        armor: 0,
+        move_speed: Fixed::ZERO,
+        attack_damage: 10,
+        attack_interval_ticks: 1,
+        attack_range: 0,
+    };
+    core_room
+        .monsters
+        .push(monster(0, core_room.id, guard_stats, 40));
+    let dungeon = DungeonState {
+        rooms: vec![entry, core_room],
+        edges: vec![(RoomId(0), RoomId(1))],
+        core_room_id: RoomId(1),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let waves = vec![
+        single_hero_wave("w1", RoomId(0)),
+        single_hero_wave("w2", RoomId(0)),
+    ];
+    let trap_triggers = |result: &crate::model::SimulationResult| {
+        result
+            .events
+            .iter()
+            .filter(|e| matches!(e, SimulationEvent::TrapTriggered { .. }))
+            .count()
+    };
+
+    let mut rules = RunRules::default();
+    rules.between_waves.monster_heal_percent = 50;
+    let run = simulate_run(dungeon.clone(), &waves, 11, &rules).expect("run should succeed");
+    assert_eq!(
+        run,
+        simulate_run(dungeon.clone(), &waves, 11, &rules).unwrap()
+    );
+    assert_eq!(SimulationOutcome::DungeonWin, run.summary.outcome);
+    assert_eq!(2, run.summary.waves_cleared);
+    assert_eq!(2, run.summary.heroes_killed);
+    assert_eq!(
+        vec![1, 1],
+        run.waves.iter().map(trap_triggers).collect::<Vec<_>>()
+    );
+    let guard_hp = |w: usize| run.waves[w].final_dungeon.rooms[1].monsters[0].hp;
+    assert_eq!(35, guard_hp(0));
+    assert_eq!(35, guard_hp(1), "guard healed back to full between waves");
+    let spawned: Vec<UnitId> = run
+        .waves
+        .iter()
+        .flat_map(|w| w.events.iter())
+        .filter_map(|e| match e {
+            SimulationEvent::UnitSpawned { unit_id, .. } => Some(*unit_id),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![UnitId(1), UnitId(2)],
+        spawned,
+        "ids are unique across the run"
+    );
+
+    rules.between_waves.recharge_traps = false;
+    let run = simulate_run(dungeon, &waves, 11, &rules).expect("run should succeed");
+    assert_eq!(
+        vec![1, 0],
+        run.waves.iter().map(trap_triggers).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn runs_carry_surviving_heroes_and_repair_the_core() {
+    let rooms: Vec<RoomState> = (0..4).map(basic_room).collect();
+    let dungeon = DungeonState {
+        rooms,
+        edges: vec![
+            (RoomId(0), RoomId(1)),
+            (RoomId(1), RoomId(2)),
+            (RoomId(2), RoomId(3)),
+        ],
+        core_room_id: RoomId(3),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let waves = vec![
+        single_hero_wave("w1", RoomId(0)),
+        single_hero_wave("w2", RoomId(0)),
+    ];
+    let mut rules = RunRules {
+        max_ticks_per_wave: 2,
+        ..RunRules::default()
+    };
+    rules.between_waves.core_repair = 3;
+
+    let run = simulate_run(dungeon, &waves, 2, &rules).expect("run should succeed");
+
+    assert_eq!(SimulationOutcome::Timeout, run.waves[0].outcome);
+    let wave1_core = run.waves[0].final_dungeon.core_hp;
+    assert_eq!(95, wave1_core, "hero reaches range of the core on tick 1");
+    match &run.waves[1].events[0] {
+        SimulationEvent::UnitSpawned {
+            unit_id, room_id, ..
+        } => {
+            assert_eq!(UnitId(0), *unit_id);
+            assert_eq!(RoomId(2), *room_id, "carried hero keeps its room");
+        }
+        other => panic!("unexpected first event {other:?}"),
+    }
+    assert_eq!(2, run.waves[1].final_heroes.len());
+    let wave2_damage = run.waves[1].stats.total_damage_to_core;
+    assert_eq!(
+        98 - wave2_damage,
+        run.summary.core_hp_remaining,
+        "core repaired to 98 before wave 2"
+    );
+    assert_eq!(
+        2, run.summary.heroes_spawned,
+        "carried heroes are not counted twice"
+    );
+}
+
+#[test]
+fn rng_streams_do_not_perturb_each_other() {
+    let mut quiet = RngStreams::new(11);
+    let mut busy = RngStreams::new(11);
+    let mut legacy = Rng::new(11);
+    let mut expected_unit = Vec::new();
+    let mut busy_unit = Vec::new();
+    for round in 0..16 {
+        let targeting = quiet.stream(RngStream::Targeting).gen_below(1000);
+        assert_eq!(
+            legacy.gen_below(1000),
+            targeting,
+            "targeting keeps the wave seed"
+        );
+
+        busy.stream(RngStream::Traps).gen_below(7);
+        busy.unit(UnitId(round % 3)).gen_below(7);
+        assert_eq!(targeting, busy.stream(RngStream::Targeting).gen_below(1000));
+        busy.stream(RngStream::Abilities).gen_below(7);
+
+        expected_unit.push(quiet.unit(UnitId(5)).gen_below(1000));
+        busy_unit.push(busy.unit(UnitId(5)).gen_below(1000));
+    }
+    assert_eq!(expected_unit, busy_unit, "unit streams ignore other units");
+
+    let mut streams = RngStreams::new(11);
+    let firsts: HashSet<u64> = [RngStream::Targeting, RngStream::Spawns, RngStream::Traps]
+        .into_iter()
+        .map(|stream| streams.stream(stream).gen_below(u64::MAX))
+        .collect();
+    assert_eq!(3, firsts.len(), "streams are seeded independently");
+}
+
+#[test]
+fn batches_match_sequential_runs_in_job_order() {
+    let fixture = test_fixtures::core_room_duel();
+    let config = SimulationConfig::default();
+    let mut jobs = BatchJob::seed_sweep(&fixture.dungeon, &fixture.wave, 40, 24, 30, &config);
+    jobs[5].max_ticks = config.limits.max_ticks + 1;
+
+    let sequential = simulate_batch(&jobs, 1);
+    assert_eq!(24, sequential.len());
+    for (job, summary) in jobs.iter().zip(&sequential) {
+        let Ok(summary) = summary else {
+            assert_eq!(5, job.seed - 40, "only the oversized job fails");
+            continue;
+        };
+        let full = simulate_wave(
+            fixture.dungeon.clone(),
+            fixture.wave.clone(),
+            job.seed,
+            job.max_ticks,
+            &config,
+        )
+        .unwrap();
+        assert_eq!(job.seed, summary.seed);
+        assert_eq!(
+            (&full.outcome, &full.stats),
+            (&summary.outcome, &summary.stats)
+        );
+        assert_eq!(full.score.total, summary.score);
+    }
+    assert!(matches!(sequential[5], Err(SimError::TickLimit)));
+
+    let mut order = Vec::new();
+    let mut parallel = Vec::new();
+    simulate_batch_with(&jobs, 4, |index, summary| {
+        order.push(index);
+        parallel.push(summary.ok());
+    });
+    assert_eq!((0..24).collect::<Vec<_>>(), order);
+    let sequential: Vec<_> = sequential.into_iter().map(Result::ok).collect();
+    assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn batches_run_in_place_when_no_worker_spawns() {
+    let fixture = test_fixtures::core_room_duel();
+    let config = SimulationConfig::default();
+    let jobs = BatchJob::seed_sweep(&fixture.dungeon, &fixture.wave, 7, 6, 30, &config);
+
+    let mut order = Vec::new();
+    let mut summaries = Vec::new();
+    simulate_batch_on(
+        &jobs,
+        4,
+        |_, _| Err(std::io::Error::other("no threads")),
+        |index, summary| {
+            order.push(index);
+            summaries.push(summary.ok());
+        },
+    );
+    assert_eq!((0..6).collect::<Vec<_>>(), order);
+    let sequential: Vec<_> = simulate_batch(&jobs, 1)
+        .into_iter()
+        .map(Result::ok)
+        .collect();
+    assert_eq!(sequential, summaries);
+}
+
+#[test]
+fn stress_stats_report_intervals_and_distributions() {
+    let z = z_for_confidence(0.95).unwrap();
+    assert!((z - 1.959_964).abs() < 1e-5, "{z}");
+    assert!((z_for_confidence(0.99).unwrap() - 2.575_829).abs() < 1e-5);
+    assert_eq!(None, z_for_confidence(1.0));
+
+    let ci = wilson_interval(52, 100, z);
+    assert!(
+        (ci.low - 0.4231).abs() < 1e-4 && (ci.high - 0.6153).abs() < 1e-4,
+        "{ci:?}"
+    );
+    let all = wilson_interval(20, 20, z);
+    assert_eq!(1.0, all.high);
+    assert!(all.low > 0.8 && all.low < 0.85, "{all:?}");
+
+    let mut distribution = Distribution::default();
+    assert_eq!(None, distribution.summary());
+    for value in (1..=100).rev() {
+        distribution.record(value);
+    }
+    let summary = distribution.summary().unwrap();
+    assert_eq!((1, 100), (summary.min, summary.max));
+    assert_eq!(
+        (10, 50, 90, 99),
+        (summary.p10, summary.p50, summary.p90, summary.p99)
+    );
+    assert_eq!(50.5, summary.mean);
+    assert_eq!(10, summary.histogram.len());
+    assert!(summary.histogram.iter().all(|bin| bin.count == 10));
+    assert_eq!(
+        (91, 100),
+        (summary.histogram[9].low, summary.histogram[9].high)
+    );
+
+    let mut narrow = Distribution::default();
+    for value in [3, 3, 4, 5] {
+        narrow.record(value);
+    }
+    let counts: Vec<(i64, u64)> = narrow
+        .summary()
+        .unwrap()
+        .histogram
+        .iter()
+        .map(|bin| (bin.low, bin.count))
+        .collect();
+    assert_eq!(vec![(3, 2), (4, 1), (5, 1)], counts);
+
+    let fixture = test_fixtures::core_room_duel();
+    let config = SimulationConfig::default();
+    let jobs = BatchJob::seed_sweep(&fixture.dungeon, &fixture.wave, 1, 50, 30, &config);
+    let mut stats = StressStats::default();
+    for summary in simulate_batch(&jobs, 0) {
+        stats.record(&summary.unwrap());
+    }
+    let report = stats.report(0.95).unwrap();
+    assert_eq!(50, report.runs);
+    assert_eq!(
+        50,
+        report.dungeon_wins + report.hero_wins + report.timeouts + report.stalemates
+    );
+    assert_eq!(50, report.ticks.unwrap().count);
+    assert!(!stats.is_precise(z, 0.01));
+    assert!(stats.is_precise(z, 0.5));
+}
+
+#[test]
+fn checksums_are_recorded_at_the_interval_and_the_final_tick() {
+    let fixture = test_fixtures::core_room_duel();
+    assert!(
+        fixture.run().unwrap().checksums.is_empty(),
+        "off by default"
+    );
+
+    let config = SimulationConfig {
+        checksum_interval: 4,
+        ..SimulationConfig::default()
+    };
+    let run = || {
+        simulate_wave(
+            fixture.dungeon.clone(),
+            fixture.wave.clone(),
+            fixture.seed,
+            fixture.max_ticks,
+            &config,
+        )
+        .unwrap()
+    };
+    let result = run();
+    let ticks: Vec<u32> = result.checksums.iter().map(|c| c.tick).collect();
+    let last = result.stats.ticks_run;
+    let mut expected: Vec<u32> = (1..=last).filter(|tick| tick % 4 == 0).collect();
+    if last % 4 != 0 {
+        expected.push(last);
+    }
+    assert_eq!(expected, ticks);
+    assert_eq!(result.checksums, run().checksums);
+
+    let json = serde_json::to_string(&result).unwrap();
+    let hex = format!("\"{}\"", result.checksums[0].checksum);
+    assert_eq!(18, hex.len());
+    assert!(json.contains(&hex), "checksums serialize as hex strings");
+    let parsed: SimulationResult = serde_json::from_str(&json).unwrap();
+    assert_eq!(result.checksums, parsed.checksums);
+}
+
+#[test]
+fn stepping_a_wave_matches_simulating_it_at_once() {
+    let config = SimulationConfig {
+        checksum_interval: 3,
+        ..SimulationConfig::default()
+    };
+    for fixture in [
+        test_fixtures::movement_to_core(),
+        test_fixtures::trapped_entry_hall(),
+        test_fixtures::core_room_duel(),
+    ] {
+        let expected = simulate_wave(
+            fixture.dungeon.clone(),
+            fixture.wave.clone(),
+            fixture.seed,
+            fixture.max_ticks,
+            &config,
+        )
+        .unwrap();
+
+        let mut simulation = WaveSimulation::new(
+            fixture.dungeon.clone(),
+            fixture.wave.clone(),
+            fixture.seed,
+            fixture.max_ticks,
+            &config,
+        )
+        .unwrap();
+        let mut streamed = Vec::new();
+        let mut chunk = 1;
+        while !simulation.is_finished() {
+            assert!(simulation.view().outcome.is_none());
+            let cursor = streamed.len();
+            let before = simulation.tick();
+            let ran = simulation.step(chunk).unwrap();
+            assert_eq!(before + ran, simulation.tick());
+            streamed.extend_from_slice(simulation.events_since(cursor));
+            assert_eq!(streamed.len(), simulation.view().event_count);
+            chunk += 1;
+        }
+        assert_eq!(0, simulation.step(5).unwrap(), "finished waves stay put");
+        assert!(simulation.events_since(usize::MAX).is_empty());
+        assert_eq!(Some(expected.outcome.clone()), simulation.view().outcome);
+        assert_eq!(expected.events, streamed);
+        assert_eq!(expected, simulation.finish().unwrap());
+    }
+
+    let fixture = test_fixtures::movement_to_core();
+    let unfinished = WaveSimulation::new(
+        fixture.dungeon.clone(),
+        fixture.wave.clone(),
+        fixture.seed,
+        fixture.max_ticks,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(
+        fixture.run().unwrap().events,
+        unfinished.finish().unwrap().events
+    );
+}
+
+#[test]
+fn first_divergence_names_the_first_differing_tick() {
+    let config = SimulationConfig {
+        checksum_interval: 1,
+        ..SimulationConfig::default()
+    };
+    let fixture = test_fixtures::trapped_entry_hall();
+    let mut weaker = fixture.dungeon.clone();
+    weaker.rooms[0].traps[0].damage -= 1;
+    let run = |dungeon: DungeonState| {
+        simulate_wave(dungeon, fixture.wave.clone(), fixture.seed, 10, &config).unwrap()
+    };
+    let left = run(fixture.dungeon.clone());
+    let right = run(weaker);
+
+    assert_eq!(None, first_divergence(&left.checksums, &left.checksums));
+    let divergence = first_divergence(&left.checksums, &right.checksums).expect("diverges");
+    let trap_tick = left
+        .events
+        .iter()
+        .find(|e| matches!(e, SimulationEvent::TrapTriggered { .. }))
+        .map(SimulationEvent::tick)
+        .expect("trap fires");
+    // Events carry the tick being processed; checksums the number of ticks completed.
+    assert_eq!(trap_tick + 1, divergence.tick);
+    assert!(divergence.left.is_some() && divergence.right.is_some());
+
+    let last = left.stats.ticks_run;
+    let sparse: Vec<TickChecksum> = left
+        .checksums
+        .iter()
+        .filter(|c| c.tick % 3 == 0 || c.tick == last)
+        .copied()
+        .collect();
+    assert_eq!(None, first_divergence(&sparse, &left.checksums));
+    let truncated = &left.checksums[..1];
+    let ended = first_divergence(truncated, &left.checksums).expect("left ended early");
+    assert_eq!((left.checksums[1].tick, None), (ended.tick, ended.left));
+}
+
+#[test]
+fn wave_scores_weight_kills_by_tier_and_apply_relic_multipliers() {
+    let stats = SimulationStats {
+        ticks_run: 25,
+        heroes_spawned: 4,
+        heroes_killed: 4,
+        monsters_killed: 1,
+        total_damage_to_core: 30,
+    };
+    let context = ScoreContext {
+        tier: 3,
+        multiplier_permille: 1500,
+    };
+    let score = score_wave(
+        &SimulationOutcome::DungeonWin,
+        &stats,
+        120,
+        90,
+        100,
+        context,
+    );
+    assert_eq!(
+        WaveScore {
+            version: SCORE_VERSION,
+            tier: 3,
+            hero_kills: 120,
+            core_hp: 75,
+            efficiency: 37,
+            multiplier_permille: 1500,
+            relic_bonus: 116,
+            total: 348,
+        },
+        score
+    );
+
+    let lost = score_wave(&SimulationOutcome::HeroesWin, &stats, 120, 0, 100, context);
+    assert_eq!((0, 0, 180), (lost.core_hp, lost.efficiency, lost.total));
+    let timeout = score_wave(&SimulationOutcome::Timeout, &stats, 120, 90, 100, context);
+    assert_eq!(0, timeout.efficiency, "only dungeon wins earn efficiency");
+
+    let content: ContentPack = serde_json::from_str(
+        r#"{ "version": "1", "relics": [
+            { "id": "greed", "name": "Greed", "effect_type": "score_multiplier",
+              "effect_multiplier": 1.25 },
+            { "id": "pride", "name": "Pride", "effect_type": "score_multiplier",
+              "effect_multiplier": 1.2 },
+            { "id": "furnace", "name": "Furnace", "effect_type": "global_multiplier",
+              "effect_multiplier": 3.0 }
+        ] }"#,
+    )
+    .unwrap();
+    let relics = ["greed", "pride", "furnace", "unknown"].map(String::from);
+    assert_eq!(1500, relic_multiplier_permille(&relics, &content));
+}
+
+#[test]
+fn run_scores_sum_wave_scores_by_tier() {
+    let rooms: Vec<RoomState> = (0..4).map(basic_room).collect();
+    let dungeon = DungeonState {
+        rooms,
+        edges: vec![
+            (RoomId(0), RoomId(1)),
+            (RoomId(1), RoomId(2)),
+            (RoomId(2), RoomId(3)),
+        ],
+        core_room_id: RoomId(3),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let waves: Vec<WaveConfig> = (0..3)
+        .map(|i| single_hero_wave(&format!("w{i}"), RoomId(0)))
+        .collect();
+    let rules = RunRules {
+        max_ticks_per_wave: 3,
+        waves_per_tier: 2,
+        ..RunRules::default()
+    };
+
+    let run = simulate_run(dungeon, &waves, 8, &rules).expect("run should succeed");
+    let tiers: Vec<u32> = run.waves.iter().map(|w| w.score.tier).collect();
+    assert_eq!(vec![1, 1, 2], tiers);
+    let total: i64 = run.waves.iter().map(|w| w.score.total).sum();
+    assert_eq!(total, run.summary.score.total);
+    let core_hp: i64 = run.waves.iter().map(|w| w.score.core_hp).sum();
+    assert_eq!(core_hp, run.summary.score.core_hp);
+    assert_eq!(SCORE_VERSION, run.summary.score.version);
+}
+
+#[test]
+fn drafted_monsters_get_ids_above_carried_heroes() {
+    let rooms: Vec<RoomState> = (0..4).map(basic_room).collect();
+    let dungeon = DungeonState {
+        rooms,
+        edges: vec![
+            (RoomId(0), RoomId(1)),
+            (RoomId(1), RoomId(2)),
+            (RoomId(2), RoomId(3)),
+        ],
+        core_room_id: RoomId(3),
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+    let content: ContentPack = serde_json::from_str(
+        r#"{ "version": "1", "monsters": [{ "id": "monster_rat", "name": "Rat", "max_hp": 8,
+             "armor": 0, "move_speed": 0.0, "attack_damage": 2, "attack_interval_ticks": 1,
+             "attack_range": 0 }] }"#,
+    )
+    .unwrap();
+    let offer = DraftOffer {
+        draft_index: 1,
+        tier: 1,
+        options: vec![DraftOption::AddMonster {
+            template_id: "monster_rat".into(),
+            rarity: Rarity::Common,
+        }],
+    };
+    let rules = RunRules {
+        max_ticks_per_wave: 2,
+        ..RunRules::default()
+    };
+
+    let mut run = RunState::new(dungeon, 4, rules);
+    run.play_wave(&single_hero_wave("w1", RoomId(0))).unwrap();
+    let pick = DraftPick {
+        option: 0,
+        target_room: Some(RoomId(3)),
+    };
+    run.apply_pick(&content, &offer, &pick).unwrap();
+    let rat = run.dungeon().rooms[3].monsters[0].id;
+    assert_eq!(UnitId(1), rat, "hero 0 is still alive and keeps its id");
+
+    let wave2 = run.play_wave(&single_hero_wave("w2", RoomId(0))).unwrap();
+    assert_eq!(
+        vec![UnitId(0), UnitId(2)],
+        wave2.final_heroes.iter().map(|h| h.id).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn traps_recover_cooldown_without_additional_entries() {
+    let mut room0 = basic_room(0);
+    room0.traps.push(TrapInstance {
+        id: TrapId(0),
+        trigger_type: TrapTriggerType::OnEnter,
+        cooldown_ticks: 2,
+        cooldown_remaining: 0,
+        max_charges: None,
+        charges_used: 0,
+        damage: 50,
+        status_on_hit: None,
+        tags: Vec::new(),
+        template_id: None,
+    });
+    let room1 = basic_room(1);
+
+    let dungeon = DungeonState {
+        rooms: vec![room0.clone(), room1.clone()],
+        edges: vec![(room0.id, room1.id)],
+        core_room_id: room1.id,
+        core_hp: 100,
+        modifiers: Vec::new(),
+    };
+
+    let wave = WaveConfig {
+        id: "wave-cooldowns".into(),
+        entries: vec![
+            HeroSpawn {
+                hero_template_id: "h1".into(),
+                count: 1,
+                spawn_room_id: room0.id,
+                delay_ticks: 0,
+            },
+            HeroSpawn {
+                hero_template_id: "h2".into(),
+                count: 1,
+                spawn_room_id: room0.id,
+                delay_ticks: 3,
+            },
+        ],
+        modifiers: Vec::new(),
+    };
+
+    let result = simulate_wave(dungeon, wave, 4, 10, &SimulationConfig::default())
+        .expect("simulation should succeed");
+
+    assert_eq!(2, result.stats.heroes_spawned);
+    assert_eq!(2, result.stats.heroes_killed);
+
+    let trap = result
+        .final_dungeon
+        .rooms
+        .iter()
+        .find(|room| room.id == room0.id)
+        .and_then(|room| room.traps.first())
+        .expect("trap should remain in dungeon");
+
+    assert_eq!(2, trap.charges_used);
+}
+
+fn unit_stats_strategy() -> impl Strategy<Value = UnitStats> {
+    (
+        5..=50i32,
+        0..=10i32,
+        500i64..=2500,
+        5..=25i32,
+        1..=5u32,
+        1..=3u32,
+    )
+        .prop_map(
+            |(max_hp, armor, move_speed, attack_damage, attack_interval_ticks, attack_range)| {
+                UnitStats {
+                    max_hp,
+                    armor,
+                    move_speed: Fixed::from_milli(move_speed),
+                    attack_damage,
+                    attack_interval_ticks,
+                    attack_range,
+                }
+            },
+        )
+}
+
+fn status_strategy(kinds: Vec<StatusKind>) -> impl Strategy<Value = StatusInstance> {
+    (prop::sample::select(kinds), 1..=6u32, 250i64..=3000).prop_map(
+        |(kind, remaining_ticks, magnitude)| StatusInstance {
+            kind,
+            remaining_ticks,
+            magnitude: Fixed::from_milli(magnitude),
+        },
+    )
+}
+
+fn damage_over_time_strategy() -> impl Strategy<Value = StatusInstance> {
+    status_strategy(vec![StatusKind::Poison, StatusKind::Burn])
+}
+
+fn monster_group_strategy() -> impl Strategy<Value = Vec<UnitInstance>> {
+    let monster = (
+        unit_stats_strategy(),
+        1..=50i32,
+        prop::collection::vec(damage_over_time_strategy(), 0..=2),
+    );
+    prop::collection::vec(monster, 0..=3).prop_map(|monsters| {
+        monsters
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (stats, hp_guess, status_effects))| UnitInstance {
+                id: UnitId(idx as u32),
+                faction: Faction::Monster,
+                stats: stats.clone(),
+                hp: hp_guess.clamp(1, stats.max_hp.max(1)),
+                room_id: RoomId(0),
+                status_effects,
+                ai_behavior: AiBehavior::Aggressive,
+                attack_cooldown: 0,
+                template_id: None,
+            })
+            .collect()
+    })
+}
+
+/// Traps with and without a charge limit or an on-hit status, so the reducer sees charges
+/// run out and statuses applied.
+fn trap_group_strategy() -> impl Strategy<Value = Vec<TrapInstance>> {
+    let trap = (
+        prop::sample::select(vec![
+            TrapTriggerType::OnEnter,
+            TrapTriggerType::OnExit,
+            TrapTriggerType::Timed,
+        ]),
+        0..=3u32,
+        prop::option::of(1..=3u32),
+        1..=20i32,
+        prop::option::of(status_strategy(vec![
+            StatusKind::Poison,
+            StatusKind::Burn,
+            StatusKind::Slow,
+            StatusKind::Stun,
+        ])),
+    );
+    prop::collection::vec(trap, 0..=2).prop_map(|traps| {
+        traps
+            .into_iter()
+            .map(
+                |(trigger_type, cooldown_ticks, max_charges, damage, status_on_hit)| TrapInstance {
+                    id: TrapId(0),
+                    trigger_type,
+                    cooldown_ticks,
+                    cooldown_remaining: 0,
+                    max_charges,
+                    charges_used: 0,
+                    damage,
+                    status_on_hit,
+                    tags: Vec::new(),
+                    template_id: None,
+                },
+            )
+            .collect()
+    })
+}
+
+fn dungeon_and_wave_strategy() -> impl Strategy<Value = (DungeonState, WaveConfig, u64)> {
+    const MAX_ROOMS: usize = 4;
+    let room = (monster_group_strategy(), trap_group_strategy());
+    prop::collection::vec(room, 1..=MAX_ROOMS).prop_flat_map(|room_contents| {
+        let room_count = room_contents.len();
+        let entries_strategy = prop::collection::vec((1..=3u32, 0..room_count, 0..=10u32), 1..=3);
         (
-            Just(monster_groups),
+            Just(room_contents),
             0..room_count,
             25..=250i32,
             entries_strategy,
             any::<u64>(),
         )
-            .prop_map(|(mut monster_groups, core_idx, core_hp, entries, seed)| {
+            .prop_map(|(room_contents, core_idx, core_hp, entries, seed)| {
                 let mut next_id = 0u32;
-                let rooms: Vec<RoomState> = monster_groups
-                    .iter_mut()
+                let mut next_trap_id = 0u32;
+                let rooms: Vec<RoomState> = room_contents
+                    .into_iter()
                     .enumerate()
-                    .map(|(room_idx, monsters)| {
+                    .map(|(room_idx, (mut monsters, mut traps))| {
                         let room_id = RoomId(room_idx as u32);
                         for monster in monsters.iter_mut() {
                             monster.room_id = room_id;
                             monster.id = UnitId(next_id);
                             next_id += 1;
                         }
+                        for trap in traps.iter_mut() {
+                            trap.id = TrapId(next_trap_id);
+                            next_trap_id += 1;
+                        }
                         RoomState {
                             id: room_id,
-                            traps: Vec::new(),
-                            monsters: monsters.clone(),
+                            traps,
+                            monsters,
                             tags: Vec::new(),
+                            template_id: None,
                         }
                     })
                     .collect();
@@ -547,6 +1831,7 @@ fn dungeon_and_wave_strategy() -> impl Strategy<Value = (DungeonState, WaveConfi
                     edges,
                     core_room_id: RoomId(core_idx as u32),
                     core_hp,
+                    modifiers: Vec::new(),
                 };
 
                 let wave_entries = entries
@@ -571,12 +1856,116 @@ fn dungeon_and_wave_strategy() -> impl Strategy<Value = (DungeonState, WaveConfi
     })
 }
 
+/// Inputs drawn once from [`dungeon_and_wave_strategy`] and pinned together with a digest
+/// of each result from a native build. Pinning the inputs keeps the corpus identical on
+/// every target (proptest samples `usize` ranges differently on 32-bit ones), so running
+/// this under `cargo test --target wasm32-wasip1` proves wasm reproduces native results.
+/// Regenerate after an intended rules change with
+/// `cargo test refresh_conformance_corpus -- --ignored`.
+const CONFORMANCE_CORPUS: &str = include_str!("test_fixtures/conformance_corpus.json");
+const CONFORMANCE_CASES: usize = 64;
+const CONFORMANCE_MAX_TICKS: u32 = 250;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ConformanceCase {
+    seed: u64,
+    dungeon: DungeonState,
+    wave: WaveConfig,
+    result_digest: String,
+}
+
+/// The part of a result the rules produce. Echoed inputs such as `config` and the engine
+/// version are left out, so the corpus only moves when the rules do.
+#[derive(serde::Serialize)]
+struct RulesOutput<'a> {
+    outcome: &'a SimulationOutcome,
+    final_dungeon: &'a DungeonState,
+    final_heroes: &'a [UnitInstance],
+    stats: &'a SimulationStats,
+    events: &'a [SimulationEvent],
+    score: &'a WaveScore,
+}
+
+/// FNV-1a over the JSON of the result's [`RulesOutput`].
+fn result_digest(result: &SimulationResult) -> String {
+    let output = RulesOutput {
+        outcome: &result.outcome,
+        final_dungeon: &result.final_dungeon,
+        final_heroes: &result.final_heroes,
+        stats: &result.stats,
+        events: &result.events,
+        score: &result.score,
+    };
+    let bytes = serde_json::to_vec(&output).expect("result serializes");
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+fn run_conformance_case(dungeon: &DungeonState, wave: &WaveConfig, seed: u64) -> SimulationResult {
+    simulate_wave(
+        dungeon.clone(),
+        wave.clone(),
+        seed,
+        CONFORMANCE_MAX_TICKS,
+        &SimulationConfig::default(),
+    )
+    .expect("conformance case should simulate")
+}
+
+#[test]
+fn conformance_corpus_matches_native_results() {
+    let cases: Vec<ConformanceCase> =
+        serde_json::from_str(CONFORMANCE_CORPUS).expect("corpus should parse");
+    assert_eq!(CONFORMANCE_CASES, cases.len());
+    for (idx, case) in cases.iter().enumerate() {
+        let result = run_conformance_case(&case.dungeon, &case.wave, case.seed);
+        assert_eq!(
+            case.result_digest,
+            result_digest(&result),
+            "conformance case {idx} diverged"
+        );
+    }
+}
+
+#[test]
+#[ignore = "rewrites test_fixtures/conformance_corpus.json"]
+fn refresh_conformance_corpus() {
+    let mut runner = proptest::test_runner::TestRunner::deterministic();
+    let strategy = dungeon_and_wave_strategy();
+    let lines: Vec<String> = (0..CONFORMANCE_CASES)
+        .map(|_| {
+            let (dungeon, wave, seed) = strategy
+                .new_tree(&mut runner)
+                .expect("strategy generates a case")
+                .current();
+            let result = run_conformance_case(&dungeon, &wave, seed);
+            let case = ConformanceCase {
+                seed,
+                result_digest: result_digest(&result),
+                dungeon,
+                wave,
+            };
+            serde_json::to_string(&case).expect("case serializes")
+        })
+        .collect();
+    let path = format!(
+        "{}/src/sim/test_fixtures/conformance_corpus.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::write(path, format!("[\n{}\n]\n", lines.join(",\n"))).expect("corpus written");
+}
+
 proptest! {
     #[test]
     fn simulation_is_deterministic((dungeon, wave, seed) in dungeon_and_wave_strategy()) {
-        let first = simulate_wave(dungeon.clone(), wave.clone(), seed, 250)
+        let report = validate(&dungeon, &wave);
+        prop_assert!(!report.has_errors(), "{}", report);
+
+        let first = simulate_wave(dungeon.clone(), wave.clone(), seed, 250, &SimulationConfig::default())
             .expect("first simulation should succeed");
-        let second = simulate_wave(dungeon, wave, seed, 250)
+        let second = simulate_wave(dungeon, wave, seed, 250, &SimulationConfig::default())
             .expect("second simulation should succeed");
 
         prop_assert_eq!(first, second);
@@ -586,7 +1975,7 @@ proptest! {
 proptest! {
     #[test]
     fn simulation_preserves_basic_invariants((dungeon, wave, seed) in dungeon_and_wave_strategy()) {
-        let result = simulate_wave(dungeon, wave, seed, 250)
+        let result = simulate_wave(dungeon, wave, seed, 250, &SimulationConfig::default())
             .expect("simulation should succeed");
 
         prop_assert!(!result.final_dungeon.rooms.is_empty());
@@ -611,6 +2000,91 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn events_reconstruct_final_state((dungeon, wave, seed) in dungeon_and_wave_strategy()) {
+        let result = simulate_wave(dungeon.clone(), wave, seed, 250, &SimulationConfig::default())
+            .expect("simulation should succeed");
+
+        let report = check_consistency(&dungeon, &result);
+        prop_assert!(report.is_consistent(), "{:?}", report.divergences);
+    }
+}
+
+#[test]
+fn event_log_matches_final_state_for_trap_fixture() {
+    let fixture = test_fixtures::trapped_entry_hall();
+    let result = fixture.run().expect("simulation should succeed");
+
+    let report = check_consistency(&fixture.dungeon, &result);
+    assert!(report.is_consistent(), "{:?}", report.divergences);
+}
+
+#[test]
+fn consistency_check_reports_tampered_results() {
+    let fixture = test_fixtures::movement_to_core();
+    let mut result = fixture.run().expect("simulation should succeed");
+    result.final_dungeon.core_hp += 3;
+    result
+        .events
+        .retain(|e| !matches!(e, SimulationEvent::UnitMoved { .. }));
+
+    let report = check_consistency(&fixture.dungeon, &result);
+
+    assert!(
+        report
+            .divergences
+            .iter()
+            .any(|d| matches!(d, Divergence::CoreHp { .. }))
+    );
+    assert!(
+        report
+            .divergences
+            .iter()
+            .any(|d| matches!(d, Divergence::BadEventId { .. }))
+    );
+}
+
+#[test]
+fn narrative_resolves_names_and_hp_transitions() {
+    let mut fixture = test_fixtures::trapped_entry_hall();
+    fixture.dungeon.rooms[0].tags = vec!["entrance".into()];
+    fixture.dungeon.rooms[0].traps[0].template_id = Some("trap_fire_glyph".into());
+    fixture.wave.entries[0].hero_template_id = "hero_thief".into();
+    let result = fixture.run().expect("simulation should succeed");
+
+    let content: ContentPack = serde_json::from_str(
+        r#"{
+            "version": "1.0.0",
+            "traps": [{
+                "id": "trap_fire_glyph",
+                "name": "fire_glyph",
+                "base_damage": 12,
+                "cooldown_ticks": 0,
+                "trigger_type": "on_enter"
+            }]
+        }"#,
+    )
+    .expect("content pack should parse");
+
+    let text = render_narrative(
+        &fixture.dungeon,
+        &result,
+        Some(&content),
+        NarrativeFormat::PlainText,
+    );
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(
+        "T=0: Hero#0 'hero_thief' triggered trap 'fire_glyph' in Room#0 [entrance] – took 12 dmg (HP 20 → 8)",
+        lines[1]
+    );
+    assert!(lines.iter().any(|l| l.ends_with("died (killed by poison)")));
+
+    let markdown = render_narrative(&fixture.dungeon, &result, None, NarrativeFormat::Markdown);
+    assert!(markdown.lines().all(|l| l.starts_with("- **T=")));
+    assert!(markdown.contains("trap `trap_fire_glyph`"));
+}
+
 fn assert_snapshot(fixture: &test_fixtures::ScenarioFixture) {
     let expected = fixture.expected_result();
     let actual = fixture
@@ -1,39 +1,65 @@
+use std::collections::HashMap;
+
 use crate::error::SimError;
+use crate::fixed::{Fixed, Rounding};
 use crate::model::SimulationOutcome;
 use crate::model::SimulationStats;
 use crate::model::{
-    AiBehavior, DungeonState, Faction, HeroSpawn, RoomId, StatusKind, TrapTriggerType, UnitId,
-    UnitInstance, UnitStats, WaveConfig,
+    AiBehavior, DungeonState, Faction, HeroSpawn, RoomId, StalemateReason, StatusKind,
+    TrapTriggerType, UnitId, UnitInstance, UnitStats, WaveConfig,
 };
-use crate::rng::Rng;
-use crate::sim::events::SimulationEvent;
+use crate::rng::{RngStream, RngStreams};
+use crate::sim::config::SimulationConfig;
+use crate::sim::events::{DamageSource, EventId, EventLog, SimulationEvent};
 use crate::sim::pathfinding::shortest_path;
-use crate::sim::{MAX_EVENTS, MAX_TICKS, MAX_UNITS};
 
 pub struct SimState {
     pub tick: u32,
     pub dungeon: DungeonState,
     pub heroes: Vec<UnitInstance>,
-    pub rng: Rng,
-    pub events: Vec<SimulationEvent>,
+    pub rng: RngStreams,
+    pub events: EventLog,
     pub stats: SimulationStats,
     pub spawn_progress: Vec<u32>,
-    next_unit_id: u32,
+    pub config: SimulationConfig,
+    /// Id for the next spawned hero. Starts above every pre-placed monster id so hero and
+    /// monster ids never collide; `u64` so the first hero after `UnitId(u32::MAX)` fails
+    /// with an error instead of wrapping.
+    next_unit_id: u64,
+    /// Latest `StatusApplied` event per unit and status kind, used as the cause of DoT ticks.
+    status_sources: HashMap<(UnitId, StatusKind), EventId>,
 }
 
 impl SimState {
     pub fn new(dungeon: DungeonState, wave: &WaveConfig, seed: u64) -> Result<Self, SimError> {
+        Self::with_config(dungeon, wave, seed, SimulationConfig::default())
+    }
+
+    pub fn with_config(
+        dungeon: DungeonState,
+        wave: &WaveConfig,
+        seed: u64,
+        config: SimulationConfig,
+    ) -> Result<Self, SimError> {
         let monster_count: usize = dungeon.rooms.iter().map(|r| r.monsters.len()).sum();
-        if monster_count > MAX_UNITS {
+        if monster_count > config.limits.max_units {
             return Err(SimError::EntityLimit);
         }
 
+        let next_unit_id = dungeon
+            .rooms
+            .iter()
+            .flat_map(|r| r.monsters.iter())
+            .map(|m| u64::from(m.id.0) + 1)
+            .max()
+            .unwrap_or(0);
+
         Ok(Self {
             tick: 0,
             dungeon,
             heroes: Vec::new(),
-            rng: Rng::new(seed),
-            events: Vec::new(),
+            rng: RngStreams::new(seed),
+            events: EventLog::new(config.limits.max_events),
             stats: SimulationStats {
                 ticks_run: 0,
                 heroes_spawned: 0,
@@ -42,29 +68,59 @@ impl SimState {
                 total_damage_to_core: 0,
             },
             spawn_progress: vec![0; wave.entries.len()],
-            next_unit_id: 0,
+            config,
+            next_unit_id,
+            status_sources: HashMap::new(),
         })
     }
 
+    /// Place heroes that survived the previous wave of a run at their current rooms and
+    /// continue hero ids from `next_unit_id`. Each hero gets a `UnitSpawned` event (without
+    /// triggering traps) so the wave's event log stays self-contained; statuses and attack
+    /// cooldowns do not carry over.
+    pub(crate) fn carry_over(
+        &mut self,
+        heroes: Vec<UnitInstance>,
+        next_unit_id: u64,
+    ) -> Result<(), SimError> {
+        self.next_unit_id = self.next_unit_id.max(next_unit_id);
+        for mut hero in heroes {
+            if self.total_units() + 1 > self.config.limits.max_units {
+                return Err(SimError::EntityLimit);
+            }
+            hero.status_effects.clear();
+            hero.attack_cooldown = 0;
+            self.next_unit_id = self.next_unit_id.max(u64::from(hero.id.0) + 1);
+            self.events.push(|id| SimulationEvent::UnitSpawned {
+                tick: self.tick,
+                id,
+                caused_by: None,
+                unit_id: hero.id,
+                room_id: hero.room_id,
+                hp: hero.hp,
+                template_id: hero.template_id.clone(),
+            })?;
+            self.heroes.push(hero);
+        }
+        Ok(())
+    }
+
+    /// Id the next spawned hero would receive.
+    pub(crate) fn next_unit_id(&self) -> u64 {
+        self.next_unit_id
+    }
+
     pub fn total_units(&self) -> usize {
         let monsters: usize = self.dungeon.rooms.iter().map(|r| r.monsters.len()).sum();
         monsters + self.heroes.len()
     }
 }
 
-fn push_event(events: &mut Vec<SimulationEvent>, event: SimulationEvent) -> Result<(), SimError> {
-    if events.len() >= MAX_EVENTS {
-        return Err(SimError::EventLimit);
-    }
-    events.push(event);
-    Ok(())
-}
-
 pub fn step_tick(
     state: &mut SimState,
     wave: &WaveConfig,
 ) -> Result<Option<SimulationOutcome>, SimError> {
-    if state.tick >= MAX_TICKS {
+    if state.tick >= state.config.limits.max_ticks {
         return Err(SimError::TickLimit);
     }
 
@@ -93,19 +149,25 @@ pub fn step_tick(
         return Ok(Some(SimulationOutcome::DungeonWin));
     }
 
+    if let Some(reason) = detect_stalemate(state, wave) {
+        return Ok(Some(SimulationOutcome::Stalemate(reason)));
+    }
+
     Ok(None)
 }
 
 fn spawn_heroes(state: &mut SimState, wave: &WaveConfig) -> Result<(), SimError> {
     for (idx, spawn) in wave.entries.iter().enumerate() {
         while state.spawn_progress[idx] < spawn.count && state.tick >= spawn.delay_ticks {
-            if state.total_units() + 1 > MAX_UNITS {
+            if state.total_units() + 1 > state.config.limits.max_units {
                 return Err(SimError::EntityLimit);
             }
 
+            let unit_id =
+                UnitId(u32::try_from(state.next_unit_id).map_err(|_| SimError::EntityLimit)?);
             let stats = hero_stats_for_spawn(spawn, wave);
             let unit = UnitInstance {
-                id: UnitId(state.next_unit_id),
+                id: unit_id,
                 faction: Faction::Hero,
                 stats: stats.clone(),
                 hp: stats.max_hp,
@@ -113,21 +175,30 @@ fn spawn_heroes(state: &mut SimState, wave: &WaveConfig) -> Result<(), SimError>
                 status_effects: Vec::new(),
                 ai_behavior: AiBehavior::Aggressive,
                 attack_cooldown: 0,
+                template_id: Some(spawn.hero_template_id.clone()),
             };
             state.next_unit_id += 1;
             state.stats.heroes_spawned += 1;
-            let unit_id = unit.id;
             let room_id = unit.room_id;
+            let hp = unit.hp;
+            let template_id = unit.template_id.clone();
             state.heroes.push(unit);
-            push_event(
-                &mut state.events,
-                SimulationEvent::UnitSpawned {
-                    tick: state.tick,
-                    unit_id,
-                    room_id,
-                },
+            let spawn_event = state.events.push(|id| SimulationEvent::UnitSpawned {
+                tick: state.tick,
+                id,
+                caused_by: None,
+                unit_id,
+                room_id,
+                hp,
+                template_id,
+            })?;
+            trigger_traps(
+                state,
+                room_id,
+                TrapTriggerType::OnEnter,
+                Some(unit_id),
+                spawn_event,
             )?;
-            trigger_traps(state, room_id, TrapTriggerType::OnEnter, Some(unit_id))?;
             state.spawn_progress[idx] += 1;
         }
     }
@@ -136,11 +207,23 @@ fn spawn_heroes(state: &mut SimState, wave: &WaveConfig) -> Result<(), SimError>
 
 fn apply_status_effects(state: &mut SimState) -> Result<(), SimError> {
     for hero in state.heroes.iter_mut() {
-        tick_statuses(&mut state.events, state.tick, hero)?;
+        tick_statuses(
+            &mut state.events,
+            &state.status_sources,
+            state.tick,
+            state.config.min_damage,
+            hero,
+        )?;
     }
     for room in state.dungeon.rooms.iter_mut() {
         for monster in room.monsters.iter_mut() {
-            tick_statuses(&mut state.events, state.tick, monster)?;
+            tick_statuses(
+                &mut state.events,
+                &state.status_sources,
+                state.tick,
+                state.config.min_damage,
+                monster,
+            )?;
         }
     }
     Ok(())
@@ -155,22 +238,53 @@ fn tick_trap_cooldowns(state: &mut SimState) {
 }
 
 fn tick_statuses(
-    events: &mut Vec<SimulationEvent>,
+    events: &mut EventLog,
+    status_sources: &HashMap<(UnitId, StatusKind), EventId>,
     tick: u32,
+    min_damage: i32,
     unit: &mut UnitInstance,
 ) -> Result<(), SimError> {
-    let mut damage = 0;
+    let mut poison = 0;
+    let mut burn = 0;
     for status in unit.status_effects.iter_mut() {
-        if matches!(status.kind, StatusKind::Poison | StatusKind::Burn) {
-            damage += status.magnitude as i32;
+        match status.kind {
+            StatusKind::Poison => poison += status.magnitude.to_int(Rounding::TowardZero),
+            StatusKind::Burn => burn += status.magnitude.to_int(Rounding::TowardZero),
+            _ => {}
         }
         if status.remaining_ticks > 0 {
             status.remaining_ticks -= 1;
         }
     }
     unit.status_effects.retain(|s| s.remaining_ticks > 0);
-    if damage > 0 {
-        apply_damage(events, tick, None, unit, damage)?;
+    let (poison, burn) = (poison.max(0), burn.max(0));
+    let total = poison + burn;
+    if total == 0 {
+        return Ok(());
+    }
+    // Armor and the damage floor apply once to the combined damage over time; the result
+    // is split between the kinds in proportion to their raw amounts.
+    let damage = mitigated_damage(unit, total, min_damage);
+    let poison_share =
+        i32::try_from(i64::from(damage) * i64::from(poison) / i64::from(total)).unwrap_or(damage);
+    for (kind, share) in [
+        (StatusKind::Poison, poison_share),
+        (StatusKind::Burn, damage - poison_share),
+    ] {
+        if unit.hp <= 0 {
+            break;
+        }
+        if share > 0 {
+            let caused_by = status_sources.get(&(unit.id, kind.clone())).copied();
+            deal_damage(
+                events,
+                tick,
+                DamageSource::Status(kind),
+                caused_by,
+                unit,
+                share,
+            )?;
+        }
     }
     Ok(())
 }
@@ -197,13 +311,10 @@ fn move_heroes(state: &mut SimState) -> Result<(), SimError> {
             continue;
         }
 
-        let speed = effective_move_speed(hero);
-        if speed <= 0.0 {
+        let steps = move_steps(hero, &state.config);
+        if steps == 0 {
             continue;
         }
-
-        let steps = speed.floor() as usize;
-        let steps = steps.max(1);
         let steps = steps.min(path.len() - 1);
         let new_room = path[steps];
         let old_room = hero.room_id;
@@ -212,21 +323,46 @@ fn move_heroes(state: &mut SimState) -> Result<(), SimError> {
     }
 
     for (unit_id, from, to) in movements {
-        push_event(
-            &mut state.events,
-            SimulationEvent::UnitMoved {
-                tick: state.tick,
-                unit_id,
-                from,
-                to,
-            },
+        let move_event = state.events.push(|id| SimulationEvent::UnitMoved {
+            tick: state.tick,
+            id,
+            caused_by: None,
+            unit_id,
+            from,
+            to,
+        })?;
+        trigger_traps(
+            state,
+            from,
+            TrapTriggerType::OnExit,
+            Some(unit_id),
+            move_event,
+        )?;
+        trigger_traps(
+            state,
+            to,
+            TrapTriggerType::OnEnter,
+            Some(unit_id),
+            move_event,
         )?;
-        trigger_traps(state, from, TrapTriggerType::OnExit, Some(unit_id))?;
-        trigger_traps(state, to, TrapTriggerType::OnEnter, Some(unit_id))?;
     }
     Ok(())
 }
 
+/// Rooms a hero advances along its path this tick.
+fn move_steps(hero: &UnitInstance, config: &SimulationConfig) -> usize {
+    let speed = effective_move_speed(hero);
+    if speed <= Fixed::ZERO {
+        return 0;
+    }
+    let steps = speed.to_int(Rounding::Floor) as usize;
+    if config.rules.min_one_step_per_tick {
+        steps.max(1)
+    } else {
+        steps
+    }
+}
+
 fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
     for room in state.dungeon.rooms.iter_mut() {
         // Monster attacks
@@ -250,21 +386,33 @@ fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
                         && rooms_within_attack_range(
                             room.id,
                             h.room_id,
-                            monster.stats.attack_range,
+                            attack_range(monster, &state.config),
                             &state.dungeon.edges,
                         )
                 })
                 .map(|(idx, _)| idx)
                 .collect();
 
-            if let Some(choice_idx) = state.rng.choose_index(&candidates) {
+            if let Some(choice_idx) = state
+                .rng
+                .stream(RngStream::Targeting)
+                .choose_index(&candidates)
+            {
                 let target_idx = candidates[choice_idx];
                 let dmg = effective_damage(monster);
                 let target = state
                     .heroes
                     .get_mut(target_idx)
                     .expect("index from candidates must exist");
-                apply_damage(&mut state.events, state.tick, Some(monster.id), target, dmg)?;
+                apply_damage(
+                    &mut state.events,
+                    state.tick,
+                    DamageSource::Unit(monster.id),
+                    None,
+                    state.config.min_damage,
+                    target,
+                    dmg,
+                )?;
                 monster.attack_cooldown = monster.stats.attack_interval_ticks;
             }
         }
@@ -289,7 +437,7 @@ fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
                 if !rooms_within_attack_range(
                     hero.room_id,
                     target_room.id,
-                    hero.stats.attack_range,
+                    attack_range(hero, &state.config),
                     &state.dungeon.edges,
                 ) {
                     continue;
@@ -302,7 +450,22 @@ fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
                 }
             }
 
-            if let Some(choice_idx) = state.rng.choose_index(&candidates) {
+            let core_in_range = rooms_within_attack_range(
+                hero.room_id,
+                state.dungeon.core_room_id,
+                attack_range(hero, &state.config),
+                &state.dungeon.edges,
+            );
+            let choice = if core_in_range && !state.config.rules.heroes_target_monsters_first {
+                None
+            } else {
+                state
+                    .rng
+                    .stream(RngStream::Targeting)
+                    .choose_index(&candidates)
+            };
+
+            if let Some(choice_idx) = choice {
                 let (room_idx, monster_idx) = candidates[choice_idx];
                 let dmg = effective_damage(hero);
                 let target = state
@@ -311,25 +474,27 @@ fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
                     .get_mut(room_idx)
                     .and_then(|room| room.monsters.get_mut(monster_idx))
                     .expect("candidate monster must exist");
-                apply_damage(&mut state.events, state.tick, Some(hero.id), target, dmg)?;
+                apply_damage(
+                    &mut state.events,
+                    state.tick,
+                    DamageSource::Unit(hero.id),
+                    None,
+                    state.config.min_damage,
+                    target,
+                    dmg,
+                )?;
                 hero.attack_cooldown = hero.stats.attack_interval_ticks;
-            } else if rooms_within_attack_range(
-                hero.room_id,
-                state.dungeon.core_room_id,
-                hero.stats.attack_range,
-                &state.dungeon.edges,
-            ) {
+            } else if core_in_range {
                 let dmg = effective_damage(hero);
                 state.dungeon.core_hp -= dmg;
                 state.stats.total_damage_to_core += dmg;
-                push_event(
-                    &mut state.events,
-                    SimulationEvent::CoreDamaged {
-                        tick: state.tick,
-                        amount: dmg,
-                        core_hp_after: state.dungeon.core_hp,
-                    },
-                )?;
+                state.events.push(|id| SimulationEvent::CoreDamaged {
+                    tick: state.tick,
+                    id,
+                    caused_by: None,
+                    amount: dmg,
+                    core_hp_after: state.dungeon.core_hp,
+                })?;
                 hero.attack_cooldown = hero.stats.attack_interval_ticks;
             }
         }
@@ -338,6 +503,15 @@ fn process_attacks(state: &mut SimState) -> Result<(), SimError> {
     Ok(())
 }
 
+/// Attack range in rooms, or 0 (same room only) when cross-room attacks are disabled.
+fn attack_range(unit: &UnitInstance, config: &SimulationConfig) -> u32 {
+    if config.rules.cross_room_attacks {
+        unit.stats.attack_range
+    } else {
+        0
+    }
+}
+
 fn rooms_within_attack_range(
     origin: RoomId,
     target: RoomId,
@@ -355,14 +529,16 @@ fn cleanup_dead(state: &mut SimState) -> Result<(), SimError> {
     while hero_idx < state.heroes.len() {
         if state.heroes[hero_idx].hp <= 0 {
             let unit_id = state.heroes[hero_idx].id;
+            let (cause, blow) = killing_blow(state.events.as_slice(), state.tick, unit_id);
             state.stats.heroes_killed += 1;
-            push_event(
-                &mut state.events,
-                SimulationEvent::UnitDied {
-                    tick: state.tick,
-                    unit_id,
-                },
-            )?;
+            state.events.push(|id| SimulationEvent::UnitDied {
+                tick: state.tick,
+                id,
+                caused_by: blow,
+                unit_id,
+                killer: cause.unit(),
+                cause,
+            })?;
             state.heroes.remove(hero_idx);
         } else {
             hero_idx += 1;
@@ -374,14 +550,16 @@ fn cleanup_dead(state: &mut SimState) -> Result<(), SimError> {
         while monster_idx < room.monsters.len() {
             if room.monsters[monster_idx].hp <= 0 {
                 let unit_id = room.monsters[monster_idx].id;
+                let (cause, blow) = killing_blow(state.events.as_slice(), state.tick, unit_id);
                 state.stats.monsters_killed += 1;
-                push_event(
-                    &mut state.events,
-                    SimulationEvent::UnitDied {
-                        tick: state.tick,
-                        unit_id,
-                    },
-                )?;
+                state.events.push(|id| SimulationEvent::UnitDied {
+                    tick: state.tick,
+                    id,
+                    caused_by: blow,
+                    unit_id,
+                    killer: cause.unit(),
+                    cause,
+                })?;
                 room.monsters.remove(monster_idx);
             } else {
                 monster_idx += 1;
@@ -392,14 +570,145 @@ fn cleanup_dead(state: &mut SimState) -> Result<(), SimError> {
     Ok(())
 }
 
-fn heroes_exhausted(state: &SimState, wave: &WaveConfig) -> bool {
-    let done_spawning = wave
-        .entries
+/// Find the damage that took `unit_id` from positive HP to zero or below during `tick`.
+///
+/// Units are only removed at the end of the tick they die in, so the killing blow is
+/// always among this tick's events. Units that start at zero HP fall back to
+/// [`DamageSource::Environment`] with no causing event.
+fn killing_blow(
+    events: &[SimulationEvent],
+    tick: u32,
+    unit_id: UnitId,
+) -> (DamageSource, Option<EventId>) {
+    events
+        .iter()
+        .rev()
+        .take_while(|event| event.tick() == tick)
+        .filter_map(|event| match event {
+            SimulationEvent::DamageApplied {
+                id,
+                source,
+                target,
+                hp_before,
+                hp_after,
+                ..
+            } if *target == unit_id && *hp_before > 0 && *hp_after <= 0 => {
+                Some((source.clone(), Some(*id)))
+            }
+            _ => None,
+        })
+        .next()
+        .unwrap_or((DamageSource::Environment, None))
+}
+
+fn spawns_finished(state: &SimState, wave: &WaveConfig) -> bool {
+    wave.entries
         .iter()
         .enumerate()
-        .all(|(i, spawn)| state.spawn_progress[i] >= spawn.count || spawn.count == 0);
+        .all(|(i, spawn)| state.spawn_progress[i] >= spawn.count || spawn.count == 0)
+}
+
+fn heroes_exhausted(state: &SimState, wave: &WaveConfig) -> bool {
+    spawns_finished(state, wave) && state.heroes.is_empty()
+}
+
+/// Detect a wave that can no longer change: all spawns are done, no unit has a status
+/// effect that could expire or tick, every hero is stuck in its room, and no attack or
+/// timed trap in range can deal damage. Monsters never move and stationary heroes never
+/// trigger enter/exit traps, so such a state repeats until `max_ticks`.
+fn detect_stalemate(state: &SimState, wave: &WaveConfig) -> Option<StalemateReason> {
+    if state.heroes.is_empty() || !spawns_finished(state, wave) {
+        return None;
+    }
+    let monsters = || {
+        state
+            .dungeon
+            .rooms
+            .iter()
+            .flat_map(|room| room.monsters.iter())
+    };
+    if state
+        .heroes
+        .iter()
+        .chain(monsters())
+        .any(|unit| !unit.status_effects.is_empty())
+    {
+        return None;
+    }
+
+    let config = &state.config;
+    let edges = &state.dungeon.edges;
+    let core_room = state.dungeon.core_room_id;
+    let hit = |attacker: &UnitInstance, target: &UnitInstance| {
+        (effective_damage(attacker) - effective_armor(target)).max(config.min_damage)
+    };
+    let mut all_stranded = true;
+    let mut any_immobile = false;
+    for hero in &state.heroes {
+        if let Some(path) = shortest_path(hero.room_id, core_room, edges) {
+            all_stranded = false;
+            if path.len() >= 2 {
+                if move_steps(hero, config) > 0 {
+                    return None;
+                }
+                any_immobile = true;
+            }
+        }
+
+        let mut monster_in_range = false;
+        for monster in monsters() {
+            if rooms_within_attack_range(
+                hero.room_id,
+                monster.room_id,
+                attack_range(hero, config),
+                edges,
+            ) {
+                monster_in_range = true;
+                if hit(hero, monster) > 0 {
+                    return None;
+                }
+            }
+            if hit(monster, hero) > 0
+                && rooms_within_attack_range(
+                    monster.room_id,
+                    hero.room_id,
+                    attack_range(monster, config),
+                    edges,
+                )
+            {
+                return None;
+            }
+        }
 
-    done_spawning && state.heroes.is_empty()
+        let core_targetable = !monster_in_range || !config.rules.heroes_target_monsters_first;
+        if core_targetable
+            && effective_damage(hero) > 0
+            && rooms_within_attack_range(hero.room_id, core_room, attack_range(hero, config), edges)
+        {
+            return None;
+        }
+
+        let room = state.dungeon.rooms.iter().find(|r| r.id == hero.room_id);
+        let armed_timed_trap = room.into_iter().flat_map(|r| r.traps.iter()).any(|trap| {
+            trap.trigger_type == TrapTriggerType::Timed
+                && trap
+                    .max_charges
+                    .is_none_or(|max_charges| trap.charges_used < max_charges)
+                && ((trap.damage - effective_armor(hero)).max(config.min_damage) > 0
+                    || trap.status_on_hit.is_some())
+        });
+        if armed_timed_trap {
+            return None;
+        }
+    }
+
+    Some(if all_stranded {
+        StalemateReason::CoreUnreachable
+    } else if any_immobile {
+        StalemateReason::HeroesImmobile
+    } else {
+        StalemateReason::NoDamagePossible
+    })
 }
 
 fn trigger_traps(
@@ -407,6 +716,7 @@ fn trigger_traps(
     room_id: RoomId,
     trigger: TrapTriggerType,
     target: Option<UnitId>,
+    caused_by: EventId,
 ) -> Result<(), SimError> {
     if let Some(room) = state.dungeon.rooms.iter_mut().find(|r| r.id == room_id) {
         for trap in room.traps.iter_mut() {
@@ -416,35 +726,46 @@ fn trigger_traps(
             if trap.cooldown_remaining > 0 {
                 continue;
             }
-            if let Some(max_charges) = trap.max_charges {
-                if trap.charges_used >= max_charges {
-                    continue;
-                }
+            if let Some(max_charges) = trap.max_charges
+                && trap.charges_used >= max_charges
+            {
+                continue;
             }
             trap.charges_used += 1;
             trap.cooldown_remaining = trap.cooldown_ticks;
-            push_event(
-                &mut state.events,
-                SimulationEvent::TrapTriggered {
-                    tick: state.tick,
-                    trap_id: trap.id,
-                    room_id,
-                },
-            )?;
-            if let Some(target_id) = target {
-                if let Some(hero) = state.heroes.iter_mut().find(|h| h.id == target_id) {
-                    apply_damage(&mut state.events, state.tick, None, hero, trap.damage)?;
-                    if let Some(status) = trap.status_on_hit.clone() {
-                        hero.status_effects.push(status.clone());
-                        push_event(
-                            &mut state.events,
-                            SimulationEvent::StatusApplied {
-                                tick: state.tick,
-                                target: hero.id,
-                                kind: status.kind,
-                            },
-                        )?;
-                    }
+            let trap_event = state.events.push(|id| SimulationEvent::TrapTriggered {
+                tick: state.tick,
+                id,
+                caused_by: Some(caused_by),
+                trap_id: trap.id,
+                room_id,
+            })?;
+            if let Some(target_id) = target
+                && let Some(hero) = state.heroes.iter_mut().find(|h| h.id == target_id)
+            {
+                let damage_event = apply_damage(
+                    &mut state.events,
+                    state.tick,
+                    DamageSource::Trap(trap.id),
+                    Some(trap_event),
+                    state.config.min_damage,
+                    hero,
+                    trap.damage,
+                )?;
+                if let Some(status) = trap.status_on_hit.clone() {
+                    let status_event = state.events.push(|id| SimulationEvent::StatusApplied {
+                        tick: state.tick,
+                        id,
+                        caused_by: Some(damage_event),
+                        target: hero.id,
+                        kind: status.kind.clone(),
+                        magnitude: status.magnitude,
+                        duration_ticks: status.remaining_ticks,
+                    })?;
+                    state
+                        .status_sources
+                        .insert((hero.id, status.kind.clone()), status_event);
+                    hero.status_effects.push(status);
                 }
             }
         }
@@ -472,36 +793,48 @@ fn trigger_timed_traps(state: &mut SimState) -> Result<(), SimError> {
             if trap.cooldown_remaining > 0 {
                 continue;
             }
-            if let Some(max_charges) = trap.max_charges {
-                if trap.charges_used >= max_charges {
-                    continue;
-                }
+            if let Some(max_charges) = trap.max_charges
+                && trap.charges_used >= max_charges
+            {
+                continue;
             }
 
             trap.charges_used += 1;
             trap.cooldown_remaining = trap.cooldown_ticks;
-            push_event(
-                &mut state.events,
-                SimulationEvent::TrapTriggered {
-                    tick: state.tick,
-                    trap_id: trap.id,
-                    room_id: room.id,
-                },
-            )?;
+            let trap_event = state.events.push(|id| SimulationEvent::TrapTriggered {
+                tick: state.tick,
+                id,
+                caused_by: None,
+                trap_id: trap.id,
+                room_id: room.id,
+            })?;
 
             for target_id in &heroes_in_room {
                 if let Some(hero) = state.heroes.iter_mut().find(|h| h.id == *target_id) {
-                    apply_damage(&mut state.events, state.tick, None, hero, trap.damage)?;
+                    let damage_event = apply_damage(
+                        &mut state.events,
+                        state.tick,
+                        DamageSource::Trap(trap.id),
+                        Some(trap_event),
+                        state.config.min_damage,
+                        hero,
+                        trap.damage,
+                    )?;
                     if let Some(status) = trap.status_on_hit.clone() {
-                        hero.status_effects.push(status.clone());
-                        push_event(
-                            &mut state.events,
-                            SimulationEvent::StatusApplied {
+                        let status_event =
+                            state.events.push(|id| SimulationEvent::StatusApplied {
                                 tick: state.tick,
+                                id,
+                                caused_by: Some(damage_event),
                                 target: hero.id,
-                                kind: status.kind,
-                            },
-                        )?;
+                                kind: status.kind.clone(),
+                                magnitude: status.magnitude,
+                                duration_ticks: status.remaining_ticks,
+                            })?;
+                        state
+                            .status_sources
+                            .insert((hero.id, status.kind.clone()), status_event);
+                        hero.status_effects.push(status);
                     }
                 }
             }
@@ -512,54 +845,77 @@ fn trigger_timed_traps(state: &mut SimState) -> Result<(), SimError> {
 }
 
 fn apply_damage(
-    events: &mut Vec<SimulationEvent>,
+    events: &mut EventLog,
     tick: u32,
-    source: Option<UnitId>,
+    source: DamageSource,
+    caused_by: Option<EventId>,
+    min_damage: i32,
     target: &mut UnitInstance,
     raw_amount: i32,
-) -> Result<(), SimError> {
-    let damage = (raw_amount - effective_armor(target)).max(1);
+) -> Result<EventId, SimError> {
+    let damage = mitigated_damage(target, raw_amount, min_damage);
+    deal_damage(events, tick, source, caused_by, target, damage)
+}
+
+/// `raw_amount` after the target's armor, floored at `min_damage`.
+fn mitigated_damage(target: &UnitInstance, raw_amount: i32, min_damage: i32) -> i32 {
+    (raw_amount - effective_armor(target)).max(min_damage)
+}
+
+/// Apply already-mitigated damage and record it.
+fn deal_damage(
+    events: &mut EventLog,
+    tick: u32,
+    source: DamageSource,
+    caused_by: Option<EventId>,
+    target: &mut UnitInstance,
+    damage: i32,
+) -> Result<EventId, SimError> {
+    let hp_before = target.hp;
     target.hp -= damage;
-    push_event(
-        events,
-        SimulationEvent::DamageApplied {
-            tick,
-            source,
-            target: target.id,
-            amount: damage,
-        },
-    )?;
-    Ok(())
+    events.push(|id| SimulationEvent::DamageApplied {
+        tick,
+        id,
+        caused_by,
+        source,
+        target: target.id,
+        amount: damage,
+        hp_before,
+        hp_after: target.hp,
+    })
 }
 
 fn effective_damage(unit: &UnitInstance) -> i32 {
-    let bonus: f32 = unit
+    let bonus: Fixed = unit
         .status_effects
         .iter()
         .filter(|s| matches!(s.kind, StatusKind::BuffDamage))
         .map(|s| s.magnitude)
         .sum();
-    (unit.stats.attack_damage as f32 + bonus).round() as i32
+    (Fixed::from_int(unit.stats.attack_damage) + bonus).to_int(Rounding::HalfAwayFromZero)
 }
 
 fn effective_armor(unit: &UnitInstance) -> i32 {
-    let bonus: f32 = unit
+    let bonus: Fixed = unit
         .status_effects
         .iter()
         .filter(|s| matches!(s.kind, StatusKind::BuffArmor))
         .map(|s| s.magnitude)
         .sum();
-    (unit.stats.armor as f32 + bonus).round() as i32
+    (Fixed::from_int(unit.stats.armor) + bonus).to_int(Rounding::HalfAwayFromZero)
 }
 
-fn effective_move_speed(unit: &UnitInstance) -> f32 {
-    let slow: f32 = unit
+fn effective_move_speed(unit: &UnitInstance) -> Fixed {
+    let slow: Fixed = unit
         .status_effects
         .iter()
         .filter(|s| matches!(s.kind, StatusKind::Slow))
         .map(|s| s.magnitude)
         .sum();
-    (unit.stats.move_speed * (1.0 - slow)).max(0.0)
+    unit.stats
+        .move_speed
+        .mul(Fixed::ONE - slow, Rounding::TowardZero)
+        .max(Fixed::ZERO)
 }
 
 fn is_stunned(unit: &UnitInstance) -> bool {
@@ -572,7 +928,7 @@ fn default_hero_stats() -> UnitStats {
     UnitStats {
         max_hp: 20,
         armor: 0,
-        move_speed: 1.0,
+        move_speed: Fixed::ONE,
         attack_damage: 5,
         attack_interval_ticks: 1,
         attack_range: 1,
@@ -590,7 +946,7 @@ fn hero_template_stats(template_id: &str) -> Option<UnitStats> {
         "h2" => Some(UnitStats {
             max_hp: 24,
             armor: 1,
-            move_speed: 0.9,
+            move_speed: Fixed::from_milli(900),
             attack_damage: 6,
             attack_interval_ticks: 1,
             attack_range: 1,
@@ -598,7 +954,7 @@ fn hero_template_stats(template_id: &str) -> Option<UnitStats> {
         "archer" => Some(UnitStats {
             max_hp: 20,
             armor: 0,
-            move_speed: 1.0,
+            move_speed: Fixed::ONE,
             attack_damage: 5,
             attack_interval_ticks: 1,
             attack_range: 2,
@@ -606,7 +962,7 @@ fn hero_template_stats(template_id: &str) -> Option<UnitStats> {
         "ranger" => Some(UnitStats {
             max_hp: 20,
             armor: 0,
-            move_speed: 1.0,
+            move_speed: Fixed::ONE,
             attack_damage: 5,
             attack_interval_ticks: 1,
             attack_range: 2,
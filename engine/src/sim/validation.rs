@@ -0,0 +1,368 @@
+//! Structural validation of simulation inputs.
+//!
+//! [`validate`] walks a dungeon and wave and collects every problem instead of stopping
+//! at the first one, so editors and the CLI can show a complete list. Each issue carries
+//! a stable [`IssueCode`] and a path into the input JSON, rooted at `dungeon` or `wave`
+//! (e.g. `dungeon.rooms[2].monsters[0].hp`). Errors make [`crate::simulate_wave`] refuse
+//! the input; warnings describe inputs that simulate but probably do not do what the
+//! author meant.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{DungeonState, RoomId, WaveConfig};
+use crate::sim::pathfinding::shortest_path;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Stable identifier of a validation rule. The serialized snake_case name is part of
+/// the public contract; new rules get new codes rather than reusing old ones.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+    NoRooms,
+    DuplicateRoomId,
+    MissingCoreRoom,
+    NonPositiveCoreHp,
+    UnknownEdgeRoom,
+    SelfLoopEdge,
+    DuplicateEdge,
+    DuplicateUnitId,
+    DuplicateTrapId,
+    MonsterRoomMismatch,
+    HpExceedsMax,
+    NonPositiveHp,
+    UnknownSpawnRoom,
+    CoreUnreachable,
+    SpawnCannotReachCore,
+    IsolatedRoom,
+    EmptyWave,
+}
+
+impl IssueCode {
+    /// The serialized name of the code, e.g. `duplicate_room_id`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueCode::NoRooms => "no_rooms",
+            IssueCode::DuplicateRoomId => "duplicate_room_id",
+            IssueCode::MissingCoreRoom => "missing_core_room",
+            IssueCode::NonPositiveCoreHp => "non_positive_core_hp",
+            IssueCode::UnknownEdgeRoom => "unknown_edge_room",
+            IssueCode::SelfLoopEdge => "self_loop_edge",
+            IssueCode::DuplicateEdge => "duplicate_edge",
+            IssueCode::DuplicateUnitId => "duplicate_unit_id",
+            IssueCode::DuplicateTrapId => "duplicate_trap_id",
+            IssueCode::MonsterRoomMismatch => "monster_room_mismatch",
+            IssueCode::HpExceedsMax => "hp_exceeds_max",
+            IssueCode::NonPositiveHp => "non_positive_hp",
+            IssueCode::UnknownSpawnRoom => "unknown_spawn_room",
+            IssueCode::CoreUnreachable => "core_unreachable",
+            IssueCode::SpawnCannotReachCore => "spawn_cannot_reach_core",
+            IssueCode::IsolatedRoom => "isolated_room",
+            IssueCode::EmptyWave => "empty_wave",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub code: IssueCode,
+    /// Location of the offending value, rooted at `dungeon` or `wave`.
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{severity}[{}] {}: {}",
+            self.code.as_str(),
+            self.path,
+            self.message
+        )
+    }
+}
+
+/// Every issue found by [`validate`], in input order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    fn error(&mut self, code: IssueCode, path: String, message: String) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Error,
+            code,
+            path,
+            message,
+        });
+    }
+
+    fn warning(&mut self, code: IssueCode, path: String, message: String) {
+        self.issues.push(ValidationIssue {
+            severity: Severity::Warning,
+            code,
+            path,
+            message,
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, issue) in self.issues.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check `dungeon` and `wave` for structural problems and report all of them.
+///
+/// Size limits are not checked here; they depend on the [`crate::SimulationConfig`] and
+/// are enforced by [`crate::simulate_wave`].
+pub fn validate(dungeon: &DungeonState, wave: &WaveConfig) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let rooms = validate_rooms(dungeon, &mut report);
+    validate_edges(dungeon, &rooms, &mut report);
+    validate_wave(dungeon, wave, &rooms, &mut report);
+    report
+}
+
+fn validate_rooms(dungeon: &DungeonState, report: &mut ValidationReport) -> HashSet<RoomId> {
+    if dungeon.rooms.is_empty() {
+        report.error(
+            IssueCode::NoRooms,
+            "dungeon.rooms".into(),
+            "dungeon has no rooms".into(),
+        );
+    }
+
+    let mut rooms = HashSet::new();
+    let mut units = HashMap::new();
+    let mut traps = HashMap::new();
+    for (room_idx, room) in dungeon.rooms.iter().enumerate() {
+        let room_path = format!("dungeon.rooms[{room_idx}]");
+        if !rooms.insert(room.id) {
+            report.error(
+                IssueCode::DuplicateRoomId,
+                format!("{room_path}.id"),
+                format!("room id {} is used more than once", room.id.0),
+            );
+        }
+
+        for (monster_idx, monster) in room.monsters.iter().enumerate() {
+            let path = format!("{room_path}.monsters[{monster_idx}]");
+            if let Some(first) = units.get(&monster.id) {
+                report.error(
+                    IssueCode::DuplicateUnitId,
+                    format!("{path}.id"),
+                    format!("unit id {} is already used at {first}", monster.id.0),
+                );
+            } else {
+                units.insert(monster.id, path.clone());
+            }
+            if monster.room_id != room.id {
+                report.error(
+                    IssueCode::MonsterRoomMismatch,
+                    format!("{path}.room_id"),
+                    format!(
+                        "monster says it is in room {} but is listed in room {}",
+                        monster.room_id.0, room.id.0
+                    ),
+                );
+            }
+            if monster.hp > monster.stats.max_hp {
+                report.error(
+                    IssueCode::HpExceedsMax,
+                    format!("{path}.hp"),
+                    format!("hp {} exceeds max_hp {}", monster.hp, monster.stats.max_hp),
+                );
+            }
+            if monster.hp <= 0 {
+                report.warning(
+                    IssueCode::NonPositiveHp,
+                    format!("{path}.hp"),
+                    format!("monster starts dead with hp {}", monster.hp),
+                );
+            }
+        }
+
+        for (trap_idx, trap) in room.traps.iter().enumerate() {
+            let path = format!("{room_path}.traps[{trap_idx}]");
+            if let Some(first) = traps.get(&trap.id) {
+                report.error(
+                    IssueCode::DuplicateTrapId,
+                    format!("{path}.id"),
+                    format!("trap id {} is already used at {first}", trap.id.0),
+                );
+            } else {
+                traps.insert(trap.id, path);
+            }
+        }
+    }
+
+    if !dungeon.rooms.is_empty() && !rooms.contains(&dungeon.core_room_id) {
+        report.error(
+            IssueCode::MissingCoreRoom,
+            "dungeon.core_room_id".into(),
+            format!("core room {} does not exist", dungeon.core_room_id.0),
+        );
+    }
+    if dungeon.core_hp <= 0 {
+        report.warning(
+            IssueCode::NonPositiveCoreHp,
+            "dungeon.core_hp".into(),
+            format!(
+                "core starts destroyed with hp {}; heroes win on the first tick",
+                dungeon.core_hp
+            ),
+        );
+    }
+
+    rooms
+}
+
+fn validate_edges(dungeon: &DungeonState, rooms: &HashSet<RoomId>, report: &mut ValidationReport) {
+    let mut seen = HashMap::new();
+    let mut connected = HashSet::new();
+    for (edge_idx, (a, b)) in dungeon.edges.iter().enumerate() {
+        let path = format!("dungeon.edges[{edge_idx}]");
+        for (end, room) in [(0, a), (1, b)] {
+            if !rooms.contains(room) {
+                report.error(
+                    IssueCode::UnknownEdgeRoom,
+                    format!("{path}[{end}]"),
+                    format!("edge references unknown room {}", room.0),
+                );
+            }
+        }
+        if a == b {
+            report.error(
+                IssueCode::SelfLoopEdge,
+                path,
+                format!("edge connects room {} to itself", a.0),
+            );
+            continue;
+        }
+        let key = if a < b { (*a, *b) } else { (*b, *a) };
+        if let Some(first) = seen.get(&key) {
+            report.error(
+                IssueCode::DuplicateEdge,
+                path,
+                format!(
+                    "rooms {} and {} are already connected by edges[{first}]",
+                    a.0, b.0
+                ),
+            );
+        } else {
+            seen.insert(key, edge_idx);
+        }
+        connected.insert(*a);
+        connected.insert(*b);
+    }
+
+    if dungeon.rooms.len() > 1 {
+        for (room_idx, room) in dungeon.rooms.iter().enumerate() {
+            if !connected.contains(&room.id) {
+                report.warning(
+                    IssueCode::IsolatedRoom,
+                    format!("dungeon.rooms[{room_idx}]"),
+                    format!("room {} has no edges", room.id.0),
+                );
+            }
+        }
+    }
+}
+
+fn validate_wave(
+    dungeon: &DungeonState,
+    wave: &WaveConfig,
+    rooms: &HashSet<RoomId>,
+    report: &mut ValidationReport,
+) {
+    if wave.entries.iter().all(|entry| entry.count == 0) {
+        report.warning(
+            IssueCode::EmptyWave,
+            "wave.entries".into(),
+            "wave spawns no heroes".into(),
+        );
+    }
+
+    let core_exists = rooms.contains(&dungeon.core_room_id);
+    let mut reachable = HashMap::new();
+    let mut any_known_spawn = false;
+    for (entry_idx, entry) in wave.entries.iter().enumerate() {
+        let path = format!("wave.entries[{entry_idx}].spawn_room_id");
+        if !rooms.contains(&entry.spawn_room_id) {
+            report.error(
+                IssueCode::UnknownSpawnRoom,
+                path,
+                format!("spawn room {} does not exist", entry.spawn_room_id.0),
+            );
+            continue;
+        }
+        any_known_spawn = true;
+        if !core_exists {
+            continue;
+        }
+        let reaches_core = *reachable.entry(entry.spawn_room_id).or_insert_with(|| {
+            shortest_path(entry.spawn_room_id, dungeon.core_room_id, &dungeon.edges).is_some()
+        });
+        if !reaches_core {
+            report.warning(
+                IssueCode::SpawnCannotReachCore,
+                path,
+                format!(
+                    "no path from spawn room {} to core room {}",
+                    entry.spawn_room_id.0, dungeon.core_room_id.0
+                ),
+            );
+        }
+    }
+
+    if core_exists && any_known_spawn && !reachable.values().any(|reaches| *reaches) {
+        report.error(
+            IssueCode::CoreUnreachable,
+            "dungeon.core_room_id".into(),
+            format!(
+                "core room {} cannot be reached from any spawn room",
+                dungeon.core_room_id.0
+            ),
+        );
+    }
+}
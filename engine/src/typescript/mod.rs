@@ -0,0 +1,102 @@
+//! TypeScript declarations for the engine's JSON types, generated from the Rust model so
+//! the client cannot drift from it.
+//!
+//! `cargo run --bin gen_ts_types` writes [`typescript_bindings`] to [`BINDINGS_PATH`]; a
+//! test fails while the checked-in file is stale.
+
+use ts_rs::TS;
+
+use crate::draft::{DraftOffer, DraftOption, DraftPick, DraftRules, RarityWeights};
+use crate::fixed::Fixed;
+use crate::model::{
+    AiBehavior, DungeonState, Faction, HeroSpawn, Rarity, RoomId, RoomState, SimulationOutcome,
+    SimulationResult, SimulationStats, StalemateReason, StatusInstance, StatusKind, TrapId,
+    TrapInstance, TrapTriggerType, UnitId, UnitInstance, UnitStats, WaveConfig,
+};
+use crate::sim::events::{DamageSource, EventId, SimulationEvent};
+use crate::sim::{
+    BetweenWaveRules, RuleToggles, RunResult, RunRules, RunScore, RunSummary, SimulationConfig,
+    SimulationLimits, StateChecksum, TickChecksum, WaveScore, WaveView,
+};
+use crate::verify::{
+    DraftChoice, Mismatch, RunSubmission, SubmittedRunSummary, VerificationReport,
+};
+
+/// The generated file, relative to the engine crate.
+pub const BINDINGS_PATH: &str = "../client/src/engine/engine.generated.ts";
+
+const HEADER: &str = "\
+// Generated from the Rust model by `cargo run --bin gen_ts_types` in engine/. Do not edit;
+// change the Rust types and regenerate.
+";
+
+/// Every exported declaration, in a fixed order.
+pub fn typescript_bindings() -> String {
+    let mut out = String::from(HEADER);
+    // Ids and scalars.
+    declare::<RoomId>(&mut out);
+    declare::<UnitId>(&mut out);
+    declare::<TrapId>(&mut out);
+    declare::<EventId>(&mut out);
+    declare::<Fixed>(&mut out);
+    // Dungeon and units.
+    declare::<DungeonState>(&mut out);
+    declare::<RoomState>(&mut out);
+    declare::<TrapInstance>(&mut out);
+    declare::<TrapTriggerType>(&mut out);
+    declare::<UnitInstance>(&mut out);
+    declare::<UnitStats>(&mut out);
+    declare::<Faction>(&mut out);
+    declare::<AiBehavior>(&mut out);
+    declare::<StatusInstance>(&mut out);
+    declare::<StatusKind>(&mut out);
+    // Waves and configuration.
+    declare::<WaveConfig>(&mut out);
+    declare::<HeroSpawn>(&mut out);
+    declare::<SimulationConfig>(&mut out);
+    declare::<SimulationLimits>(&mut out);
+    declare::<RuleToggles>(&mut out);
+    // Results.
+    declare::<SimulationResult>(&mut out);
+    declare::<SimulationOutcome>(&mut out);
+    declare::<StalemateReason>(&mut out);
+    declare::<SimulationStats>(&mut out);
+    declare::<SimulationEvent>(&mut out);
+    declare::<DamageSource>(&mut out);
+    declare::<TickChecksum>(&mut out);
+    declare::<StateChecksum>(&mut out);
+    declare::<WaveScore>(&mut out);
+    declare::<WaveView<'static>>(&mut out);
+    // Runs and drafting.
+    declare::<RunRules>(&mut out);
+    declare::<BetweenWaveRules>(&mut out);
+    declare::<RunResult>(&mut out);
+    declare::<RunSummary>(&mut out);
+    declare::<RunScore>(&mut out);
+    declare::<Rarity>(&mut out);
+    declare::<DraftRules>(&mut out);
+    declare::<RarityWeights>(&mut out);
+    declare::<DraftOffer>(&mut out);
+    declare::<DraftOption>(&mut out);
+    declare::<DraftPick>(&mut out);
+    // Verification.
+    declare::<RunSubmission>(&mut out);
+    declare::<SubmittedRunSummary>(&mut out);
+    declare::<DraftChoice>(&mut out);
+    declare::<VerificationReport>(&mut out);
+    declare::<Mismatch>(&mut out);
+    out
+}
+
+fn declare<T: TS + ?Sized>(out: &mut String) {
+    out.push('\n');
+    if let Some(docs) = T::docs() {
+        out.push_str(&docs);
+    }
+    out.push_str("export ");
+    out.push_str(&T::decl());
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests;
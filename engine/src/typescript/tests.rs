@@ -0,0 +1,10 @@
+use super::typescript_bindings;
+
+#[test]
+fn checked_in_typescript_bindings_are_current() {
+    let checked_in = include_str!("../../../client/src/engine/engine.generated.ts");
+    assert!(
+        checked_in == typescript_bindings(),
+        "client/src/engine/engine.generated.ts is stale; run `cargo run --bin gen_ts_types` in engine/"
+    );
+}
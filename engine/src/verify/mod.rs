@@ -0,0 +1,373 @@
+//! Server-side verification of submitted runs (contract/phase4.md §6).
+//!
+//! [`verify_run`] replays a [`RunSubmission`] against the [`RunTemplate`] of the content
+//! pack: before wave `i` it regenerates draft offer `i` from the run seed, checks the
+//! submitted pick against it and applies it, then simulates the wave. The recomputed run
+//! is compared with what the client claimed and every disagreement is reported as a
+//! [`Mismatch`].
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::ENGINE_VERSION;
+use crate::draft::{DraftPick, generate_offer};
+use crate::model::{ContentPack, RunTemplate, SimulationOutcome};
+use crate::sim::{RunResult, RunState, RunSummary};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+/// A completed run as submitted by a client (`POST /v1/run/submit`).
+///
+/// `seed` is a full `u64`, so it travels as a decimal string that JavaScript cannot round;
+/// an integer is still accepted if it is at most 2^53 - 1.
+///
+/// # JSON example
+/// ```json
+/// {
+///   "content_version": "1.0.0",
+///   "engine_version": "0.1.0",
+///   "seed": "123456789",
+///   "challenge_id": null,
+///   "score": 850,
+///   "outcome": "DungeonWin",
+///   "run_summary": {
+///     "waves_cleared": 2,
+///     "core_hp_remaining": 42,
+///     "draft_choices": [
+///       { "tier": 1, "option": 0, "target_room": 1, "picked_id": "trap_fire_glyph" },
+///       { "tier": 1, "option": 2, "picked_id": "relic_furnace_core" }
+///     ]
+///   }
+/// }
+/// ```
+pub struct RunSubmission {
+    pub content_version: String,
+    pub engine_version: String,
+    #[serde(with = "decimal_seed")]
+    #[schemars(schema_with = "decimal_seed::schema")]
+    #[ts(type = "string")]
+    pub seed: u64,
+    #[serde(default)]
+    #[ts(optional = nullable)]
+    pub challenge_id: Option<String>,
+    /// Run score as computed by the client, compared exactly with the replay.
+    #[ts(type = "number")]
+    pub score: i64,
+    pub outcome: SimulationOutcome,
+    pub run_summary: SubmittedRunSummary,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub struct SubmittedRunSummary {
+    pub waves_cleared: u32,
+    pub core_hp_remaining: i32,
+    /// One choice per draft, in order; draft `i` precedes wave `i`.
+    pub draft_choices: Vec<DraftChoice>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub struct DraftChoice {
+    /// Tier the client believed the draft was at.
+    pub tier: u32,
+    #[serde(flatten)]
+    pub pick: DraftPick,
+    /// Content id of the picked option, checked against the regenerated offer.
+    pub picked_id: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+/// One way a submission disagrees with the replay. The `reason` tag is part of the
+/// backend contract.
+pub enum Mismatch {
+    /// The content pack has no [`RunTemplate`], so nothing can be replayed.
+    NoRunTemplate,
+    ContentVersion {
+        expected: String,
+        claimed: String,
+    },
+    EngineVersion {
+        expected: String,
+        claimed: String,
+    },
+    /// The run was still going but the submission has no choice for this draft.
+    MissingDraft {
+        draft_index: u32,
+    },
+    /// More draft choices were submitted than drafts took place.
+    ExtraDrafts {
+        expected: u32,
+        claimed: u32,
+    },
+    Tier {
+        draft_index: u32,
+        expected: u32,
+        claimed: u32,
+    },
+    /// The picked option's content id is not what the regenerated offer holds there.
+    PickedId {
+        draft_index: u32,
+        expected: Option<String>,
+        claimed: String,
+    },
+    /// The pick could not be applied, e.g. its target room does not exist.
+    IllegalPick {
+        draft_index: u32,
+        message: String,
+    },
+    /// The engine refused to simulate a wave of the replayed run.
+    SimulationFailed {
+        wave_index: u32,
+        message: String,
+    },
+    Outcome {
+        expected: SimulationOutcome,
+        claimed: SimulationOutcome,
+    },
+    /// `score_version` is the [`crate::sim::SCORE_VERSION`] the expected score uses.
+    Score {
+        #[ts(type = "number")]
+        expected: i64,
+        #[ts(type = "number")]
+        claimed: i64,
+        score_version: u32,
+    },
+    WavesCleared {
+        expected: u32,
+        claimed: u32,
+    },
+    CoreHpRemaining {
+        expected: i32,
+        claimed: i32,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub struct VerificationReport {
+    /// True when there are no mismatches.
+    pub valid: bool,
+    pub mismatches: Vec<Mismatch>,
+    /// Summary of the replayed run, absent when the replay could not finish.
+    pub recomputed: Option<RunSummary>,
+}
+
+impl VerificationReport {
+    fn new(mismatches: Vec<Mismatch>, recomputed: Option<RunSummary>) -> Self {
+        Self {
+            valid: mismatches.is_empty(),
+            mismatches,
+            recomputed,
+        }
+    }
+}
+
+/// Replay `submission` against `content` and report every way it disagrees.
+///
+/// Version mismatches are reported but do not stop the replay, so one report shows
+/// everything that is wrong with a submission.
+pub fn verify_run(submission: &RunSubmission, content: &ContentPack) -> VerificationReport {
+    let Some(template) = &content.run else {
+        return VerificationReport::new(vec![Mismatch::NoRunTemplate], None);
+    };
+
+    let mut mismatches = Vec::new();
+    if submission.content_version != content.version {
+        mismatches.push(Mismatch::ContentVersion {
+            expected: content.version.clone(),
+            claimed: submission.content_version.clone(),
+        });
+    }
+    if submission.engine_version != ENGINE_VERSION {
+        mismatches.push(Mismatch::EngineVersion {
+            expected: ENGINE_VERSION.to_string(),
+            claimed: submission.engine_version.clone(),
+        });
+    }
+
+    let result = match replay(submission, content, template, &mut mismatches) {
+        Some(result) => result,
+        None => return VerificationReport::new(mismatches, None),
+    };
+
+    let summary = result.summary;
+    let claimed = &submission.run_summary;
+    if submission.outcome != summary.outcome {
+        mismatches.push(Mismatch::Outcome {
+            expected: summary.outcome.clone(),
+            claimed: submission.outcome.clone(),
+        });
+    }
+    if submission.score != summary.score.total {
+        mismatches.push(Mismatch::Score {
+            expected: summary.score.total,
+            claimed: submission.score,
+            score_version: summary.score.version,
+        });
+    }
+    if claimed.waves_cleared != summary.waves_cleared {
+        mismatches.push(Mismatch::WavesCleared {
+            expected: summary.waves_cleared,
+            claimed: claimed.waves_cleared,
+        });
+    }
+    if claimed.core_hp_remaining != summary.core_hp_remaining {
+        mismatches.push(Mismatch::CoreHpRemaining {
+            expected: summary.core_hp_remaining,
+            claimed: claimed.core_hp_remaining,
+        });
+    }
+    VerificationReport::new(mismatches, Some(summary))
+}
+
+/// Rebuild and simulate the run. Returns `None` after recording the mismatch that made
+/// the replay impossible to finish.
+fn replay(
+    submission: &RunSubmission,
+    content: &ContentPack,
+    template: &RunTemplate,
+    mismatches: &mut Vec<Mismatch>,
+) -> Option<RunResult> {
+    let choices = &submission.run_summary.draft_choices;
+    let mut run = RunState::new(
+        template.starting_dungeon.clone(),
+        submission.seed,
+        template.rules.clone(),
+    );
+
+    for (wave_index, wave) in template.waves.iter().enumerate() {
+        let draft_index = wave_index as u32;
+        let Some(choice) = choices.get(wave_index) else {
+            mismatches.push(Mismatch::MissingDraft { draft_index });
+            return None;
+        };
+
+        let tier = template.rules.tier_for_wave(wave_index);
+        if choice.tier != tier {
+            mismatches.push(Mismatch::Tier {
+                draft_index,
+                expected: tier,
+                claimed: choice.tier,
+            });
+        }
+        let offer = generate_offer(
+            content,
+            submission.seed,
+            draft_index,
+            tier,
+            &template.draft_rules,
+        );
+        let offered = offer.options.get(choice.pick.option);
+        if offered.map(|o| o.content_id()) != Some(choice.picked_id.as_str()) {
+            mismatches.push(Mismatch::PickedId {
+                draft_index,
+                expected: offered.map(|o| o.content_id().to_string()),
+                claimed: choice.picked_id.clone(),
+            });
+            return None;
+        }
+        if let Err(err) = run.apply_pick(content, &offer, &choice.pick) {
+            mismatches.push(Mismatch::IllegalPick {
+                draft_index,
+                message: err.to_string(),
+            });
+            return None;
+        }
+
+        if let Err(err) = run.play_wave(wave) {
+            mismatches.push(Mismatch::SimulationFailed {
+                wave_index: draft_index,
+                message: err.to_string(),
+            });
+            return None;
+        }
+        if run.is_over() {
+            break;
+        }
+    }
+
+    let drafts = run.waves_played();
+    if choices.len() > drafts {
+        mismatches.push(Mismatch::ExtraDrafts {
+            expected: drafts as u32,
+            claimed: choices.len() as u32,
+        });
+    }
+    Some(run.finish())
+}
+
+/// Serde and JSON Schema for [`RunSubmission::seed`].
+mod decimal_seed {
+    use schemars::r#gen::SchemaGenerator;
+    use schemars::schema::{
+        InstanceType, NumberValidation, Schema, SchemaObject, StringValidation, SubschemaValidation,
+    };
+    use serde::de::{self, Unexpected};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Largest integer a JSON number carries exactly in JavaScript.
+    const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+    pub fn serialize<S: Serializer>(seed: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(seed)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Seed {
+            Decimal(String),
+            Number(u64),
+        }
+        match Seed::deserialize(deserializer)? {
+            Seed::Decimal(text) => {
+                if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(de::Error::invalid_value(
+                        Unexpected::Str(&text),
+                        &"a decimal u64 string",
+                    ));
+                }
+                text.parse().map_err(de::Error::custom)
+            }
+            Seed::Number(seed) if seed <= MAX_SAFE_INTEGER => Ok(seed),
+            Seed::Number(seed) => Err(de::Error::invalid_value(
+                Unexpected::Unsigned(seed),
+                &"a seed above 2^53 - 1 sent as a decimal string",
+            )),
+        }
+    }
+
+    pub fn schema(_: &mut SchemaGenerator) -> Schema {
+        let decimal = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some("^[0-9]{1,20}$".into()),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let number = SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                maximum: Some(MAX_SAFE_INTEGER as f64),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![decimal.into(), number.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,241 @@
+use crate::ENGINE_VERSION;
+use crate::draft::{DraftPick, generate_offer};
+use crate::model::{ContentPack, RoomId, SimulationOutcome};
+use crate::sim::RunState;
+use crate::verify::{DraftChoice, Mismatch, RunSubmission, SubmittedRunSummary, verify_run};
+
+fn content() -> ContentPack {
+    serde_json::from_str(
+        r#"{
+            "version": "1.0.0",
+            "rooms": [{ "id": "room_corridor", "name": "Corridor" }],
+            "traps": [
+                { "id": "trap_spikes", "name": "Spikes", "base_damage": 4, "cooldown_ticks": 2,
+                  "trigger_type": "on_enter" }
+            ],
+            "monsters": [
+                { "id": "monster_rat", "name": "Rat", "max_hp": 8, "armor": 0,
+                  "move_speed": 0.0, "attack_damage": 2, "attack_interval_ticks": 1,
+                  "attack_range": 0 }
+            ],
+            "relics": [
+                { "id": "relic_furnace_core", "name": "Furnace Core",
+                  "effect_type": "global_multiplier" }
+            ],
+            "run": {
+                "starting_dungeon": {
+                    "rooms": [
+                        { "id": 0, "traps": [], "monsters": [], "tags": [] },
+                        { "id": 1, "traps": [], "monsters": [], "tags": [] },
+                        { "id": 2, "traps": [], "monsters": [], "tags": [] }
+                    ],
+                    "edges": [[0, 1], [1, 2]],
+                    "core_room_id": 2,
+                    "core_hp": 1000
+                },
+                "waves": [
+                    { "id": "w1", "entries": [{ "hero_template_id": "h1", "count": 1,
+                      "spawn_room_id": 0, "delay_ticks": 0 }], "modifiers": [] },
+                    { "id": "w2", "entries": [{ "hero_template_id": "h1", "count": 2,
+                      "spawn_room_id": 0, "delay_ticks": 0 }], "modifiers": [] },
+                    { "id": "w3", "entries": [{ "hero_template_id": "h1", "count": 2,
+                      "spawn_room_id": 0, "delay_ticks": 1 }], "modifiers": [] }
+                ],
+                "rules": { "max_ticks_per_wave": 20, "waves_per_tier": 2 }
+            }
+        }"#,
+    )
+    .expect("content pack should parse")
+}
+
+/// Play the template run the way a client would, always taking the first option into
+/// room 1, and submit exactly what happened.
+fn honest_submission(content: &ContentPack, seed: u64) -> RunSubmission {
+    let template = content.run.as_ref().expect("pack has a run");
+    let mut run = RunState::new(
+        template.starting_dungeon.clone(),
+        seed,
+        template.rules.clone(),
+    );
+    let mut draft_choices = Vec::new();
+    for (idx, wave) in template.waves.iter().enumerate() {
+        let tier = template.rules.tier_for_wave(idx);
+        let offer = generate_offer(content, seed, idx as u32, tier, &template.draft_rules);
+        let pick = DraftPick {
+            option: 0,
+            target_room: Some(RoomId(1)),
+        };
+        run.apply_pick(content, &offer, &pick)
+            .expect("pick applies");
+        draft_choices.push(DraftChoice {
+            tier,
+            picked_id: offer.options[0].content_id().to_string(),
+            pick,
+        });
+        run.play_wave(wave).expect("wave simulates");
+        if run.is_over() {
+            break;
+        }
+    }
+    let summary = run.finish().summary;
+    RunSubmission {
+        content_version: content.version.clone(),
+        engine_version: ENGINE_VERSION.to_string(),
+        seed,
+        challenge_id: None,
+        score: summary.score.total,
+        outcome: summary.outcome,
+        run_summary: SubmittedRunSummary {
+            waves_cleared: summary.waves_cleared,
+            core_hp_remaining: summary.core_hp_remaining,
+            draft_choices,
+        },
+    }
+}
+
+#[test]
+fn honest_runs_verify() {
+    let content = content();
+    for seed in [1, 7, 99] {
+        let submission = honest_submission(&content, seed);
+        let report = verify_run(&submission, &content);
+        assert!(report.valid, "seed {seed}: {:?}", report.mismatches);
+        let recomputed = report.recomputed.expect("replay finished");
+        assert_eq!(3, submission.run_summary.draft_choices.len());
+        assert_eq!(
+            submission.run_summary.waves_cleared,
+            recomputed.waves_cleared
+        );
+    }
+}
+
+#[test]
+fn tampered_claims_are_reported() {
+    let content = content();
+    let mut submission = honest_submission(&content, 5);
+    submission.content_version = "0.9.0".into();
+    submission.outcome = SimulationOutcome::HeroesWin;
+    submission.score += 1;
+    submission.run_summary.core_hp_remaining += 50;
+    submission.run_summary.draft_choices[2].tier = 1;
+
+    let report = verify_run(&submission, &content);
+    assert!(!report.valid);
+    let reasons: Vec<&str> = report
+        .mismatches
+        .iter()
+        .map(|m| match m {
+            Mismatch::ContentVersion { .. } => "content_version",
+            Mismatch::Tier { draft_index: 2, .. } => "tier",
+            Mismatch::Outcome { .. } => "outcome",
+            Mismatch::Score { .. } => "score",
+            Mismatch::CoreHpRemaining { .. } => "core_hp_remaining",
+            other => panic!("unexpected mismatch {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            "content_version",
+            "tier",
+            "outcome",
+            "score",
+            "core_hp_remaining"
+        ],
+        reasons
+    );
+    assert!(
+        report.recomputed.is_some(),
+        "version mismatches still replay"
+    );
+
+    let json = serde_json::to_value(&report.mismatches[0]).unwrap();
+    assert_eq!("content_version", json["reason"]);
+}
+
+#[test]
+fn illegal_draft_choices_stop_the_replay() {
+    let content = content();
+    let honest = honest_submission(&content, 3);
+
+    let mut forged = honest.clone();
+    forged.run_summary.draft_choices[1].picked_id = "relic_not_offered".into();
+    let report = verify_run(&forged, &content);
+    assert!(matches!(
+        report.mismatches.as_slice(),
+        [Mismatch::PickedId { draft_index: 1, .. }]
+    ));
+    assert_eq!(None, report.recomputed);
+
+    let mut bad_room = honest.clone();
+    let (draft_index, choice) = bad_room
+        .run_summary
+        .draft_choices
+        .iter_mut()
+        .enumerate()
+        .find(|(_, c)| content.relic(&c.picked_id).is_none())
+        .expect("some pick needs a room");
+    choice.pick.target_room = Some(RoomId(40));
+    let report = verify_run(&bad_room, &content);
+    assert_eq!(1, report.mismatches.len());
+    assert!(matches!(
+        &report.mismatches[0],
+        Mismatch::IllegalPick { draft_index: idx, .. } if *idx as usize == draft_index
+    ));
+
+    let mut short = honest.clone();
+    short.run_summary.draft_choices.pop();
+    let report = verify_run(&short, &content);
+    assert_eq!(
+        vec![Mismatch::MissingDraft { draft_index: 2 }],
+        report.mismatches
+    );
+
+    let mut long = honest;
+    let extra = long.run_summary.draft_choices[0].clone();
+    long.run_summary.draft_choices.push(extra);
+    let report = verify_run(&long, &content);
+    assert_eq!(
+        vec![Mismatch::ExtraDrafts {
+            expected: 3,
+            claimed: 4
+        }],
+        report.mismatches
+    );
+}
+
+#[test]
+fn packs_without_a_run_cannot_verify() {
+    let mut content = content();
+    let submission = honest_submission(&content, 1);
+    content.run = None;
+    let report = verify_run(&submission, &content);
+    assert_eq!(vec![Mismatch::NoRunTemplate], report.mismatches);
+    assert!(!report.valid);
+}
+
+#[test]
+fn seeds_travel_as_decimal_strings() {
+    let mut submission = honest_submission(&content(), 7);
+    submission.seed = u64::MAX;
+    let json = serde_json::to_value(&submission).unwrap();
+    assert_eq!(serde_json::json!("18446744073709551615"), json["seed"]);
+    let parsed: RunSubmission = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(u64::MAX, parsed.seed);
+
+    let with_seed = |seed: serde_json::Value| {
+        let mut json = json.clone();
+        json["seed"] = seed;
+        serde_json::from_value::<RunSubmission>(json).map(|submission| submission.seed)
+    };
+    assert_eq!(
+        9_007_199_254_740_991,
+        with_seed(9_007_199_254_740_991u64.into()).unwrap()
+    );
+    assert!(
+        with_seed(9_007_199_254_740_992u64.into()).is_err(),
+        "JS may have rounded it"
+    );
+    assert!(with_seed("-1".into()).is_err());
+    assert!(with_seed("".into()).is_err());
+    assert!(with_seed("18446744073709551616".into()).is_err());
+}
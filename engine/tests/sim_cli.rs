@@ -0,0 +1,253 @@
+//! The `sim_cli --format json|ndjson` contract: exit codes, the `{"error": ...}` object and
+//! the ndjson line types that scripts parse.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use engine::model::{HeroSpawn, RoomId, WaveConfig};
+use engine::sim::test_fixtures;
+use serde_json::Value;
+
+const FORMATS: [&str; 2] = ["json", "ndjson"];
+
+struct CliOutput {
+    code: i32,
+    stdout: String,
+}
+
+impl CliOutput {
+    /// The single JSON document on stdout.
+    fn document(&self) -> Value {
+        serde_json::from_str(&self.stdout).unwrap_or_else(|err| {
+            panic!("stdout is not one JSON document ({err}): {}", self.stdout)
+        })
+    }
+
+    /// One JSON value per stdout line.
+    fn lines(&self) -> Vec<Value> {
+        self.stdout
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each ndjson line is JSON"))
+            .collect()
+    }
+}
+
+fn sim_cli(args: &[&str]) -> CliOutput {
+    let output = Command::new(env!("CARGO_BIN_EXE_sim_cli"))
+        .args(args)
+        .output()
+        .expect("sim_cli should start");
+    CliOutput {
+        code: output.status.code().expect("sim_cli should exit normally"),
+        stdout: String::from_utf8(output.stdout).expect("stdout is UTF-8"),
+    }
+}
+
+/// Write `value` to a file unique to this test process and return its path.
+fn write_json(name: &str, value: &impl serde::Serialize) -> String {
+    let dir = std::env::temp_dir().join(format!("sim_cli_contract_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path: PathBuf = dir.join(name);
+    fs::write(&path, serde_json::to_string(value).unwrap()).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+/// The duel fixture's dungeon and wave, plus a wave that spawns into a missing room.
+fn inputs(test: &str) -> (String, String, String) {
+    let fixture = test_fixtures::core_room_duel();
+    let broken = WaveConfig {
+        id: "broken".into(),
+        entries: vec![HeroSpawn {
+            hero_template_id: "h1".into(),
+            count: 1,
+            spawn_room_id: RoomId(99),
+            delay_ticks: 0,
+        }],
+        modifiers: Vec::new(),
+    };
+    (
+        write_json(&format!("{test}_dungeon.json"), &fixture.dungeon),
+        write_json(&format!("{test}_wave.json"), &fixture.wave),
+        write_json(&format!("{test}_broken_wave.json"), &broken),
+    )
+}
+
+/// Assert the `{"error": {...}}` object every non-`failed` error prints in JSON formats.
+fn assert_error(output: &CliOutput, format: &str, kind: &str, exit_code: i32) {
+    assert_eq!(exit_code, output.code, "{format}: {}", output.stdout);
+    if format == "ndjson" {
+        assert_eq!(
+            1,
+            output.stdout.lines().count(),
+            "one line: {}",
+            output.stdout
+        );
+    }
+    let error = &output.document()["error"];
+    assert_eq!(kind, error["kind"], "{format}");
+    assert_eq!(exit_code, error["exit_code"], "{format}");
+    assert!(error["message"].is_string(), "{format}");
+}
+
+#[test]
+fn failed_commands_exit_1_with_the_verdict_and_no_error_object() {
+    let (dungeon, _, broken) = inputs("failed");
+    for format in FORMATS {
+        let output = sim_cli(&[
+            "validate",
+            "--dungeon",
+            &dungeon,
+            "--wave",
+            &broken,
+            "--format",
+            format,
+        ]);
+        assert_eq!(1, output.code, "{format}");
+        let report = output.document();
+        assert!(report.get("error").is_none(), "{format}");
+        assert!(!report["issues"].as_array().unwrap().is_empty(), "{format}");
+    }
+}
+
+#[test]
+fn usage_errors_exit_2() {
+    for format in FORMATS {
+        assert_error(
+            &sim_cli(&["frobnicate", "--format", format]),
+            format,
+            "usage",
+            2,
+        );
+        assert_error(
+            &sim_cli(&["run", "--seed", "x", "--format", format]),
+            format,
+            "usage",
+            2,
+        );
+    }
+    // An unknown format is reported as JSON, since the requested one is unusable.
+    assert_error(&sim_cli(&["run", "--format", "yaml"]), "json", "usage", 2);
+}
+
+#[test]
+fn input_errors_exit_3() {
+    let (_, wave, _) = inputs("input");
+    for format in FORMATS {
+        let output = sim_cli(&[
+            "run",
+            "--dungeon",
+            "/nonexistent/dungeon.json",
+            "--wave",
+            &wave,
+            "--seed",
+            "1",
+            "--format",
+            format,
+        ]);
+        assert_error(&output, format, "input", 3);
+    }
+}
+
+#[test]
+fn simulation_errors_exit_4_with_validation_details() {
+    let (dungeon, wave, broken) = inputs("simulation");
+    for format in FORMATS {
+        let too_long = sim_cli(&[
+            "run",
+            "--dungeon",
+            &dungeon,
+            "--wave",
+            &wave,
+            "--seed",
+            "1",
+            "--max-ticks",
+            "4000000000",
+            "--format",
+            format,
+        ]);
+        assert_error(&too_long, format, "simulation", 4);
+
+        let invalid = sim_cli(&[
+            "run",
+            "--dungeon",
+            &dungeon,
+            "--wave",
+            &broken,
+            "--seed",
+            "1",
+            "--format",
+            format,
+        ]);
+        assert_error(&invalid, format, "simulation", 4);
+        let issues = &invalid.document()["error"]["validation"]["issues"];
+        assert!(!issues.as_array().unwrap().is_empty(), "{format}");
+    }
+}
+
+#[test]
+fn stress_ndjson_prints_a_line_per_seed_then_the_report() {
+    let (dungeon, wave, _) = inputs("stress");
+    let output = sim_cli(&[
+        "stress",
+        "--dungeon",
+        &dungeon,
+        "--wave",
+        &wave,
+        "--start-seed",
+        "10",
+        "--runs",
+        "3",
+        "--max-ticks",
+        "50",
+        "--format",
+        "ndjson",
+    ]);
+    assert_eq!(0, output.code, "{}", output.stdout);
+
+    let lines = output.lines();
+    assert_eq!(4, lines.len());
+    for (line, seed) in lines.iter().zip(10..13) {
+        assert_eq!("seed", line["type"]);
+        assert_eq!(seed, line["seed"]);
+        assert!(line["outcome"].is_string() || line["outcome"].is_object());
+    }
+    let report = &lines[3];
+    assert_eq!("report", report["type"]);
+    assert_eq!(10, report["start_seed"]);
+    assert_eq!(3, report["runs"]);
+    assert_eq!(false, report["stopped_early"]);
+}
+
+#[test]
+fn stress_seeds_wrap_past_the_largest_seed_across_chunks() {
+    let (dungeon, wave, _) = inputs("stress_wrap");
+    let start_seed = u64::MAX.to_string();
+    let output = sim_cli(&[
+        "stress",
+        "--dungeon",
+        &dungeon,
+        "--wave",
+        &wave,
+        "--start-seed",
+        &start_seed,
+        "--runs",
+        "1026",
+        "--max-ticks",
+        "5",
+        "--format",
+        "ndjson",
+    ]);
+    assert_eq!(0, output.code, "{}", output.stdout);
+
+    let lines = output.lines();
+    assert_eq!(1027, lines.len());
+    assert_eq!(u64::MAX, lines[0]["seed"]);
+    assert_eq!(0, lines[1]["seed"]);
+    assert_eq!(
+        1023, lines[1024]["seed"],
+        "the first chunk holds 1024 seeds"
+    );
+    assert_eq!(1024, lines[1025]["seed"]);
+    assert_eq!(u64::MAX, lines[1026]["start_seed"]);
+}
@@ -1,24 +1,181 @@
-use engine::{simulate_wave, DungeonState, WaveConfig, ENGINE_VERSION};
+use engine::draft::{apply_pick, generate_offer, DraftOffer, DraftPick, DraftRules};
+use engine::model::ContentPack;
+use engine::{
+    simulate_run, simulate_wave, DungeonState, RunRules, SimulationConfig, WaveConfig,
+    WaveSimulation, ENGINE_VERSION,
+};
 use wasm_bindgen::prelude::*;
 
+/// `config` may be `undefined`/`null` to use `SimulationConfig::default()`.
 #[wasm_bindgen]
 pub fn simulate_wave_wasm(
     dungeon: JsValue,
     wave: JsValue,
     seed: u64,
     max_ticks: u32,
+    config: JsValue,
 ) -> Result<JsValue, JsValue> {
     let dungeon: DungeonState = serde_wasm_bindgen::from_value(dungeon)
         .map_err(|err| JsValue::from_str(&format!("failed to parse dungeon: {err}")))?;
     let wave: WaveConfig = serde_wasm_bindgen::from_value(wave)
         .map_err(|err| JsValue::from_str(&format!("failed to parse wave: {err}")))?;
+    let config: SimulationConfig = if config.is_undefined() || config.is_null() {
+        SimulationConfig::default()
+    } else {
+        serde_wasm_bindgen::from_value(config)
+            .map_err(|err| JsValue::from_str(&format!("failed to parse config: {err}")))?
+    };
 
-    simulate_wave(dungeon, wave, seed, max_ticks)
+    simulate_wave(dungeon, wave, seed, max_ticks, &config)
         .map_err(|err| JsValue::from_str(&err.to_string()))
         .and_then(|result| serde_wasm_bindgen::to_value(&result)
             .map_err(|err| JsValue::from_str(&format!("failed to serialize result: {err}"))))
 }
 
+/// A wave simulated a few ticks at a time, so the client can render it while it runs
+/// (e.g. a few ticks per `requestAnimationFrame`). Stepping in any chunk sizes ends in the
+/// same result as `simulate_wave_wasm`.
+#[wasm_bindgen]
+pub struct Simulation {
+    inner: WaveSimulation<'static>,
+}
+
+#[wasm_bindgen]
+impl Simulation {
+    /// Same arguments and checks as `simulate_wave_wasm`; `config` may be
+    /// `undefined`/`null`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        dungeon: JsValue,
+        wave: JsValue,
+        seed: u64,
+        max_ticks: u32,
+        config: JsValue,
+    ) -> Result<Simulation, JsValue> {
+        let dungeon: DungeonState = serde_wasm_bindgen::from_value(dungeon)
+            .map_err(|err| JsValue::from_str(&format!("failed to parse dungeon: {err}")))?;
+        let wave: WaveConfig = serde_wasm_bindgen::from_value(wave)
+            .map_err(|err| JsValue::from_str(&format!("failed to parse wave: {err}")))?;
+        let config: SimulationConfig = if config.is_undefined() || config.is_null() {
+            SimulationConfig::default()
+        } else {
+            serde_wasm_bindgen::from_value(config)
+                .map_err(|err| JsValue::from_str(&format!("failed to parse config: {err}")))?
+        };
+
+        WaveSimulation::new(dungeon, wave, seed, max_ticks, &config)
+            .map(|inner| Simulation { inner })
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Run up to `ticks` more ticks. Returns how many ran: fewer when the wave finished,
+    /// 0 once it has.
+    pub fn step(&mut self, ticks: u32) -> Result<u32, JsValue> {
+        self.inner
+            .step(ticks)
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+
+    /// Events logged after the first `cursor`. Pass the `event_count` of the previous
+    /// `state_view()` (0 at the start) to receive each event exactly once.
+    pub fn events_since(&self, cursor: usize) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(self.inner.events_since(cursor))
+            .map_err(|err| JsValue::from_str(&format!("failed to serialize events: {err}")))
+    }
+
+    /// `{tick, max_ticks, dungeon, heroes, stats, event_count, outcome}`; `outcome` is
+    /// `undefined` until the wave finishes.
+    pub fn state_view(&self) -> Result<JsValue, JsValue> {
+        serde_wasm_bindgen::to_value(&self.inner.view())
+            .map_err(|err| JsValue::from_str(&format!("failed to serialize state: {err}")))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+
+    /// Run any remaining ticks and return the `SimulationResult`. Frees the simulation.
+    pub fn finish(self) -> Result<JsValue, JsValue> {
+        self.inner
+            .finish()
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+            .and_then(|result| serde_wasm_bindgen::to_value(&result)
+                .map_err(|err| JsValue::from_str(&format!("failed to serialize result: {err}"))))
+    }
+}
+
+/// Simulate a multi-wave run. `rules` may be `undefined`/`null` for `RunRules::default()`.
+#[wasm_bindgen]
+pub fn simulate_run_wasm(
+    dungeon: JsValue,
+    waves: JsValue,
+    run_seed: u64,
+    rules: JsValue,
+) -> Result<JsValue, JsValue> {
+    let dungeon: DungeonState = serde_wasm_bindgen::from_value(dungeon)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse dungeon: {err}")))?;
+    let waves: Vec<WaveConfig> = serde_wasm_bindgen::from_value(waves)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse waves: {err}")))?;
+    let rules: RunRules = if rules.is_undefined() || rules.is_null() {
+        RunRules::default()
+    } else {
+        serde_wasm_bindgen::from_value(rules)
+            .map_err(|err| JsValue::from_str(&format!("failed to parse rules: {err}")))?
+    };
+
+    simulate_run(dungeon, &waves, run_seed, &rules)
+        .map_err(|err| JsValue::from_str(&err.to_string()))
+        .and_then(|result| serde_wasm_bindgen::to_value(&result)
+            .map_err(|err| JsValue::from_str(&format!("failed to serialize result: {err}"))))
+}
+
+/// Generate draft offer `draft_index` of a run. `rules` may be `undefined`/`null` for
+/// `DraftRules::default()`.
+#[wasm_bindgen]
+pub fn draft_offer_wasm(
+    content: JsValue,
+    run_seed: u64,
+    draft_index: u32,
+    tier: u32,
+    rules: JsValue,
+) -> Result<JsValue, JsValue> {
+    let content: ContentPack = serde_wasm_bindgen::from_value(content)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse content pack: {err}")))?;
+    let rules: DraftRules = if rules.is_undefined() || rules.is_null() {
+        DraftRules::default()
+    } else {
+        serde_wasm_bindgen::from_value(rules)
+            .map_err(|err| JsValue::from_str(&format!("failed to parse draft rules: {err}")))?
+    };
+
+    let offer = generate_offer(&content, run_seed, draft_index, tier, &rules);
+    serde_wasm_bindgen::to_value(&offer)
+        .map_err(|err| JsValue::from_str(&format!("failed to serialize offer: {err}")))
+}
+
+/// Apply a draft pick and return the updated dungeon.
+#[wasm_bindgen]
+pub fn apply_draft_pick_wasm(
+    dungeon: JsValue,
+    content: JsValue,
+    offer: JsValue,
+    pick: JsValue,
+) -> Result<JsValue, JsValue> {
+    let mut dungeon: DungeonState = serde_wasm_bindgen::from_value(dungeon)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse dungeon: {err}")))?;
+    let content: ContentPack = serde_wasm_bindgen::from_value(content)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse content pack: {err}")))?;
+    let offer: DraftOffer = serde_wasm_bindgen::from_value(offer)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse offer: {err}")))?;
+    let pick: DraftPick = serde_wasm_bindgen::from_value(pick)
+        .map_err(|err| JsValue::from_str(&format!("failed to parse pick: {err}")))?;
+
+    apply_pick(&mut dungeon, &content, &offer, &pick)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+    serde_wasm_bindgen::to_value(&dungeon)
+        .map_err(|err| JsValue::from_str(&format!("failed to serialize dungeon: {err}")))
+}
+
 #[wasm_bindgen]
 pub fn engine_version_wasm() -> String {
     ENGINE_VERSION.to_string()
@@ -154,6 +154,7 @@ for (const scenario of scenarios) {
       scenario.wave,
       scenario.seed,
       scenario.maxTicks,
+      undefined,
     );
 
     const roundTripped = JSON.parse(JSON.stringify(result));
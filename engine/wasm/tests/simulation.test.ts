@@ -0,0 +1,51 @@
+import assert from 'node:assert/strict';
+import { test } from 'node:test';
+
+import { Simulation, simulate_wave_wasm } from '../pkg/engine_wasm';
+
+const room = (id: number) => ({ id, traps: [] as unknown[], monsters: [] as unknown[], tags: [] as string[] });
+
+const dungeon = {
+  rooms: [room(0), room(1), room(2)],
+  edges: [
+    [0, 1],
+    [1, 2],
+  ],
+  core_room_id: 2,
+  core_hp: 15,
+};
+
+const wave = {
+  id: 'stepped-wave',
+  entries: [{ hero_template_id: 'scout', count: 3, spawn_room_id: 0, delay_ticks: 2 }],
+  modifiers: [] as unknown[],
+};
+
+const plain = (value: unknown) => JSON.parse(JSON.stringify(value));
+
+test('stepping matches simulate_wave and streams every event once', () => {
+  const expected = plain(simulate_wave_wasm(dungeon, wave, 5n, 60, undefined));
+  const simulation = new Simulation(dungeon, wave, 5n, 60, undefined);
+
+  const events: unknown[] = [];
+  let cursor = 0;
+  while (!simulation.is_finished()) {
+    const before = simulation.state_view().tick;
+    const ran = simulation.step(2);
+    const view = simulation.state_view();
+    assert.equal(view.tick, before + ran);
+    events.push(...simulation.events_since(cursor));
+    cursor = view.event_count;
+    assert.equal(events.length, cursor);
+  }
+
+  assert.equal(simulation.step(10), 0);
+  assert.deepStrictEqual(plain(simulation.state_view().outcome), expected.outcome);
+  assert.deepStrictEqual(plain(events), expected.events);
+  assert.deepStrictEqual(plain(simulation.finish()), expected);
+});
+
+test('the constructor rejects invalid input like simulate_wave', () => {
+  const badWave = { ...wave, entries: [{ ...wave.entries[0], spawn_room_id: 9 }] };
+  assert.throws(() => new Simulation(dungeon, badWave, 1n, 60, undefined));
+});